@@ -1,5 +1,7 @@
 use crate::{script::Script, transaction::Transaction};
-use bsv::{Interpreter as BSVInterpreter, State as BSVState};
+use std::sync::{Arc, Mutex};
+
+use bsv::{Breakpoint as BSVBreakpoint, Interpreter as BSVInterpreter, InterpreterSnapshot as BSVInterpreterSnapshot, ScriptConfig as BSVScriptConfig, State as BSVState, TraceRecorder};
 use wasm_bindgen::{prelude::*, JsError};
 
 #[wasm_bindgen]
@@ -23,17 +25,42 @@ impl Interpreter {
         Ok(Interpreter(BSVInterpreter::from_transaction(&tx.0, txin_idx)?))
     }
 
+    /// Accepts a partial ScriptConfig object, missing fields use post Genesis consensus defaults
+    pub fn from_transaction_with_config(tx: Transaction, txin_idx: usize, config: JsValue) -> Result<Interpreter, JsError> {
+        let config: BSVScriptConfig = serde_wasm_bindgen::from_value(config)?;
+        Ok(Interpreter(BSVInterpreter::from_transaction_with_config(&tx.0, txin_idx, &config)?))
+    }
+
     pub fn from_script(script: Script) -> Interpreter {
         Interpreter(BSVInterpreter::from_script(&script.0))
     }
 
+    pub fn from_script_with_config(script: Script, config: JsValue) -> Result<Interpreter, JsError> {
+        let config: BSVScriptConfig = serde_wasm_bindgen::from_value(config)?;
+        Ok(Interpreter(BSVInterpreter::from_script_with_config(&script.0, &config)))
+    }
+
+    /// Errors are reported as `<node error code> at script index <index>: <reason>`
     pub fn run(&mut self) -> Result<(), JsError> {
-        Ok(self.0.run()?)
+        self.0.run().map_err(|e| JsError::new(&format!("{} at script index {}: {}", e.script_error(), self.0.script_index(), e)))
     }
 
+    /// Runs the script to completion and returns the recorded execution trace, including the failing step if any
+    pub fn run_with_trace(&mut self) -> Result<JsValue, JsError> {
+        let recorder = Arc::new(Mutex::new(TraceRecorder::new()));
+        self.0.add_observer(recorder.clone());
+        // Errors are recorded in the trace
+        let _ = self.0.run();
+        self.0.remove_observer(&recorder);
+
+        let events = recorder.lock().map_err(|e| JsError::new(&e.to_string()))?.events();
+        Ok(serde_wasm_bindgen::to_value(&events)?)
+    }
+
+    /// Executes the next script bit, recording it so it can be undone with step_back
     #[wasm_bindgen(js_name = "next")]
     pub fn step(&mut self) -> Result<Option<State>, JsError> {
-        let state = match self.0.next() {
+        let state = match self.0.step() {
             Some(v) => v?,
             None => return Ok(None),
         };
@@ -42,6 +69,66 @@ impl Interpreter {
         Ok(Some(js_state))
     }
 
+    pub fn step_back(&mut self) -> bool {
+        self.0.step_back()
+    }
+
+    /// Sets how many steps step_back can undo, defaults to 1000. A limit of 0 stops recording them
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.0.set_history_limit(limit)
+    }
+
+    pub fn step_over(&mut self) -> Result<Option<State>, JsError> {
+        let state = match self.0.step_over() {
+            Some(v) => v?,
+            None => return Ok(None),
+        };
+
+        Ok(Some(State(state)))
+    }
+
+    pub fn snapshot(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.0.snapshot())?)
+    }
+
+    pub fn restore(&mut self, snapshot: JsValue) -> Result<(), JsError> {
+        let snapshot: BSVInterpreterSnapshot = serde_wasm_bindgen::from_value(snapshot)?;
+        self.0.restore(&snapshot);
+        Ok(())
+    }
+
+    /// Accepts a breakpoint object, either { ScriptIndex: number } or { OpCode: "OP_CHECKSIG" }
+    pub fn add_breakpoint(&mut self, breakpoint: JsValue) -> Result<(), JsError> {
+        let breakpoint: BSVBreakpoint = serde_wasm_bindgen::from_value(breakpoint)?;
+        self.0.add_breakpoint(&breakpoint);
+        Ok(())
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: JsValue) -> Result<(), JsError> {
+        let breakpoint: BSVBreakpoint = serde_wasm_bindgen::from_value(breakpoint)?;
+        self.0.remove_breakpoint(&breakpoint);
+        Ok(())
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.0.clear_breakpoints()
+    }
+
+    /// Runs until the given breakpoint is reached, returns the breakpoint or undefined if the script finished first
+    pub fn run_until(&mut self, breakpoint: JsValue) -> Result<JsValue, JsError> {
+        let breakpoint: BSVBreakpoint = serde_wasm_bindgen::from_value(breakpoint)?;
+        Ok(serde_wasm_bindgen::to_value(&self.0.run_until(&breakpoint)?)?)
+    }
+
+    /// Runs until any added breakpoint is reached, returns the breakpoint or undefined if the script finished first
+    pub fn resume(&mut self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.0.resume()?)?)
+    }
+
+    pub fn get_script_index(&self) -> usize {
+        self.0.script_index()
+    }
+
     pub fn get_state(&self) -> State {
         State(self.0.state())
     }
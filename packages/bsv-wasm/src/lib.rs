@@ -43,5 +43,8 @@ pub use sighash::*;
 mod signature;
 pub use signature::*;
 
+mod spv;
+pub use spv::*;
+
 mod transaction;
 pub use transaction::*;
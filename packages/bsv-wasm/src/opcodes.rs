@@ -164,9 +164,9 @@ pub enum JsOpCodes {
     OP_DIV = 150,
     /// Returns the remainder after dividing a by b
     OP_MOD = 151,
-    /// Shifts a left b bits, preserving sign
+    /// Shifts the bits of byte string a left by b, keeping its length
     OP_LSHIFT = 152,
-    /// Shifts a right b bits, preserving sign
+    /// Shifts the bits of byte string a right by b, keeping its length
     OP_RSHIFT = 153,
     /// If both a and b are not empty, the output is 1. Otherwise, 0.
     OP_BOOLAND = 154,
@@ -248,11 +248,11 @@ pub enum JsOpCodes {
     // --------------------------------------------------------------------------------------------
     /// Transaction is invalid unless occuring in an unexecuted OP_IF branch
     OP_RESERVED = 80,
-    /// Transaction is invalid unless occuring in an unexecuted OP_IF branch
+    /// Pushes the transaction version after Chronicle, otherwise invalid unless occuring in an unexecuted OP_IF branch
     OP_VER = 98,
-    /// Transaction is invalid even when occuring in an unexecuted OP_IF branch
+    /// Executes the statements if the top stack item equals the transaction version after Chronicle, otherwise always invalid
     OP_VERIF = 101,
-    /// Transaction is invalid even when occuring in an unexecuted OP_IF branch
+    /// Executes the statements if the top stack item does not equal the transaction version after Chronicle, otherwise always invalid
     OP_VERNOTIF = 102,
     /// Transaction is invalid unless occuring in an unexecuted OP_IF branch
     OP_RESERVED1 = 137,
@@ -260,15 +260,15 @@ pub enum JsOpCodes {
     OP_RESERVED2 = 138,
     /// The word is ignored. Does not mark transaction as invalid.
     OP_NOP1 = 176,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_SUBSTR after
     OP_NOP4 = 179,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_LEFT after
     OP_NOP5 = 180,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_RIGHT after
     OP_NOP6 = 181,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_LSHIFTNUM after
     OP_NOP7 = 182,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_RSHIFTNUM after
     OP_NOP8 = 183,
     /// The word is ignored. Does not mark transaction as invalid.
     OP_NOP9 = 184,
@@ -279,7 +279,7 @@ pub enum JsOpCodes {
     OP_INVALID_ABOVE = 186,
 
     // --------------------------------------------------------------------------------------------
-    // Disabled words
+    // Disabled words, restored by Chronicle
     // --------------------------------------------------------------------------------------------
     /// The input is multiplied by 2
     OP_2MUL = 141,
@@ -1,9 +1,30 @@
-use bsv::Script as BSVScript;
+use crate::{sighash::SighashSignature, JsOpCodes, PublicKey};
+use bsv::{Script as BSVScript, ScriptBuilder as BSVScriptBuilder};
+use num_traits::FromPrimitive;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 pub struct Script(pub(crate) BSVScript);
 
+#[wasm_bindgen]
+pub enum AsmDialect {
+    Default,
+    Bitcoind,
+    BsvJs,
+    Scrypt,
+}
+
+impl From<AsmDialect> for bsv::AsmDialect {
+    fn from(v: AsmDialect) -> bsv::AsmDialect {
+        match v {
+            AsmDialect::Default => bsv::AsmDialect::Default,
+            AsmDialect::Bitcoind => bsv::AsmDialect::Bitcoind,
+            AsmDialect::BsvJs => bsv::AsmDialect::BsvJs,
+            AsmDialect::Scrypt => bsv::AsmDialect::Scrypt,
+        }
+    }
+}
+
 impl From<BSVScript> for Script {
     fn from(v: BSVScript) -> Script {
         Script(v)
@@ -29,6 +50,10 @@ impl Script {
         BSVScript::to_extended_asm_string(&self.0)
     }
 
+    pub fn to_asm_string_with_dialect(&self, dialect: AsmDialect) -> String {
+        BSVScript::to_asm_string_with_dialect(&self.0, dialect.into())
+    }
+
     pub fn from_hex(hex: &str) -> Result<Script, wasm_bindgen::JsError> {
         Ok(Script(BSVScript::from_hex(hex)?))
     }
@@ -41,6 +66,10 @@ impl Script {
         Ok(Script(BSVScript::from_asm_string(asm_string)?))
     }
 
+    pub fn from_asm_string_with_dialect(asm_string: &str, dialect: AsmDialect) -> Result<Script, wasm_bindgen::JsError> {
+        Ok(Script(BSVScript::from_asm_string_with_dialect(asm_string, dialect.into())?))
+    }
+
     pub fn encode_pushdata(data_bytes: &[u8]) -> Result<Vec<u8>, wasm_bindgen::JsError> {
         Ok(BSVScript::encode_pushdata(data_bytes)?)
     }
@@ -72,3 +101,62 @@ impl Script {
         BSVScript::remove_codeseparators(&mut self.0)
     }
 }
+
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct ScriptBuilder(pub(crate) BSVScriptBuilder);
+
+/**
+ * Builder methods take the builder and return it, so calls can be chained
+ */
+#[wasm_bindgen]
+impl ScriptBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ScriptBuilder {
+        ScriptBuilder(BSVScriptBuilder::new())
+    }
+
+    pub fn push_opcode(self, code: JsOpCodes) -> Result<ScriptBuilder, wasm_bindgen::JsError> {
+        let code = bsv::OpCodes::from_u8(code as u8).ok_or_else(|| wasm_bindgen::JsError::new("Unknown OpCode"))?;
+        Ok(ScriptBuilder(self.0.push_opcode(code)))
+    }
+
+    pub fn push_data(self, data: &[u8]) -> ScriptBuilder {
+        ScriptBuilder(self.0.push_data(data))
+    }
+
+    pub fn push_int(self, number: i64) -> ScriptBuilder {
+        ScriptBuilder(self.0.push_int(number))
+    }
+
+    pub fn push_bool(self, value: bool) -> ScriptBuilder {
+        ScriptBuilder(self.0.push_bool(value))
+    }
+
+    pub fn push_pubkey(self, pub_key: &PublicKey) -> Result<ScriptBuilder, wasm_bindgen::JsError> {
+        Ok(ScriptBuilder(self.0.push_pubkey(&pub_key.0)?))
+    }
+
+    pub fn push_sig(self, sig: &SighashSignature) -> Result<ScriptBuilder, wasm_bindgen::JsError> {
+        Ok(ScriptBuilder(self.0.push_sig(&sig.0)?))
+    }
+
+    /**
+     * Adds an OP_IF block with the bits of the given builders as its branches
+     */
+    pub fn if_else(self, pass: &ScriptBuilder, fail: &ScriptBuilder) -> ScriptBuilder {
+        ScriptBuilder(self.0.if_else(|_| pass.0.clone(), |_| fail.0.clone()))
+    }
+
+    pub fn if_then(self, pass: &ScriptBuilder) -> ScriptBuilder {
+        ScriptBuilder(self.0.if_then(|_| pass.0.clone()))
+    }
+
+    pub fn push_script(self, script: &Script) -> ScriptBuilder {
+        ScriptBuilder(self.0.push_script(&script.0))
+    }
+
+    pub fn build(self) -> Script {
+        Script(self.0.build())
+    }
+}
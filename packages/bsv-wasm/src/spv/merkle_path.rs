@@ -0,0 +1,64 @@
+use bsv::MerklePath as BSVMerklePath;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct MerklePath(pub(crate) BSVMerklePath);
+
+impl From<BSVMerklePath> for MerklePath {
+    fn from(v: BSVMerklePath) -> MerklePath {
+        MerklePath(v)
+    }
+}
+
+impl From<MerklePath> for BSVMerklePath {
+    fn from(v: MerklePath) -> BSVMerklePath {
+        v.0
+    }
+}
+
+#[wasm_bindgen]
+impl MerklePath {
+    pub fn from_bytes(bytes: &[u8]) -> Result<MerklePath, wasm_bindgen::JsError> {
+        Ok(MerklePath(BSVMerklePath::from_bytes(bytes)?))
+    }
+
+    pub fn from_hex(hex_str: &str) -> Result<MerklePath, wasm_bindgen::JsError> {
+        Ok(MerklePath(BSVMerklePath::from_hex(hex_str)?))
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, wasm_bindgen::JsError> {
+        Ok(self.0.to_bytes()?)
+    }
+
+    pub fn to_hex(&self) -> Result<String, wasm_bindgen::JsError> {
+        Ok(self.0.to_hex()?)
+    }
+
+    pub fn to_json(&self) -> Result<JsValue, wasm_bindgen::JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.0)?)
+    }
+
+    pub fn get_block_height(&self) -> u64 {
+        self.0.block_height
+    }
+
+    pub fn compute_root(&self, txid: &[u8]) -> Result<Vec<u8>, wasm_bindgen::JsError> {
+        Ok(self.0.compute_root(txid)?)
+    }
+
+    pub fn compute_root_hex(&self, txid_hex: &str) -> Result<String, wasm_bindgen::JsError> {
+        Ok(self.0.compute_root_hex(txid_hex)?)
+    }
+
+    pub fn combine(&self, other: &MerklePath) -> Result<MerklePath, wasm_bindgen::JsError> {
+        Ok(MerklePath(self.0.combine(&other.0)?))
+    }
+
+    /**
+     * Extracts the smallest path proving the transactions, given as an array of txid byte arrays.
+     */
+    pub fn extract(&self, txids: JsValue) -> Result<MerklePath, wasm_bindgen::JsError> {
+        let txids = serde_wasm_bindgen::from_value::<Vec<Vec<u8>>>(txids)?;
+        Ok(MerklePath(self.0.extract(&txids)?))
+    }
+}
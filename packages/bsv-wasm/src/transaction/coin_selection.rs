@@ -0,0 +1,101 @@
+use bsv::{CoinSelection as BSVCoinSelection, CoinSelectionStrategy as BSVCoinSelectionStrategy, CoinSelector as BSVCoinSelector};
+use wasm_bindgen::prelude::*;
+
+use crate::{script::Script, transaction::FeeModel, transaction::Utxo, TxOut};
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum CoinSelectionStrategy {
+    /**
+     * Spends the largest UTXOs first, using as few inputs as possible.
+     */
+    LargestFirst,
+    /**
+     * Spends the smallest UTXOs first, consolidating small UTXOs.
+     */
+    SmallestFirst,
+    /**
+     * Searches for a set of UTXOs that pays the outputs and fee without a change output.
+     */
+    BranchAndBound,
+    /**
+     * Picks random UTXOs, leaving change of similar size to the payments.
+     */
+    RandomImprove,
+}
+
+impl From<CoinSelectionStrategy> for BSVCoinSelectionStrategy {
+    fn from(v: CoinSelectionStrategy) -> BSVCoinSelectionStrategy {
+        match v {
+            CoinSelectionStrategy::LargestFirst => BSVCoinSelectionStrategy::LargestFirst,
+            CoinSelectionStrategy::SmallestFirst => BSVCoinSelectionStrategy::SmallestFirst,
+            CoinSelectionStrategy::BranchAndBound => BSVCoinSelectionStrategy::BranchAndBound,
+            CoinSelectionStrategy::RandomImprove => BSVCoinSelectionStrategy::RandomImprove,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct CoinSelection(pub(crate) BSVCoinSelection);
+
+impl From<BSVCoinSelection> for CoinSelection {
+    fn from(v: BSVCoinSelection) -> CoinSelection {
+        CoinSelection(v)
+    }
+}
+
+impl From<CoinSelection> for BSVCoinSelection {
+    fn from(v: CoinSelection) -> BSVCoinSelection {
+        v.0
+    }
+}
+
+#[wasm_bindgen]
+impl CoinSelection {
+    pub fn get_utxos(&self) -> Vec<Utxo> {
+        self.0.utxos.iter().cloned().map(Utxo).collect()
+    }
+
+    pub fn get_fee(&self) -> u64 {
+        self.0.fee
+    }
+
+    /**
+     * Satoshis for the change output, undefined if the transaction has no change
+     */
+    pub fn get_change(&self) -> Option<u64> {
+        self.0.change
+    }
+
+    pub fn get_satoshis(&self) -> u64 {
+        self.0.satoshis()
+    }
+}
+
+#[wasm_bindgen]
+pub struct CoinSelector(pub(crate) BSVCoinSelector);
+
+#[wasm_bindgen]
+impl CoinSelector {
+    /**
+     * Creates a selector for P2PKH UTXOs with P2PKH change.
+     */
+    #[wasm_bindgen(constructor)]
+    pub fn new(fee_model: &FeeModel) -> CoinSelector {
+        CoinSelector(BSVCoinSelector::new(&fee_model.0))
+    }
+
+    pub fn set_dust_limit(self, dust_limit: u64) -> CoinSelector {
+        CoinSelector(self.0.set_dust_limit(dust_limit))
+    }
+
+    pub fn set_change_script(self, change_script: &Script) -> CoinSelector {
+        CoinSelector(self.0.set_change_script(&change_script.0))
+    }
+
+    pub fn select(&self, candidates: Vec<Utxo>, outputs: Vec<TxOut>, strategy: CoinSelectionStrategy) -> Result<CoinSelection, wasm_bindgen::JsError> {
+        let candidates = candidates.into_iter().map(|x| x.0).collect::<Vec<_>>();
+        let outputs = outputs.into_iter().map(|x| x.0).collect::<Vec<_>>();
+        Ok(CoinSelection(self.0.select(&candidates, &outputs, strategy.into())?))
+    }
+}
@@ -0,0 +1,59 @@
+use bsv::{FeeModel as BSVFeeModel, SizeEstimator};
+use wasm_bindgen::prelude::*;
+
+use crate::transaction::Transaction;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct FeeModel(pub(crate) BSVFeeModel);
+
+impl From<BSVFeeModel> for FeeModel {
+    fn from(v: BSVFeeModel) -> FeeModel {
+        FeeModel(v)
+    }
+}
+
+impl From<FeeModel> for BSVFeeModel {
+    fn from(v: FeeModel) -> BSVFeeModel {
+        v.0
+    }
+}
+
+#[wasm_bindgen]
+impl FeeModel {
+    #[wasm_bindgen(constructor)]
+    pub fn new(standard_rate: u64, data_rate: u64) -> FeeModel {
+        FeeModel(BSVFeeModel::new(standard_rate, data_rate))
+    }
+
+    pub fn flat(rate: u64) -> FeeModel {
+        FeeModel(BSVFeeModel::flat(rate))
+    }
+
+    pub fn get_standard_rate(&self) -> u64 {
+        self.0.standard_rate
+    }
+
+    pub fn get_data_rate(&self) -> u64 {
+        self.0.data_rate
+    }
+
+    pub fn fee(&self, tx: &Transaction) -> Result<u64, wasm_bindgen::JsError> {
+        Ok(self.0.fee(&tx.0)?)
+    }
+
+    /**
+     * Estimates the fee once unsigned P2PKH inputs are signed, their locking scripts must be set.
+     */
+    pub fn estimate_fee(&self, tx: &Transaction) -> Result<u64, wasm_bindgen::JsError> {
+        Ok(self.0.estimate_fee(&tx.0, &SizeEstimator::new())?)
+    }
+
+    pub fn estimate_size(tx: &Transaction) -> Result<usize, wasm_bindgen::JsError> {
+        Ok(SizeEstimator::new().estimate_size(&tx.0)?)
+    }
+
+    pub fn is_paid_by(&self, tx: &Transaction) -> Result<bool, wasm_bindgen::JsError> {
+        Ok(self.0.is_paid_by(&tx.0)?)
+    }
+}
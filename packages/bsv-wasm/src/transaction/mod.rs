@@ -1,8 +1,14 @@
 use bsv::Transaction as BSVTransaction;
 use wasm_bindgen::prelude::*;
 
+mod coin_selection;
+mod fee_model;
+mod transaction_builder;
 mod txin;
 mod txout;
+pub use coin_selection::*;
+pub use fee_model::*;
+pub use transaction_builder::*;
 pub use txin::*;
 pub use txout::*;
 
@@ -242,6 +248,25 @@ impl Transaction {
         Ok(Transaction(BSVTransaction::from_compact_hex(&compact_hex)?))
     }
 
+    /**
+     * Serialises to the Extended Format (BRC-30), every input needs its satoshis and locking script set.
+     */
+    pub fn to_extended_bytes(&self) -> Result<Vec<u8>, wasm_bindgen::JsError> {
+        Ok(self.0.to_extended_bytes()?)
+    }
+
+    pub fn to_extended_hex(&self) -> Result<String, wasm_bindgen::JsError> {
+        Ok(self.0.to_extended_hex()?)
+    }
+
+    pub fn from_extended_bytes(tx_bytes: &[u8]) -> Result<Transaction, wasm_bindgen::JsError> {
+        Ok(Transaction(BSVTransaction::from_extended_bytes(tx_bytes)?))
+    }
+
+    pub fn from_extended_hex(hex_str: &str) -> Result<Transaction, wasm_bindgen::JsError> {
+        Ok(Transaction(BSVTransaction::from_extended_hex(hex_str)?))
+    }
+
     pub fn is_coinbase(&self) -> bool {
         self.0.is_coinbase()
     }
@@ -0,0 +1,115 @@
+use bsv::{TransactionBuilder as BSVTransactionBuilder, Utxo as BSVUtxo};
+use wasm_bindgen::prelude::*;
+
+use crate::{address::P2PKHAddress, keypair::private_key::PrivateKey, script::Script, sighash::SigHash, transaction::FeeModel, transaction::Transaction, TxOut};
+
+#[wasm_bindgen]
+pub struct Utxo(pub(crate) BSVUtxo);
+
+impl From<BSVUtxo> for Utxo {
+    fn from(v: BSVUtxo) -> Utxo {
+        Utxo(v)
+    }
+}
+
+impl From<Utxo> for BSVUtxo {
+    fn from(v: Utxo) -> BSVUtxo {
+        v.0
+    }
+}
+
+#[wasm_bindgen]
+impl Utxo {
+    #[wasm_bindgen(constructor)]
+    pub fn new(tx_id: &[u8], vout: u32, locking_script: &Script, satoshis: u64) -> Utxo {
+        Utxo(BSVUtxo::new(tx_id, vout, &locking_script.0, satoshis))
+    }
+
+    pub fn get_tx_id(&self) -> Vec<u8> {
+        self.0.tx_id.clone()
+    }
+
+    pub fn get_vout(&self) -> u32 {
+        self.0.vout
+    }
+
+    pub fn get_locking_script(&self) -> Script {
+        Script(self.0.locking_script.clone())
+    }
+
+    pub fn get_satoshis(&self) -> u64 {
+        self.0.satoshis
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct TransactionBuilder(pub(crate) BSVTransactionBuilder);
+
+#[wasm_bindgen]
+impl TransactionBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> TransactionBuilder {
+        TransactionBuilder(BSVTransactionBuilder::new())
+    }
+
+    pub fn set_version(self, version: u32) -> TransactionBuilder {
+        TransactionBuilder(self.0.set_version(version))
+    }
+
+    pub fn set_nlocktime(self, n_locktime: u32) -> TransactionBuilder {
+        TransactionBuilder(self.0.set_nlocktime(n_locktime))
+    }
+
+    pub fn add_input(self, utxo: &Utxo, private_key: &PrivateKey) -> TransactionBuilder {
+        TransactionBuilder(self.0.add_input(&utxo.0, &private_key.0))
+    }
+
+    pub fn add_output(self, output: &TxOut) -> TransactionBuilder {
+        TransactionBuilder(self.0.add_output(&output.0))
+    }
+
+    pub fn pay_to_address(self, address: &P2PKHAddress, satoshis: u64) -> Result<TransactionBuilder, wasm_bindgen::JsError> {
+        Ok(TransactionBuilder(self.0.pay_to_address(&address.0, satoshis)?))
+    }
+
+    pub fn set_change_script(self, script: &Script) -> TransactionBuilder {
+        TransactionBuilder(self.0.set_change_script(&script.0))
+    }
+
+    pub fn set_change_address(self, address: &P2PKHAddress) -> Result<TransactionBuilder, wasm_bindgen::JsError> {
+        Ok(TransactionBuilder(self.0.set_change_address(&address.0)?))
+    }
+
+    pub fn set_fee_rate(self, satoshis_per_kb: u64) -> TransactionBuilder {
+        TransactionBuilder(self.0.set_fee_rate(satoshis_per_kb))
+    }
+
+    pub fn set_fee_model(self, fee_model: &FeeModel) -> TransactionBuilder {
+        TransactionBuilder(self.0.set_fee_model(&fee_model.0))
+    }
+
+    pub fn set_dust_limit(self, dust_limit: u64) -> TransactionBuilder {
+        TransactionBuilder(self.0.set_dust_limit(dust_limit))
+    }
+
+    pub fn set_sighash(self, sighash: SigHash) -> TransactionBuilder {
+        TransactionBuilder(self.0.set_sighash(sighash.into()))
+    }
+
+    pub fn estimate_size(&self) -> Result<usize, wasm_bindgen::JsError> {
+        Ok(self.0.estimate_size()?)
+    }
+
+    pub fn estimate_fee(&self) -> Result<u64, wasm_bindgen::JsError> {
+        Ok(self.0.estimate_fee()?)
+    }
+
+    pub fn change(&self) -> Result<Option<u64>, wasm_bindgen::JsError> {
+        Ok(self.0.change()?)
+    }
+
+    pub fn build(&self) -> Result<Transaction, wasm_bindgen::JsError> {
+        Ok(Transaction(self.0.build()?))
+    }
+}
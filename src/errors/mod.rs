@@ -124,6 +124,12 @@ pub enum BSVErrors {
     #[error("{0}")]
     OutOfBounds(String),
 
+    #[error("Insufficient funds, {0} satoshis available but {1} required")]
+    InsufficientFunds(u64, u64),
+
+    #[error("Unable to select coins: {0}")]
+    CoinSelection(String),
+
     #[error("{0}")]
     ECIESError(String),
 
@@ -155,6 +161,15 @@ pub enum BSVErrors {
     #[error("Error serialising TxIn field {0}: {1}")]
     SerialiseTxIn(String, #[source] std::io::Error),
 
+    #[error("Error serialising TxIn to the extended format, {0} is not set")]
+    SerialiseExtendedTxIn(String),
+
+    #[error("Error deserialising MerklePath field {0}: {1}")]
+    DeserialiseMerklePath(String, #[source] std::io::Error),
+
+    #[error("Invalid merkle path: {0}")]
+    MerklePath(String),
+
     #[error("Error deserialising TxOut field {0}: {1}")]
     DeserialiseTxOut(String, #[source] std::io::Error),
 
@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+
+use crate::{OpCodes, ScriptBit};
+use serde::{Deserialize, Serialize};
+
+use super::{errors::InterpreterError, state::State, Interpreter};
+
+/// Point in a script where `resume` and `run_until` stop, before the script bit is executed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Breakpoint {
+    ScriptIndex(usize),
+    OpCode(OpCodes),
+}
+
+/// Everything needed to restore an Interpreter to an earlier point of execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterpreterSnapshot {
+    pub script_index: usize,
+    pub state: State,
+}
+
+/// Default number of steps `step_back` can undo
+pub const DEFAULT_HISTORY_LIMIT: usize = 1_000;
+
+/// Snapshots recorded by `step`, the oldest are dropped once there are more than `limit`.
+#[derive(Debug, Clone)]
+pub(crate) struct History {
+    snapshots: VecDeque<InterpreterSnapshot>,
+    limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History {
+            snapshots: VecDeque::new(),
+            limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+}
+
+impl History {
+    fn push(&mut self, snapshot: InterpreterSnapshot) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.snapshots.len() == self.limit {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        while self.snapshots.len() > limit {
+            self.snapshots.pop_front();
+        }
+    }
+}
+
+impl Interpreter {
+    fn is_at_breakpoint(&self, breakpoint: &Breakpoint) -> bool {
+        match breakpoint {
+            Breakpoint::ScriptIndex(index) => self.script_index == *index,
+            Breakpoint::OpCode(code) => match self.script_bits.get(self.script_index) {
+                Some(ScriptBit::OpCode(v)) => v == code,
+                Some(ScriptBit::PushData(v, _)) => v == code,
+                Some(ScriptBit::Push(_)) => *code == OpCodes::OP_DATA,
+                _ => false,
+            },
+        }
+    }
+
+    /// Steps until one of the breakpoints is reached or the script finishes, always executing at least one step.
+    fn run_until_any(&mut self, breakpoints: &[Breakpoint]) -> Result<Option<Breakpoint>, InterpreterError> {
+        while let Some(state) = self.step() {
+            state?;
+
+            if let Some(breakpoint) = breakpoints.iter().find(|x| self.is_at_breakpoint(x)) {
+                return Ok(Some(breakpoint.clone()));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl Interpreter {
+    pub fn snapshot(&self) -> InterpreterSnapshot {
+        InterpreterSnapshot {
+            script_index: self.script_index,
+            state: self.state.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &InterpreterSnapshot) {
+        self.script_index = snapshot.script_index;
+        self.state = snapshot.state.clone();
+    }
+
+    /// Executes the next script bit like `next`, recording a snapshot so it can be undone with `step_back`.
+    pub fn step(&mut self) -> Option<Result<State, InterpreterError>> {
+        let snapshot = self.snapshot();
+        let result = self.next_impl();
+        // Reaching the end of the script only changes the status
+        if result.is_some() || snapshot.state.status != self.state.status {
+            self.history.push(snapshot);
+        }
+        result
+    }
+
+    /// Undoes the last `step`, returns false if there is nothing to undo.
+    pub fn step_back(&mut self) -> bool {
+        match self.history.snapshots.pop_back() {
+            Some(snapshot) => {
+                self.restore(&snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Steps over a whole OP_IF/OP_NOTIF block, or a single script bit otherwise.
+    pub fn step_over(&mut self) -> Option<Result<State, InterpreterError>> {
+        let depth = self.state.vf_exec.len();
+
+        let mut result = self.step();
+        while let Some(Ok(_)) = result {
+            if self.state.vf_exec.len() <= depth {
+                break;
+            }
+            result = self.step();
+        }
+
+        result
+    }
+
+    /// Runs until the given breakpoint is reached, returns None if the script finished first.
+    pub fn run_until(&mut self, breakpoint: &Breakpoint) -> Result<Option<Breakpoint>, InterpreterError> {
+        self.run_until_any(std::slice::from_ref(breakpoint))
+    }
+
+    /// Runs until any of the attached breakpoints is reached, returns None if the script finished first.
+    pub fn resume(&mut self) -> Result<Option<Breakpoint>, InterpreterError> {
+        let breakpoints = self.breakpoints.clone();
+        self.run_until_any(&breakpoints)
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: &Breakpoint) {
+        if !self.breakpoints.contains(breakpoint) {
+            self.breakpoints.push(breakpoint.clone());
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) {
+        self.breakpoints.retain(|x| x != breakpoint);
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Get the attached breakpoints.
+    #[must_use]
+    pub fn breakpoints(&self) -> Vec<Breakpoint> {
+        self.breakpoints.clone()
+    }
+
+    /// Get the snapshots recorded by `step`, oldest first.
+    #[must_use]
+    pub fn history(&self) -> Vec<InterpreterSnapshot> {
+        self.history.snapshots.iter().cloned().collect()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.snapshots.clear();
+    }
+
+    /// Sets how many steps `step_back` can undo, defaults to DEFAULT_HISTORY_LIMIT. A limit of 0 stops recording snapshots.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
+    }
+}
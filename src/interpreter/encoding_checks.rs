@@ -0,0 +1,124 @@
+use crate::{OpCodes, ScriptBit};
+use k256::ecdsa::Signature as SecpSignature;
+
+use super::{errors::InterpreterError, script_config::VerifyFlags};
+
+const SIGHASH_ANYONECANPAY: u8 = 0x80;
+const SIGHASH_FORKID: u8 = 0x40;
+
+/// Checks a signature against the encoding rules enabled in the flags. Empty signatures are always allowed.
+pub(crate) fn check_signature_encoding(sig: &[u8], flags: &VerifyFlags) -> Result<(), InterpreterError> {
+    if sig.is_empty() {
+        return Ok(());
+    }
+
+    if (flags.strict_enc || flags.low_s) && !is_valid_signature_encoding(sig) {
+        return Err(InterpreterError::SigDer);
+    }
+
+    if flags.low_s && !is_low_der_signature(sig) {
+        return Err(InterpreterError::SigHighS);
+    }
+
+    if flags.strict_enc && !is_defined_hashtype_signature(sig) {
+        return Err(InterpreterError::SigHashType);
+    }
+
+    Ok(())
+}
+
+/// Checks the signature is strictly DER encoded and ends with a defined sighash type, as STRICTENC requires.
+pub(crate) fn is_strict_signature_encoding(sig: &[u8]) -> bool {
+    is_valid_signature_encoding(sig) && is_defined_hashtype_signature(sig)
+}
+
+/// Checks a public key is either compressed or uncompressed when STRICTENC is enabled.
+pub(crate) fn check_pubkey_encoding(pubkey: &[u8], flags: &VerifyFlags) -> Result<(), InterpreterError> {
+    if flags.strict_enc && !is_compressed_or_uncompressed_pubkey(pubkey) {
+        return Err(InterpreterError::PubKeyType);
+    }
+
+    Ok(())
+}
+
+/// Checks the data was pushed with the smallest possible push operation.
+pub(crate) fn is_minimal_push(bit: &ScriptBit) -> bool {
+    match bit {
+        ScriptBit::Push(data) => match data.len() {
+            // Should have used OP_0
+            0 => false,
+            // Should have used OP_1 - OP_16 or OP_1NEGATE
+            1 => !matches!(data[0], 1..=16 | 0x81),
+            len => len <= 75,
+        },
+        ScriptBit::PushData(code, data) => match code {
+            OpCodes::OP_PUSHDATA1 => data.len() > 75 && data.len() <= 0xff,
+            OpCodes::OP_PUSHDATA2 => data.len() > 0xff && data.len() <= 0xffff,
+            _ => data.len() > 0xffff,
+        },
+        _ => true,
+    }
+}
+
+/// Strict DER encoding check, including the trailing sighash byte.
+/// Format: 0x30 [total-length] 0x02 [R-length] [R] 0x02 [S-length] [S] [sighash]
+fn is_valid_signature_encoding(sig: &[u8]) -> bool {
+    if sig.len() < 9 || sig.len() > 73 {
+        return false;
+    }
+
+    // A signature is of type 0x30 (compound), the length covers the entire signature except the sighash byte
+    if sig[0] != 0x30 || sig[1] as usize != sig.len() - 3 {
+        return false;
+    }
+
+    // Make sure the length of the S element is still inside the signature
+    let len_r = sig[3] as usize;
+    if 5 + len_r >= sig.len() {
+        return false;
+    }
+
+    // Verify that the length of the signature matches the sum of the length of the elements
+    let len_s = sig[5 + len_r] as usize;
+    if len_r + len_s + 7 != sig.len() {
+        return false;
+    }
+
+    // R must be a positive integer without excess padding
+    if sig[2] != 0x02 || len_r == 0 || sig[4] & 0x80 != 0 {
+        return false;
+    }
+    if len_r > 1 && sig[4] == 0x00 && sig[5] & 0x80 == 0 {
+        return false;
+    }
+
+    // S must be a positive integer without excess padding
+    if sig[len_r + 4] != 0x02 || len_s == 0 || sig[len_r + 6] & 0x80 != 0 {
+        return false;
+    }
+    if len_s > 1 && sig[len_r + 6] == 0x00 && sig[len_r + 7] & 0x80 == 0 {
+        return false;
+    }
+
+    true
+}
+
+fn is_low_der_signature(sig: &[u8]) -> bool {
+    match SecpSignature::from_der(&sig[..sig.len() - 1]) {
+        Ok(v) => v.normalize_s().is_none(),
+        Err(_) => false,
+    }
+}
+
+fn is_defined_hashtype_signature(sig: &[u8]) -> bool {
+    let base_type = sig[sig.len() - 1] & !(SIGHASH_ANYONECANPAY | SIGHASH_FORKID);
+    (1..=3).contains(&base_type)
+}
+
+fn is_compressed_or_uncompressed_pubkey(pubkey: &[u8]) -> bool {
+    match pubkey.first() {
+        Some(0x04) => pubkey.len() == 65,
+        Some(0x02 | 0x03) => pubkey.len() == 33,
+        _ => false,
+    }
+}
@@ -1,10 +1,16 @@
+use std::fmt;
+
 use crate::{BSVErrors, OpCodes};
+use serde::{Deserialize, Serialize};
 use thiserror::*;
 
 #[derive(Debug, Error)]
 pub enum InterpreterError {
-    #[error("Number provided is out of range of an i32")]
+    #[error("Number is longer than the maximum number length")]
     NumberOutOfRange,
+    #[error("Number is not minimally encoded")]
+    NonMinimalNumber,
+
     #[error("Stack is empty")]
     EmptyStack,
 
@@ -14,12 +20,30 @@ pub enum InterpreterError {
     #[error("Invalid OpCode: {0}")]
     InvalidOpcode(OpCodes),
 
+    #[error("Unknown OpCode: {0:#04x}")]
+    UnknownOpcode(u8),
+
+    #[error("Script ends with a truncated instruction")]
+    MalformedScript,
+
     #[error("Data on top of stack is too long to be casted to a boolean.")]
     TooLongForBool,
 
     #[error("Item on top of the stack was not true")]
     VerifyFailed,
 
+    #[error("OP_EQUALVERIFY failed, the top two items are not equal")]
+    EqualVerify,
+
+    #[error("OP_NUMEQUALVERIFY failed, the top two numbers are not equal")]
+    NumEqualVerify,
+
+    #[error("OP_CHECKSIGVERIFY failed, the signature is not valid")]
+    CheckSigVerify,
+
+    #[error("OP_CHECKMULTISIGVERIFY failed, the signatures are not valid")]
+    CheckMultisigVerify,
+
     #[error("Provided OpCode is disabled {0}")]
     DisabledOpCode(&'static OpCodes),
 
@@ -29,15 +53,271 @@ pub enum InterpreterError {
     #[error("Failed to convert byte to SigHash flag")]
     FailedToConvertSighash,
 
-    #[error("Stack operation is invalid {0}")]
-    InvalidStackOperation(&'static str),
+    #[error("OP_PICK or OP_ROLL index is out of range")]
+    InvalidStackOperation,
+
+    #[error("Alt stack is empty")]
+    EmptyAltStack,
+
+    #[error("ScriptBit::If must be flattened before execution")]
+    UnflattenedScript,
 
     #[error("The TxIn was not provided for this transaction")]
     NoTxInProvided,
 
+    #[error("The TxIn being spent does not have its locking script provided")]
+    MissingLockingScript,
+
+    #[error("The TxIn being spent does not have its satoshis provided")]
+    MissingSatoshis,
+
     #[error("Could not calculate SigHash preimage {0}")]
     SighashPreimageCalculation(String),
 
+    #[error("Script is larger than the maximum script size")]
+    ScriptSize,
+
+    #[error("Script exceeds the maximum number of operations")]
+    OpCount,
+
+    #[error("Stack exceeds the maximum number of elements")]
+    StackSize,
+
+    #[error("Stack exceeds the maximum memory usage")]
+    StackMemoryUsage,
+
+    #[error("Script exceeds the maximum execution cost")]
+    ExecutionCost,
+
+    #[error("Pushed data exceeds the maximum element size")]
+    PushSize,
+
+    #[error("Data was not pushed with the smallest possible push operation")]
+    MinimalData,
+
+    #[error("Unlocking script is not push only")]
+    SigPushOnly,
+
+    #[error("Stack must contain exactly one item after evaluation")]
+    CleanStack,
+
+    #[error("Signature is not strict DER encoded")]
+    SigDer,
+
+    #[error("Signature S value is not low")]
+    SigHighS,
+
+    #[error("Signature has an undefined sighash type")]
+    SigHashType,
+
+    #[error("Public key is neither compressed nor uncompressed")]
+    PubKeyType,
+
+    #[error("Signature must be empty when the signature check fails")]
+    NullFail,
+
+    #[error("Extra element consumed by OP_CHECKMULTISIG must be empty")]
+    SigNullDummy,
+
+    #[error("Public key count of OP_CHECKMULTISIG is out of range")]
+    PubKeyCount,
+
+    #[error("Signature count of OP_CHECKMULTISIG is out of range")]
+    SigCount,
+
+    #[error("Upgradable NOP {0} is discouraged")]
+    DiscourageUpgradableNops(OpCodes),
+
+    #[error("OP_RETURN was encountered")]
+    OpReturn,
+
+    #[error("Script evaluated without error but finished with a false or empty top stack element")]
+    EvalFalse,
+
+    #[error("OP_IF, OP_NOTIF, OP_ELSE and OP_ENDIF are not balanced")]
+    UnbalancedConditional,
+
+    #[error("Division by zero")]
+    DivByZero,
+
+    #[error("Modulo by zero")]
+    ModByZero,
+
+    #[error("OP_SPLIT position is outside of the data")]
+    SplitRange,
+
+    #[error("Number operand is outside of the range the opcode accepts")]
+    InvalidNumberRange,
+
+    #[error("Operands of a bitwise operation must be the same size")]
+    InvalidOperandSize,
+
+    #[error("OP_NUM2BIN can not encode the number in the requested size")]
+    ImpossibleEncoding,
+
+    #[error("Lock time on top of the stack is negative")]
+    NegativeLocktime,
+
+    #[error("Lock time requirement of the script is not satisfied by the transaction")]
+    UnsatisfiedLocktime,
+
     #[error("{0}")]
     BSVErrors(#[from] BSVErrors),
 }
+
+/// Script error codes as reported by the node, see script_error.h.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScriptError {
+    UnknownError,
+    EvalFalse,
+    OpReturn,
+    ScriptSize,
+    PushSize,
+    OpCount,
+    StackSize,
+    SigCount,
+    PubkeyCount,
+    InvalidOperandSize,
+    InvalidNumberRange,
+    ImpossibleEncoding,
+    SplitRange,
+    ScriptnumOverflow,
+    ScriptnumMinencode,
+    Verify,
+    Equalverify,
+    Checkmultisigverify,
+    Checksigverify,
+    Numequalverify,
+    BadOpcode,
+    DisabledOpcode,
+    InvalidStackOperation,
+    InvalidAltstackOperation,
+    UnbalancedConditional,
+    NegativeLocktime,
+    UnsatisfiedLocktime,
+    SigHashtype,
+    SigDer,
+    Minimaldata,
+    SigPushonly,
+    SigHighS,
+    SigNulldummy,
+    Pubkeytype,
+    Cleanstack,
+    Nullfail,
+    DiscourageUpgradableNops,
+    DivByZero,
+    ModByZero,
+}
+
+impl ScriptError {
+    /// The name the node uses for this error, eg. `EQUALVERIFY`.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScriptError::UnknownError => "UNKNOWN_ERROR",
+            ScriptError::EvalFalse => "EVAL_FALSE",
+            ScriptError::OpReturn => "OP_RETURN",
+            ScriptError::ScriptSize => "SCRIPT_SIZE",
+            ScriptError::PushSize => "PUSH_SIZE",
+            ScriptError::OpCount => "OP_COUNT",
+            ScriptError::StackSize => "STACK_SIZE",
+            ScriptError::SigCount => "SIG_COUNT",
+            ScriptError::PubkeyCount => "PUBKEY_COUNT",
+            ScriptError::InvalidOperandSize => "INVALID_OPERAND_SIZE",
+            ScriptError::InvalidNumberRange => "INVALID_NUMBER_RANGE",
+            ScriptError::ImpossibleEncoding => "IMPOSSIBLE_ENCODING",
+            ScriptError::SplitRange => "SPLIT_RANGE",
+            ScriptError::ScriptnumOverflow => "SCRIPTNUM_OVERFLOW",
+            ScriptError::ScriptnumMinencode => "SCRIPTNUM_MINENCODE",
+            ScriptError::Verify => "VERIFY",
+            ScriptError::Equalverify => "EQUALVERIFY",
+            ScriptError::Checkmultisigverify => "CHECKMULTISIGVERIFY",
+            ScriptError::Checksigverify => "CHECKSIGVERIFY",
+            ScriptError::Numequalverify => "NUMEQUALVERIFY",
+            ScriptError::BadOpcode => "BAD_OPCODE",
+            ScriptError::DisabledOpcode => "DISABLED_OPCODE",
+            ScriptError::InvalidStackOperation => "INVALID_STACK_OPERATION",
+            ScriptError::InvalidAltstackOperation => "INVALID_ALTSTACK_OPERATION",
+            ScriptError::UnbalancedConditional => "UNBALANCED_CONDITIONAL",
+            ScriptError::NegativeLocktime => "NEGATIVE_LOCKTIME",
+            ScriptError::UnsatisfiedLocktime => "UNSATISFIED_LOCKTIME",
+            ScriptError::SigHashtype => "SIG_HASHTYPE",
+            ScriptError::SigDer => "SIG_DER",
+            ScriptError::Minimaldata => "MINIMALDATA",
+            ScriptError::SigPushonly => "SIG_PUSHONLY",
+            ScriptError::SigHighS => "SIG_HIGH_S",
+            ScriptError::SigNulldummy => "SIG_NULLDUMMY",
+            ScriptError::Pubkeytype => "PUBKEYTYPE",
+            ScriptError::Cleanstack => "CLEANSTACK",
+            ScriptError::Nullfail => "NULLFAIL",
+            ScriptError::DiscourageUpgradableNops => "DISCOURAGE_UPGRADABLE_NOPS",
+            ScriptError::DivByZero => "DIV_BY_ZERO",
+            ScriptError::ModByZero => "MOD_BY_ZERO",
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl InterpreterError {
+    /// The node's error code for this failure, errors the node can not raise map to `UnknownError`.
+    #[must_use]
+    pub fn script_error(&self) -> ScriptError {
+        match self {
+            InterpreterError::NumberOutOfRange => ScriptError::ScriptnumOverflow,
+            InterpreterError::NonMinimalNumber => ScriptError::ScriptnumMinencode,
+            InterpreterError::EmptyStack | InterpreterError::InvalidStackOperation => ScriptError::InvalidStackOperation,
+            InterpreterError::EmptyAltStack => ScriptError::InvalidAltstackOperation,
+            InterpreterError::InvalidOpcode(_) | InterpreterError::UnknownOpcode(_) | InterpreterError::MalformedScript => ScriptError::BadOpcode,
+            InterpreterError::DisabledOpCode(_) => ScriptError::DisabledOpcode,
+            InterpreterError::VerifyFailed => ScriptError::Verify,
+            InterpreterError::EqualVerify => ScriptError::Equalverify,
+            InterpreterError::NumEqualVerify => ScriptError::Numequalverify,
+            InterpreterError::CheckSigVerify => ScriptError::Checksigverify,
+            InterpreterError::CheckMultisigVerify => ScriptError::Checkmultisigverify,
+            InterpreterError::ScriptSize => ScriptError::ScriptSize,
+            InterpreterError::OpCount => ScriptError::OpCount,
+            InterpreterError::StackSize | InterpreterError::StackMemoryUsage => ScriptError::StackSize,
+            InterpreterError::PushSize => ScriptError::PushSize,
+            InterpreterError::MinimalData => ScriptError::Minimaldata,
+            InterpreterError::SigPushOnly => ScriptError::SigPushonly,
+            InterpreterError::CleanStack => ScriptError::Cleanstack,
+            InterpreterError::SigDer => ScriptError::SigDer,
+            InterpreterError::SigHighS => ScriptError::SigHighS,
+            InterpreterError::SigHashType => ScriptError::SigHashtype,
+            InterpreterError::PubKeyType => ScriptError::Pubkeytype,
+            InterpreterError::NullFail => ScriptError::Nullfail,
+            InterpreterError::SigNullDummy => ScriptError::SigNulldummy,
+            InterpreterError::PubKeyCount => ScriptError::PubkeyCount,
+            InterpreterError::SigCount => ScriptError::SigCount,
+            InterpreterError::DiscourageUpgradableNops(_) => ScriptError::DiscourageUpgradableNops,
+            InterpreterError::OpReturn => ScriptError::OpReturn,
+            InterpreterError::EvalFalse => ScriptError::EvalFalse,
+            InterpreterError::UnbalancedConditional => ScriptError::UnbalancedConditional,
+            InterpreterError::DivByZero => ScriptError::DivByZero,
+            InterpreterError::ModByZero => ScriptError::ModByZero,
+            InterpreterError::SplitRange => ScriptError::SplitRange,
+            InterpreterError::InvalidOperandSize => ScriptError::InvalidOperandSize,
+            InterpreterError::InvalidNumberRange => ScriptError::InvalidNumberRange,
+            InterpreterError::ImpossibleEncoding => ScriptError::ImpossibleEncoding,
+            InterpreterError::NegativeLocktime => ScriptError::NegativeLocktime,
+            InterpreterError::UnsatisfiedLocktime => ScriptError::UnsatisfiedLocktime,
+            InterpreterError::NonScriptData
+            | InterpreterError::TooLongForBool
+            | InterpreterError::RequiresTransaction(_)
+            | InterpreterError::FailedToConvertSighash
+            | InterpreterError::UnflattenedScript
+            | InterpreterError::NoTxInProvided
+            | InterpreterError::MissingLockingScript
+            | InterpreterError::MissingSatoshis
+            | InterpreterError::SighashPreimageCalculation(_)
+            | InterpreterError::ExecutionCost
+            | InterpreterError::BSVErrors(_) => ScriptError::UnknownError,
+        }
+    }
+}
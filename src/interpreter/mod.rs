@@ -3,19 +3,37 @@
 //ops::{Neg, Not, Shl, Shr},
 //};
 
+use std::sync::{Arc, Mutex};
+
 use crate::{Script, ScriptBit, Transaction};
-use errors::InterpreterError;
 //use num_bigint::{BigInt, Sign};
 use serde::{Deserialize, Serialize};
 //use stack_trait::ScriptStack;
 
+mod encoding_checks;
+pub(crate) use encoding_checks::is_strict_signature_encoding;
 mod errors;
+pub use errors::{InterpreterError, ScriptError};
+mod script_num;
+pub use script_num::ScriptNum;
 mod stack_trait;
 pub mod state;
 pub use state::*;
+pub mod script_config;
+pub use script_config::*;
+mod debugger;
+use debugger::History;
+pub use debugger::{Breakpoint, InterpreterSnapshot, DEFAULT_HISTORY_LIMIT};
+mod observer;
+use observer::Observers;
+pub use observer::{InterpreterObserver, TraceEvent, TraceRecorder};
 mod script_matching;
+mod verify;
+pub use verify::*;
+mod transaction_verifier;
+pub use transaction_verifier::TransactionVerifier;
 
-#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum Status {
     #[default]
     Running,
@@ -24,8 +42,11 @@ pub enum Status {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TxScript {
-    pub(crate) tx: Transaction,
+    /// Shared by every clone, so the transaction and its sighash cache are not copied for each opcode or input
+    pub(crate) tx: Arc<Transaction>,
     pub(crate) input_index: usize,
+    /// Index into the executed script bits where the locking script begins
+    pub(crate) locking_script_offset: usize,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -34,65 +55,141 @@ pub struct Interpreter {
     pub(crate) script_index: usize,
     pub(crate) state: State,
     pub(crate) tx_script: Option<TxScript>,
+    pub(crate) config: ScriptConfig,
+    #[serde(skip)]
+    pub(crate) observers: Observers,
+    pub(crate) breakpoints: Vec<Breakpoint>,
+    #[serde(skip)]
+    pub(crate) history: History,
 }
 
 impl Interpreter {
+    /// Expects the script bits to be the finalised script, ie. the unlocking script followed by the locking script
     pub fn from_transaction_and_script_bits(tx: Transaction, txin: usize, script_bits: Vec<ScriptBit>) -> Interpreter {
+        let locking_script_offset = tx.get_input(txin).map(|x| x.get_unlocking_script().to_flat_script_bits().len()).unwrap_or_default();
         Interpreter {
-            script_bits,
+            script_bits: Script::flatten_script_bits(&script_bits),
             script_index: 0,
             state: State::default(),
-            tx_script: Some(TxScript { tx, input_index: txin }),
+            tx_script: Some(TxScript {
+                tx: Arc::new(tx),
+                input_index: txin,
+                locking_script_offset,
+            }),
+            config: ScriptConfig::default(),
+            observers: Observers::default(),
+            breakpoints: vec![],
+            history: History::default(),
         }
     }
 
     /// Runs the script to completion
     pub(crate) fn run_impl(&mut self) -> Result<(), InterpreterError> {
         while let Some(state) = self.next_impl() {
-            let state = state?;
-            println!("=============NEXT==============");
-            println!("{}", state);
+            state?;
         }
 
         Ok(())
     }
 
     pub(crate) fn next_impl(&mut self) -> Option<Result<State, InterpreterError>> {
+        if self.script_index == 0 && Script::script_bits_to_bytes(&self.script_bits).len() > self.config.max_script_size {
+            return Some(Err(InterpreterError::ScriptSize));
+        }
+
         let script_bits = &self.script_bits.clone();
         let index = self.script_index;
-        let new_state = match script_bits.get(index) {
-            Some(v) => match Interpreter::match_script_bit(self, v) {
-                Ok(v) => v,
-                Err(e) => return Some(Err(e)),
-            },
+        let mut new_state = match script_bits.get(index) {
+            Some(v) => {
+                self.observers.notify(|o| o.before_opcode(index, v, &self.state));
+                let result = Interpreter::match_script_bit(self, v);
+                self.observers.notify(|o| o.after_opcode(index, v, result.as_ref()));
+                match result {
+                    Ok(v) => v,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
             None => {
+                if self.state.status == Status::Finished {
+                    return None;
+                }
+
                 self.state.status = Status::Finished;
-                return None;
+                return self.check_final_state().err().map(Err);
             }
         };
 
+        if let Err(e) = self.meter(&mut new_state) {
+            return Some(Err(e));
+        }
+
         self.script_index += 1;
         self.state = new_state;
 
         Some(Ok(self.state.clone()))
     }
+
+    /// Updates the stack memory usage and cost of a step and enforces their limits
+    fn meter(&self, new_state: &mut State) -> Result<(), InterpreterError> {
+        if new_state.stack.len() + new_state.alt_stack.len() > self.config.max_stack_size {
+            return Err(InterpreterError::StackSize);
+        }
+
+        new_state.stack_memory_usage = new_state.calculate_stack_memory_usage();
+        if new_state.stack_memory_usage > self.config.max_stack_memory_usage {
+            return Err(InterpreterError::StackMemoryUsage);
+        }
+
+        let growth = new_state.stack_memory_usage.saturating_sub(self.state.stack_memory_usage) as u64;
+        new_state.cost = self.state.cost.saturating_add(1).saturating_add(growth);
+        if new_state.cost > self.config.max_cost {
+            return Err(InterpreterError::ExecutionCost);
+        }
+
+        Ok(())
+    }
+
+    /// Checks run once the end of the script has been reached
+    fn check_final_state(&self) -> Result<(), InterpreterError> {
+        if !self.state.vf_exec.is_empty() {
+            return Err(InterpreterError::UnbalancedConditional);
+        }
+
+        if self.config.flags.clean_stack && self.state.stack.len() != 1 {
+            return Err(InterpreterError::CleanStack);
+        }
+
+        Ok(())
+    }
 }
 
 /// Both WASM and Rust functionality
 impl Interpreter {
     pub fn from_script(script: &Script) -> Interpreter {
         Interpreter {
-            script_bits: script.to_script_bits(),
+            script_bits: script.to_flat_script_bits(),
             script_index: 0,
             state: State::default(),
             tx_script: None,
+            config: ScriptConfig::default(),
+            observers: Observers::default(),
+            breakpoints: vec![],
+            history: History::default(),
+        }
+    }
+
+    pub fn from_script_with_config(script: &Script, config: &ScriptConfig) -> Interpreter {
+        Interpreter {
+            config: config.clone(),
+            ..Interpreter::from_script(script)
         }
     }
 
+    /// Get the executed script, with conditionals nested into ScriptBit::If blocks for display.
     pub fn script(&self) -> Script {
-        Script::from_script_bits(self.script_bits.clone())
+        Script::from_flat_script_bits(&self.script_bits)
     }
-    /// Get the interpreter's script index.
+    /// Get the interpreter's script index, after a failed step this is the index of the failing script bit.
     #[must_use]
     pub fn script_index(&self) -> usize {
         self.script_index
@@ -110,16 +207,49 @@ impl Interpreter {
         self.tx_script.clone()
     }
 
+    /// Get the flags and limits the interpreter evaluates with.
+    #[must_use]
+    pub fn config(&self) -> ScriptConfig {
+        self.config.clone()
+    }
+
+    pub fn set_config(&mut self, config: &ScriptConfig) {
+        self.config = config.clone();
+    }
+
+    /// Attaches an observer that is notified of execution events, keep a clone of the Arc to read it back afterwards.
+    pub fn add_observer<T: InterpreterObserver + Send + 'static>(&mut self, observer: Arc<Mutex<T>>) {
+        self.observers.push(observer);
+    }
+
+    /// Detaches an observer attached with `add_observer`.
+    pub fn remove_observer<T: InterpreterObserver + Send + 'static>(&mut self, observer: &Arc<Mutex<T>>) {
+        let observer: Arc<Mutex<dyn InterpreterObserver + Send>> = observer.clone();
+        self.observers.remove(&observer);
+    }
+
     pub fn from_transaction(tx: &Transaction, txin: usize) -> Result<Interpreter, InterpreterError> {
-        let script_bits = tx.get_input(txin).unwrap().get_finalised_script_impl()?.to_script_bits();
-        Ok(Interpreter::from_transaction_and_script_bits(tx.clone(), txin, script_bits))
+        Interpreter::from_transaction_with_config(tx, txin, &ScriptConfig::default())
+    }
+
+    pub fn from_transaction_with_config(tx: &Transaction, txin: usize, config: &ScriptConfig) -> Result<Interpreter, InterpreterError> {
+        let input = tx.get_input(txin).ok_or(InterpreterError::NoTxInProvided)?;
+        if config.flags.sig_push_only && !input.get_unlocking_script().is_push_only() {
+            return Err(InterpreterError::SigPushOnly);
+        }
+
+        let script_bits = input.get_finalised_script_impl()?.to_script_bits();
+        Ok(Interpreter {
+            config: config.clone(),
+            ..Interpreter::from_transaction_and_script_bits(tx.clone(), txin, script_bits)
+        })
     }
 
     pub fn run(&mut self) -> Result<(), InterpreterError> {
         self.run_impl()
     }
 
-    /// Get a reference to the interpreter's script bits.
+    /// Get the flat script bits the interpreter executes, `script_index` indexes into these.
     #[must_use]
     pub fn script_bits(&self) -> Vec<ScriptBit> {
         self.script_bits.clone()
@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{OpCodes, ScriptBit, SigHash};
+use serde::{Deserialize, Serialize};
+
+use super::{errors::InterpreterError, state::State};
+
+/// Hooks into script evaluation, every method defaults to doing nothing so only the events of interest need implementing.
+pub trait InterpreterObserver {
+    /// Called before the script bit at `script_index` is executed.
+    fn before_opcode(&mut self, _script_index: usize, _bit: &ScriptBit, _state: &State) {}
+
+    /// Called after the script bit at `script_index` was executed, with the new state or the error it failed with.
+    fn after_opcode(&mut self, _script_index: usize, _bit: &ScriptBit, _result: Result<&State, &InterpreterError>) {}
+
+    /// Called when a sighash preimage was computed for a signature check.
+    fn on_sighash_preimage(&mut self, _sighash: SigHash, _preimage: &[u8]) {}
+
+    /// Called after a signature was checked against a public key.
+    fn on_signature_checked(&mut self, _signature: &[u8], _public_key: &[u8], _is_valid: bool) {}
+
+    /// Called when a conditional opcode decides whether its branch is executed.
+    fn on_branch(&mut self, _opcode: OpCodes, _taken: bool) {}
+}
+
+/// Observers attached to an Interpreter, shared between clones of it.
+#[derive(Clone, Default)]
+pub(crate) struct Observers(Vec<Arc<Mutex<dyn InterpreterObserver + Send>>>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: Arc<Mutex<dyn InterpreterObserver + Send>>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn remove(&mut self, observer: &Arc<Mutex<dyn InterpreterObserver + Send>>) {
+        self.0.retain(|x| !Arc::ptr_eq(x, observer));
+    }
+
+    pub(crate) fn notify(&self, event: impl Fn(&mut dyn InterpreterObserver)) {
+        for observer in &self.0 {
+            // A poisoned observer panicked during a previous event, it is still safe to keep notifying it
+            let mut observer = observer.lock().unwrap_or_else(|e| e.into_inner());
+            event(&mut *observer);
+        }
+    }
+}
+
+/// A single entry of an ExecutionTrace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TraceEvent {
+    Step { script_index: usize, bit: ScriptBit, state: State },
+    Error { script_index: usize, bit: ScriptBit, error: String },
+    SighashPreimage { sighash: SigHash, preimage: Vec<u8> },
+    SignatureChecked { signature: Vec<u8>, public_key: Vec<u8>, is_valid: bool },
+    Branch { opcode: OpCodes, taken: bool },
+}
+
+/// Observer that records every event in order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceRecorder {
+    pub(crate) events: Vec<TraceEvent>,
+}
+
+impl TraceRecorder {
+    pub fn new() -> TraceRecorder {
+        TraceRecorder::default()
+    }
+
+    /// Get the recorded events.
+    #[must_use]
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.events.clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+impl InterpreterObserver for TraceRecorder {
+    fn after_opcode(&mut self, script_index: usize, bit: &ScriptBit, result: Result<&State, &InterpreterError>) {
+        self.events.push(match result {
+            Ok(state) => TraceEvent::Step {
+                script_index,
+                bit: bit.clone(),
+                state: state.clone(),
+            },
+            Err(e) => TraceEvent::Error {
+                script_index,
+                bit: bit.clone(),
+                error: e.to_string(),
+            },
+        });
+    }
+
+    fn on_sighash_preimage(&mut self, sighash: SigHash, preimage: &[u8]) {
+        self.events.push(TraceEvent::SighashPreimage { sighash, preimage: preimage.to_vec() });
+    }
+
+    fn on_signature_checked(&mut self, signature: &[u8], public_key: &[u8], is_valid: bool) {
+        self.events.push(TraceEvent::SignatureChecked {
+            signature: signature.to_vec(),
+            public_key: public_key.to_vec(),
+            is_valid,
+        });
+    }
+
+    fn on_branch(&mut self, opcode: OpCodes, taken: bool) {
+        self.events.push(TraceEvent::Branch { opcode, taken });
+    }
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of non-push operations per script before Genesis
+pub const MAX_OPS_PER_SCRIPT_BEFORE_GENESIS: usize = 500;
+/// Maximum script length in bytes before Genesis
+pub const MAX_SCRIPT_SIZE_BEFORE_GENESIS: usize = 10_000;
+/// Maximum number of bytes pushable to the stack before Genesis
+pub const MAX_SCRIPT_ELEMENT_SIZE_BEFORE_GENESIS: usize = 520;
+/// Maximum number of elements on the stack and alt stack combined before Genesis
+pub const MAX_STACK_ELEMENTS_BEFORE_GENESIS: usize = 1_000;
+/// Maximum number of public keys OP_CHECKMULTISIG accepts before Genesis
+pub const MAX_PUBKEYS_PER_MULTISIG_BEFORE_GENESIS: usize = 20;
+/// Maximum length in bytes of a number operand before Genesis
+pub const MAX_SCRIPT_NUM_LENGTH_BEFORE_GENESIS: usize = 4;
+/// Maximum length in bytes of a number operand after Genesis
+pub const MAX_SCRIPT_NUM_LENGTH_AFTER_GENESIS: usize = 750_000;
+/// Default policy limit on the length in bytes of a number operand after Genesis
+pub const DEFAULT_SCRIPT_NUM_LENGTH_POLICY_AFTER_GENESIS: usize = 250 * 1024;
+/// Default policy limit on the memory used by the stack and alt stack after Genesis
+pub const DEFAULT_STACK_MEMORY_USAGE_POLICY_AFTER_GENESIS: usize = 100_000_000;
+/// Bytes every stack element counts towards the stack memory usage on top of its length
+pub const ELEMENT_OVERHEAD: usize = 32;
+
+/// Script verification flags, mirroring the `SCRIPT_VERIFY_*` flags used by the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerifyFlags {
+    /// The Genesis upgrade is active on the chain the spend is validated against
+    pub genesis: bool,
+    /// The UTXO being spent was created after Genesis activation, so Genesis script rules apply
+    pub utxo_after_genesis: bool,
+    /// Data must be pushed with the smallest possible push operation
+    pub minimal_data: bool,
+    /// The unlocking script may only contain push operations
+    pub sig_push_only: bool,
+    /// Exactly one item must be left on the stack after evaluation
+    pub clean_stack: bool,
+    /// Signatures must be strict DER with a defined sighash type, public keys must be compressed or uncompressed
+    pub strict_enc: bool,
+    /// Signatures must use the lower of the two possible S values
+    pub low_s: bool,
+    /// A failing signature check must have been given an empty signature
+    pub null_fail: bool,
+    /// The extra element consumed by OP_CHECKMULTISIG must be empty
+    pub null_dummy: bool,
+    /// OP_NOP1 and OP_NOP4-OP_NOP10 fail instead of being ignored
+    pub discourage_upgradable_nops: bool,
+    /// OP_CHECKLOCKTIMEVERIFY is evaluated as BIP65 instead of OP_NOP2 before Genesis
+    pub check_locktime_verify: bool,
+    /// OP_CHECKSEQUENCEVERIFY is evaluated as BIP112 instead of OP_NOP3 before Genesis
+    pub check_sequence_verify: bool,
+    /// The Chronicle upgrade is active, restoring OP_VER, OP_VERIF, OP_VERNOTIF, OP_2MUL, OP_2DIV,
+    /// OP_SUBSTR, OP_LEFT, OP_RIGHT, OP_LSHIFTNUM and OP_RSHIFTNUM
+    pub chronicle: bool,
+}
+
+impl VerifyFlags {
+    /// Consensus rules for spending an output created before Genesis
+    pub fn pre_genesis() -> VerifyFlags {
+        VerifyFlags {
+            genesis: false,
+            utxo_after_genesis: false,
+            minimal_data: false,
+            sig_push_only: false,
+            clean_stack: false,
+            strict_enc: false,
+            low_s: false,
+            null_fail: false,
+            null_dummy: false,
+            discourage_upgradable_nops: false,
+            check_locktime_verify: true,
+            check_sequence_verify: true,
+            chronicle: false,
+        }
+    }
+
+    /// Consensus rules for spending an output created after Genesis, which made push only unlocking scripts consensus
+    pub fn post_genesis() -> VerifyFlags {
+        VerifyFlags {
+            genesis: true,
+            utxo_after_genesis: true,
+            sig_push_only: true,
+            ..VerifyFlags::pre_genesis()
+        }
+    }
+
+    /// Consensus rules after the Chronicle upgrade
+    pub fn chronicle() -> VerifyFlags {
+        VerifyFlags {
+            chronicle: true,
+            ..VerifyFlags::post_genesis()
+        }
+    }
+
+    /// Standard policy rules miners apply when accepting transactions, on top of post Genesis consensus
+    pub fn standard() -> VerifyFlags {
+        VerifyFlags {
+            minimal_data: true,
+            sig_push_only: true,
+            clean_stack: true,
+            strict_enc: true,
+            low_s: true,
+            null_fail: true,
+            null_dummy: true,
+            discourage_upgradable_nops: true,
+            ..VerifyFlags::post_genesis()
+        }
+    }
+
+    /// Whether the script is evaluated under Genesis rules
+    pub fn is_post_genesis(&self) -> bool {
+        self.genesis && self.utxo_after_genesis
+    }
+}
+
+impl Default for VerifyFlags {
+    fn default() -> Self {
+        VerifyFlags::post_genesis()
+    }
+}
+
+/// Flags and resource limits the Interpreter evaluates a script with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptConfig {
+    pub flags: VerifyFlags,
+    /// Maximum number of non-push operations in a script
+    pub max_ops_per_script: usize,
+    /// Maximum length of a script in bytes
+    pub max_script_size: usize,
+    /// Maximum length of a single pushed stack element in bytes
+    pub max_script_element_size: usize,
+    /// Maximum number of elements on the stack and alt stack combined
+    pub max_stack_size: usize,
+    /// Maximum length in bytes of a number operand
+    pub max_script_num_length: usize,
+    /// Maximum number of public keys OP_CHECKMULTISIG accepts
+    pub max_pubkeys_per_multisig: usize,
+    /// Maximum memory used by the stack and alt stack, each element counts its length plus ELEMENT_OVERHEAD bytes
+    pub max_stack_memory_usage: usize,
+    /// Maximum execution cost, see `State::cost`
+    pub max_cost: u64,
+}
+
+impl ScriptConfig {
+    /// Creates a config with the consensus limits matching the given flags
+    pub fn new(flags: VerifyFlags) -> ScriptConfig {
+        match flags.is_post_genesis() {
+            true => ScriptConfig {
+                flags,
+                max_ops_per_script: usize::MAX,
+                max_script_size: usize::MAX,
+                max_script_element_size: usize::MAX,
+                max_stack_size: usize::MAX,
+                max_script_num_length: MAX_SCRIPT_NUM_LENGTH_AFTER_GENESIS,
+                max_pubkeys_per_multisig: i32::MAX as usize,
+                max_stack_memory_usage: usize::MAX,
+                max_cost: u64::MAX,
+            },
+            false => ScriptConfig {
+                flags,
+                max_ops_per_script: MAX_OPS_PER_SCRIPT_BEFORE_GENESIS,
+                max_script_size: MAX_SCRIPT_SIZE_BEFORE_GENESIS,
+                max_script_element_size: MAX_SCRIPT_ELEMENT_SIZE_BEFORE_GENESIS,
+                max_stack_size: MAX_STACK_ELEMENTS_BEFORE_GENESIS,
+                max_script_num_length: MAX_SCRIPT_NUM_LENGTH_BEFORE_GENESIS,
+                max_pubkeys_per_multisig: MAX_PUBKEYS_PER_MULTISIG_BEFORE_GENESIS,
+                max_stack_memory_usage: usize::MAX,
+                max_cost: u64::MAX,
+            },
+        }
+    }
+
+    pub fn pre_genesis() -> ScriptConfig {
+        ScriptConfig::new(VerifyFlags::pre_genesis())
+    }
+
+    pub fn post_genesis() -> ScriptConfig {
+        ScriptConfig::new(VerifyFlags::post_genesis())
+    }
+
+    pub fn chronicle() -> ScriptConfig {
+        ScriptConfig::new(VerifyFlags::chronicle())
+    }
+
+    /// Standard policy flags and the default policy limits of the node
+    pub fn standard() -> ScriptConfig {
+        ScriptConfig {
+            max_script_num_length: DEFAULT_SCRIPT_NUM_LENGTH_POLICY_AFTER_GENESIS,
+            max_stack_memory_usage: DEFAULT_STACK_MEMORY_USAGE_POLICY_AFTER_GENESIS,
+            ..ScriptConfig::new(VerifyFlags::standard())
+        }
+    }
+}
+
+impl Default for ScriptConfig {
+    fn default() -> Self {
+        ScriptConfig::post_genesis()
+    }
+}
@@ -1,10 +1,15 @@
 use std::ops::Neg;
 
-use crate::{Hash, OpCodes, PublicKey, Script, ScriptBit, SigHash, SighashSignature, ToHex};
+use crate::{Hash, OpCodes, PublicKey, Script, ScriptBit, SigHash, SighashSignature, ECDSA, SIGHASH_SINGLE_BUG_DIGEST};
 use num_bigint::{BigInt, Sign};
+use num_traits::{ToPrimitive, Zero};
 
 use super::{
+    encoding_checks,
     errors::InterpreterError,
+    observer::Observers,
+    script_config::ScriptConfig,
+    script_num::ScriptNum,
     stack_trait::{self, ScriptStack},
     state::State,
     Interpreter, TxScript,
@@ -12,16 +17,61 @@ use super::{
 
 /// Script Matching functions
 impl Interpreter {
-    fn verify(boolean: bool) -> Result<(), InterpreterError> {
+    fn verify(boolean: bool, error: InterpreterError) -> Result<(), InterpreterError> {
         match boolean {
             true => Ok(()),
-            false => Err(InterpreterError::VerifyFailed),
+            false => Err(error),
         }
     }
 
+    fn count_op(&mut self, opcode: u8) -> Result<(), InterpreterError> {
+        if opcode > OpCodes::OP_16 as u8 {
+            self.state.op_count += 1;
+            if self.state.op_count > self.config.max_ops_per_script {
+                return Err(InterpreterError::OpCount);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_push(&self, bit: &ScriptBit, data: &[u8]) -> Result<(), InterpreterError> {
+        if data.len() > self.config.max_script_element_size {
+            return Err(InterpreterError::PushSize);
+        }
+
+        if self.state.is_executing() && self.config.flags.minimal_data && !encoding_checks::is_minimal_push(bit) {
+            return Err(InterpreterError::MinimalData);
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn match_script_bit(&mut self, bit: &ScriptBit) -> Result<State, InterpreterError> {
+        let is_executing = self.state.is_executing();
+
         Ok(match bit {
-            ScriptBit::OpCode(o) => match Interpreter::match_opcode(self.script_index, o, &mut self.state.clone(), self.tx_script.clone()) {
+            ScriptBit::OpCode(o) if self.is_conditional(o) => {
+                self.count_op(*o as u8)?;
+                self.match_conditional(o, is_executing)?;
+                self.state.executed_opcodes.push(*o);
+                self.state.clone()
+            }
+            ScriptBit::OpCode(OpCodes::OP_RETURN) if is_executing && self.config.flags.is_post_genesis() => {
+                self.count_op(OpCodes::OP_RETURN as u8)?;
+                self.state.executed_opcodes.push(OpCodes::OP_RETURN);
+
+                // A top level OP_RETURN ends the script successfully, inside a branch only the conditionals are still checked
+                match self.state.vf_exec.is_empty() {
+                    true => self.script_index = self.script_bits.len() - 1,
+                    false => self.state.non_top_level_return = true,
+                }
+                self.state.clone()
+            }
+            ScriptBit::OpCode(o) if is_executing || self.fails_when_not_executing(o) => match self
+                .count_op(*o as u8)
+                .and_then(|_| Interpreter::match_opcode(self.script_index, o, &mut self.state.clone(), self.tx_script.clone(), &self.config, &self.observers))
+            {
                 Ok(mut next_state) => {
                     next_state.executed_opcodes.push(*o);
                     next_state
@@ -31,37 +81,104 @@ impl Interpreter {
                     return Err(e);
                 }
             },
+            ScriptBit::OpCode(o) => {
+                self.count_op(*o as u8)?;
+                self.state.clone()
+            }
             ScriptBit::Push(v) => {
-                self.state.stack.push(v.clone());
-                self.state.executed_opcodes.push(OpCodes::OP_DATA);
+                self.check_push(bit, v)?;
+                if is_executing {
+                    self.state.stack.push(v.clone());
+                    self.state.executed_opcodes.push(OpCodes::OP_DATA);
+                }
                 self.state.clone()
             }
             ScriptBit::PushData(size, v) => {
-                self.state.stack.push(v.clone());
-                self.state.executed_opcodes.push(*size);
+                self.check_push(bit, v)?;
+                if is_executing {
+                    self.state.stack.push(v.clone());
+                    self.state.executed_opcodes.push(*size);
+                }
                 self.state.clone()
             }
-            ScriptBit::NonScriptData(_) => return Err(InterpreterError::NonScriptData),
-            ScriptBit::If { code, pass, fail } => {
-                let predicate = self.state.stack.pop_bool()?;
-                self.state.executed_opcodes.push(*code);
-
-                if predicate {
-                    let _removed: Vec<ScriptBit> = self.script_bits.splice(self.script_index + 1..self.script_index + 1, pass.clone()).collect();
-                    // println!("Removed items: {:?}", removed);
-                } else {
-                    let _removed: Vec<ScriptBit> = self.script_bits.splice(self.script_index + 1..self.script_index + 1, fail.clone().unwrap_or_default()).collect();
-                    // println!("Removed items: {:?}", removed);
+            ScriptBit::NonScriptData(_) if is_executing => return Err(InterpreterError::NonScriptData),
+            ScriptBit::NonScriptData(_) => self.state.clone(),
+            ScriptBit::If { .. } => return Err(InterpreterError::UnflattenedScript),
+            ScriptBit::Coinbase(_) => todo!(),
+            ScriptBit::UnknownOpCode(code) => {
+                self.count_op(*code)?;
+                if is_executing {
+                    return Err(InterpreterError::UnknownOpcode(*code));
                 }
-
                 self.state.clone()
             }
-            ScriptBit::Coinbase(_) => todo!(),
+            // The node fails to read the instruction whether or not the branch is executed
+            ScriptBit::Malformed(_) => return Err(InterpreterError::MalformedScript),
         })
     }
 
+    /// OP_VERIF and OP_VERNOTIF only open a branch after Chronicle
+    fn is_conditional(&self, opcode: &OpCodes) -> bool {
+        match opcode {
+            OpCodes::OP_IF | OpCodes::OP_NOTIF | OpCodes::OP_ELSE | OpCodes::OP_ENDIF => true,
+            OpCodes::OP_VERIF | OpCodes::OP_VERNOTIF => self.config.flags.chronicle,
+            _ => false,
+        }
+    }
+
+    /// Opcodes that fail even in branches that are not executed
+    fn fails_when_not_executing(&self, opcode: &OpCodes) -> bool {
+        let flags = &self.config.flags;
+        match opcode {
+            OpCodes::OP_VERIF | OpCodes::OP_VERNOTIF => !flags.chronicle,
+            // Disabled opcodes only fail when executed after Genesis
+            OpCodes::OP_2MUL | OpCodes::OP_2DIV => !flags.chronicle && !flags.is_post_genesis(),
+            _ => false,
+        }
+    }
+
+    /// Updates the condition stack, following the vfExec/vfElse handling of the node.
+    fn match_conditional(&mut self, opcode: &OpCodes, is_executing: bool) -> Result<(), InterpreterError> {
+        match opcode {
+            OpCodes::OP_IF | OpCodes::OP_NOTIF | OpCodes::OP_VERIF | OpCodes::OP_VERNOTIF => {
+                let mut predicate = false;
+                if is_executing {
+                    let top = self.state.stack.pop().ok_or(InterpreterError::UnbalancedConditional)?;
+                    predicate = match opcode {
+                        OpCodes::OP_IF | OpCodes::OP_NOTIF => stack_trait::cast_to_bool(&top) == (*opcode == OpCodes::OP_IF),
+                        OpCodes::OP_VERIF => top == tx_version(self.tx_script.as_ref(), &OpCodes::OP_VERIF)?,
+                        _ => top != tx_version(self.tx_script.as_ref(), &OpCodes::OP_VERNOTIF)?,
+                    };
+                    self.observers.notify(|o| o.on_branch(*opcode, predicate));
+                }
+                self.state.vf_exec.push(predicate);
+                self.state.vf_else.push(false);
+            }
+            OpCodes::OP_ELSE => {
+                let seen_else = self.state.vf_else.last_mut().ok_or(InterpreterError::UnbalancedConditional)?;
+                // Only one OP_ELSE is allowed per OP_IF after Genesis
+                if *seen_else && self.config.flags.is_post_genesis() {
+                    return Err(InterpreterError::UnbalancedConditional);
+                }
+                *seen_else = true;
+
+                if let Some(top) = self.state.vf_exec.last_mut() {
+                    *top = !*top;
+                }
+                let taken = self.state.is_executing();
+                self.observers.notify(|o| o.on_branch(OpCodes::OP_ELSE, taken));
+            }
+            _ => {
+                self.state.vf_exec.pop().ok_or(InterpreterError::UnbalancedConditional)?;
+                self.state.vf_else.pop();
+            }
+        }
+
+        Ok(())
+    }
+
     #[allow(unused_mut)]
-    pub(crate) fn match_opcode(script_index: usize, opcode: &OpCodes, state: &mut State, tx: Option<TxScript>) -> Result<State, InterpreterError> {
+    pub(crate) fn match_opcode(script_index: usize, opcode: &OpCodes, state: &mut State, tx: Option<TxScript>, config: &ScriptConfig, observers: &Observers) -> Result<State, InterpreterError> {
         let mut state: &mut State = state;
         match opcode {
             OpCodes::OP_0 => state.stack.push_number(0)?,
@@ -83,23 +200,17 @@ impl Interpreter {
             OpCodes::OP_15 => state.stack.push_number(15)?,
             OpCodes::OP_16 => state.stack.push_number(16)?,
             OpCodes::OP_NOP => {}
-            OpCodes::OP_IF => {
-                // NOP - handled by ScriptBit interpreter
-            }
-            OpCodes::OP_NOTIF => {
-                // NOP - handled by ScriptBit interpreter
-            }
-            OpCodes::OP_ELSE => {
-                // NOP - handled by ScriptBit interpreter
-            }
-            OpCodes::OP_ENDIF => {
-                // NOP - handled by ScriptBit interpreter
+            OpCodes::OP_IF | OpCodes::OP_NOTIF | OpCodes::OP_ELSE | OpCodes::OP_ENDIF => {
+                // NOP - handled by the condition stack in match_conditional
             }
             OpCodes::OP_VERIFY => {
                 let predicate = state.stack.pop_bool()?;
-                Interpreter::verify(predicate)?
+                Interpreter::verify(predicate, InterpreterError::VerifyFailed)?
             }
             OpCodes::OP_RETURN => {
+                if !config.flags.is_post_genesis() {
+                    return Err(InterpreterError::OpReturn);
+                }
                 return Ok(state.clone());
             }
             OpCodes::OP_TOALTSTACK => {
@@ -107,17 +218,12 @@ impl Interpreter {
                 state.alt_stack.push_bytes(a);
             }
             OpCodes::OP_FROMALTSTACK => {
-                let a = state.alt_stack.pop_bytes()?;
+                let a = state.alt_stack.pop().ok_or(InterpreterError::EmptyAltStack)?;
                 state.stack.push_bytes(a);
             }
             OpCodes::OP_IFDUP => {
-                let predicate = state.stack.pop_bool()?;
-                if predicate {
-                    let top_data = match state.stack.last().cloned() {
-                        Some(v) => v,
-                        None => return Err(InterpreterError::EmptyStack),
-                    };
-
+                let top_data = state.stack.last().cloned().ok_or(InterpreterError::EmptyStack)?;
+                if stack_trait::cast_to_bool(&top_data) {
                     state.stack.push(top_data);
                 }
             }
@@ -137,58 +243,72 @@ impl Interpreter {
                 state.stack.push(top_data);
             }
             OpCodes::OP_NIP => {
+                state.stack.require(2)?;
                 state.stack.remove(state.stack.len() - 2);
             }
             OpCodes::OP_OVER => {
-                let index = state.stack.len() - 2;
-                let second_last = state.stack.get(index).cloned().ok_or(InterpreterError::NumberOutOfRange)?;
+                state.stack.require(2)?;
+                let second_last = state.stack[state.stack.len() - 2].clone();
                 state.stack.push_bytes(second_last);
             }
-            OpCodes::OP_PICK => {
-                let index = state.stack.pop_number()?;
-                let selected_item = state.stack.get((state.stack.len() - 1) - index as usize).cloned().ok_or(InterpreterError::NumberOutOfRange)?;
-                state.stack.push_bytes(selected_item);
-            }
-            OpCodes::OP_ROLL => {
-                let index = state.stack.pop_number()?;
-                let selected_item = state.stack.remove((state.stack.len() - 1) - index as usize);
+            OpCodes::OP_PICK | OpCodes::OP_ROLL => {
+                state.stack.require(2)?;
+                let index = state.stack.pop_bigint(config)?;
+                let index = match index.to_usize() {
+                    Some(v) if v < state.stack.len() => state.stack.len() - 1 - v,
+                    _ => return Err(InterpreterError::InvalidStackOperation),
+                };
+
+                let selected_item = match opcode {
+                    OpCodes::OP_PICK => state.stack[index].clone(),
+                    _ => state.stack.remove(index),
+                };
                 state.stack.push_bytes(selected_item);
             }
             OpCodes::OP_ROT => {
+                state.stack.require(3)?;
                 let len = state.stack.len();
                 let third = state.stack.remove(len - 3);
 
                 state.stack.push_bytes(third);
             }
             OpCodes::OP_SWAP => {
+                state.stack.require(2)?;
                 let len = state.stack.len();
                 state.stack.swap(len - 1, len - 2);
             }
             OpCodes::OP_TUCK => {
-                let selected_item = state.stack.last().cloned().ok_or(InterpreterError::NumberOutOfRange)?;
+                state.stack.require(2)?;
+                let selected_item = state.stack[state.stack.len() - 1].clone();
                 state.stack.insert(state.stack.len() - 2, selected_item);
             }
             OpCodes::OP_2DROP => {
+                state.stack.require(2)?;
                 state.stack.pop_bytes()?;
                 state.stack.pop_bytes()?;
             }
             OpCodes::OP_2DUP => {
-                let first = state.stack.last().cloned().ok_or(InterpreterError::NumberOutOfRange)?;
-                let second = state.stack.get(state.stack.len() - 2).cloned().ok_or(InterpreterError::NumberOutOfRange)?;
+                state.stack.require(2)?;
+                let len = state.stack.len();
+                let second = state.stack[len - 2].clone();
+                let first = state.stack[len - 1].clone();
 
-                state.stack.push_bytes(first);
                 state.stack.push_bytes(second);
+                state.stack.push_bytes(first);
             }
             OpCodes::OP_3DUP => {
-                let first = state.stack.last().cloned().ok_or(InterpreterError::NumberOutOfRange)?;
-                let second = state.stack.get(state.stack.len() - 2).cloned().ok_or(InterpreterError::NumberOutOfRange)?;
-                let third = state.stack.get(state.stack.len() - 3).cloned().ok_or(InterpreterError::NumberOutOfRange)?;
+                state.stack.require(3)?;
+                let len = state.stack.len();
+                let third = state.stack[len - 3].clone();
+                let second = state.stack[len - 2].clone();
+                let first = state.stack[len - 1].clone();
 
-                state.stack.push_bytes(first);
-                state.stack.push_bytes(second);
                 state.stack.push_bytes(third);
+                state.stack.push_bytes(second);
+                state.stack.push_bytes(first);
             }
             OpCodes::OP_2OVER => {
+                state.stack.require(4)?;
                 let len = state.stack.len();
                 let third = state.stack[len - 3].clone();
                 let fourth = state.stack[len - 4].clone();
@@ -196,6 +316,7 @@ impl Interpreter {
                 state.stack.push_bytes(third);
             }
             OpCodes::OP_2ROT => {
+                state.stack.require(6)?;
                 let index = state.stack.len() - 6;
                 let sixth = state.stack.remove(index);
                 let fifth = state.stack.remove(index);
@@ -204,36 +325,77 @@ impl Interpreter {
             }
 
             OpCodes::OP_2SWAP => {
+                state.stack.require(4)?;
                 let x1 = state.stack.pop_bytes()?;
                 let x2 = state.stack.pop_bytes()?;
                 let x3 = state.stack.pop_bytes()?;
                 let x4 = state.stack.pop_bytes()?;
 
-                state.stack.push_bytes(x3);
-                state.stack.push_bytes(x4);
+                state.stack.push_bytes(x2);
                 state.stack.push_bytes(x1);
-                state.stack.push_bytes(x2)
+                state.stack.push_bytes(x4);
+                state.stack.push_bytes(x3)
             }
             OpCodes::OP_CAT => {
-                let mut x1 = state.stack.pop_bytes()?;
+                state.stack.require(2)?;
                 let x2 = state.stack.pop_bytes()?;
+                let mut x1 = state.stack.pop_bytes()?;
+
+                // Checked before allocating the result
+                let length = x1.len() + x2.len();
+                if length > config.max_script_element_size || length > config.max_stack_memory_usage {
+                    return Err(InterpreterError::PushSize);
+                }
 
                 x1.extend_from_slice(&x2);
 
                 state.stack.push_bytes(x1)
             }
             OpCodes::OP_SPLIT => {
+                state.stack.require(2)?;
+                let n = state.stack.pop_bigint(config)?;
                 let x = state.stack.pop_bytes()?;
-                let n = state.stack.pop_number()?;
 
-                let (x1, x2) = x.split_at(n as usize);
+                let position = match n.to_usize() {
+                    Some(v) if v <= x.len() => v,
+                    _ => return Err(InterpreterError::SplitRange),
+                };
+                let (x1, x2) = x.split_at(position);
                 state.stack.push_bytes(x1.to_vec());
                 state.stack.push_bytes(x2.to_vec());
             }
 
+            &OpCodes::OP_SUBSTR if config.flags.chronicle => {
+                state.stack.require(3)?;
+                let size = state.stack.pop_bigint(config)?;
+                let begin = state.stack.pop_bigint(config)?;
+                let x = state.stack.pop_bytes()?;
+
+                let range = match (begin.to_usize(), size.to_usize()) {
+                    (Some(begin), Some(size)) if begin.checked_add(size).is_some_and(|end| end <= x.len()) => begin..begin + size,
+                    _ => return Err(InterpreterError::SplitRange),
+                };
+                state.stack.push_bytes(x[range].to_vec());
+            }
+            &OpCodes::OP_LEFT | &OpCodes::OP_RIGHT if config.flags.chronicle => {
+                state.stack.require(2)?;
+                let size = state.stack.pop_bigint(config)?;
+                let x = state.stack.pop_bytes()?;
+
+                let size = match size.to_usize() {
+                    Some(v) if v <= x.len() => v,
+                    _ => return Err(InterpreterError::SplitRange),
+                };
+                let data = match opcode {
+                    &OpCodes::OP_LEFT => x[..size].to_vec(),
+                    _ => x[x.len() - size..].to_vec(),
+                };
+                state.stack.push_bytes(data);
+            }
+
             OpCodes::OP_SIZE => {
-                let len = state.stack.last().unwrap().len();
-                state.stack.push_number(len as i64)?;
+                let len = state.stack.last().ok_or(InterpreterError::EmptyStack)?.len();
+                state.stack.push_bigint(BigInt::from(len))?;
             }
             OpCodes::OP_INVERT => {
                 let inverted_bytes = state.stack.pop_bytes()?.iter().map(|x| !x).collect();
@@ -243,6 +405,9 @@ impl Interpreter {
             OpCodes::OP_AND => {
                 let a = state.stack.pop_bytes()?;
                 let b = state.stack.pop_bytes()?;
+                if a.len() != b.len() {
+                    return Err(InterpreterError::InvalidOperandSize);
+                }
 
                 let and_array = b.iter().zip(a.iter()).map(|(&x1, &x2)| x1 & x2).collect();
 
@@ -251,6 +416,9 @@ impl Interpreter {
             OpCodes::OP_OR => {
                 let a = state.stack.pop_bytes()?;
                 let b = state.stack.pop_bytes()?;
+                if a.len() != b.len() {
+                    return Err(InterpreterError::InvalidOperandSize);
+                }
 
                 let or_array = b.iter().zip(a.iter()).map(|(&x1, &x2)| x1 | x2).collect();
 
@@ -259,6 +427,9 @@ impl Interpreter {
             OpCodes::OP_XOR => {
                 let a = state.stack.pop_bytes()?;
                 let b = state.stack.pop_bytes()?;
+                if a.len() != b.len() {
+                    return Err(InterpreterError::InvalidOperandSize);
+                }
 
                 let xor_array = b.iter().zip(a.iter()).map(|(&x1, &x2)| x1 ^ x2).collect();
 
@@ -274,25 +445,25 @@ impl Interpreter {
                 let a = state.stack.pop_bytes()?;
                 let b = state.stack.pop_bytes()?;
 
-                Interpreter::verify(a.eq(&b))?;
+                Interpreter::verify(a.eq(&b), InterpreterError::EqualVerify)?;
             }
             OpCodes::OP_1ADD => {
-                let a = state.stack.pop_bigint()?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bigint(a + 1)?;
             }
             OpCodes::OP_1SUB => {
-                let a = state.stack.pop_bigint()?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bigint(a - 1)?;
             }
             OpCodes::OP_NEGATE => {
-                let a = state.stack.pop_bigint()?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bigint(-a)?;
             }
             OpCodes::OP_ABS => {
-                let a = state.stack.pop_bigint()?;
+                let a = state.stack.pop_bigint(config)?;
 
                 let pos = match a < BigInt::from(0) {
                     true => a.neg(),
@@ -302,125 +473,150 @@ impl Interpreter {
                 state.stack.push_bigint(pos)?;
             }
             OpCodes::OP_NOT => {
-                let a = state.stack.pop_number()?;
+                let a = state.stack.pop_bigint(config)?;
 
-                let notted = match a {
-                    0 => 1,
-                    _ => 0,
-                };
-
-                state.stack.push_number(notted)?;
+                state.stack.push_bool(a.is_zero())?;
             }
             OpCodes::OP_0NOTEQUAL => {
-                let a = state.stack.pop_number()?;
-
-                let notted = match a {
-                    0 => 0,
-                    _ => 1,
-                };
+                let a = state.stack.pop_bigint(config)?;
 
-                state.stack.push_number(notted)?;
+                state.stack.push_bool(!a.is_zero())?;
             }
             OpCodes::OP_ADD => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
-                let sum = a + b;
-                state.stack.push(sum.to_signed_bytes_le());
+                state.stack.push_bigint(a + b)?;
             }
             OpCodes::OP_SUB => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bigint(a - b)?;
             }
             OpCodes::OP_MUL => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bigint(a * b)?;
             }
             OpCodes::OP_DIV => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
+
+                if b.is_zero() {
+                    return Err(InterpreterError::DivByZero);
+                }
 
                 state.stack.push_bigint(a / b)?;
             }
             OpCodes::OP_MOD => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
+
+                if b.is_zero() {
+                    return Err(InterpreterError::ModByZero);
+                }
 
                 state.stack.push_bigint(a % b)?;
             }
-            OpCodes::OP_LSHIFT => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_number()?;
+            OpCodes::OP_LSHIFT | OpCodes::OP_RSHIFT => {
+                state.stack.require(2)?;
+                let n = shift_amount(&state.stack.pop_bigint(config)?)?;
+                let x = state.stack.pop_bytes()?;
+
+                state.stack.push_bytes(shift_bytes(&x, n, *opcode == OpCodes::OP_LSHIFT));
+            }
+            &OpCodes::OP_LSHIFTNUM if config.flags.chronicle => {
+                state.stack.require(2)?;
+                let b = shift_amount(&state.stack.pop_bigint(config)?)?;
+                let a = state.stack.pop_bigint(config)?;
 
+                if !a.is_zero() && a.bits().saturating_add(b as u64) > config.max_script_num_length.saturating_mul(8) as u64 {
+                    return Err(InterpreterError::NumberOutOfRange);
+                }
                 state.stack.push_bigint(a << b)?;
             }
-            OpCodes::OP_RSHIFT => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_number()?;
+            &OpCodes::OP_RSHIFTNUM if config.flags.chronicle => {
+                state.stack.require(2)?;
+                let b = shift_amount(&state.stack.pop_bigint(config)?)?;
+                let a = state.stack.pop_bigint(config)?;
 
-                state.stack.push_bigint(a >> b)?;
+                // Shifts the magnitude so negative numbers round towards zero
+                state.stack.push_bigint(BigInt::from_biguint(a.sign(), a.magnitude() >> b))?;
             }
             OpCodes::OP_BOOLAND => {
-                let a = state.stack.pop_bool()?;
-                let b = state.stack.pop_bool()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
-                state.stack.push_bool(a && b)?;
+                state.stack.push_bool(!a.is_zero() && !b.is_zero())?;
             }
             OpCodes::OP_BOOLOR => {
-                let a = state.stack.pop_bool()?;
-                let b = state.stack.pop_bool()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
-                state.stack.push_bool(a || b)?;
+                state.stack.push_bool(!a.is_zero() || !b.is_zero())?;
             }
             OpCodes::OP_NUMEQUAL => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bool(a == b)?;
             }
             OpCodes::OP_NUMEQUALVERIFY => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
-                Interpreter::verify(a == b)?;
+                Interpreter::verify(a == b, InterpreterError::NumEqualVerify)?;
             }
             OpCodes::OP_NUMNOTEQUAL => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bool(a != b)?;
             }
             OpCodes::OP_LESSTHAN => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bool(a < b)?;
             }
             OpCodes::OP_LESSTHANOREQUAL => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bool(a <= b)?;
             }
             OpCodes::OP_GREATERTHAN => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bool(a > b)?;
             }
             OpCodes::OP_GREATERTHANOREQUAL => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bool(a >= b)?;
             }
             OpCodes::OP_MIN => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 let smallest = match a > b {
                     true => b,
@@ -430,8 +626,9 @@ impl Interpreter {
                 state.stack.push_bigint(smallest)?;
             }
             OpCodes::OP_MAX => {
-                let a = state.stack.pop_bigint()?;
-                let b = state.stack.pop_bigint()?;
+                state.stack.require(2)?;
+                let b = state.stack.pop_bigint(config)?;
+                let a = state.stack.pop_bigint(config)?;
 
                 let biggest = match a < b {
                     true => b,
@@ -441,41 +638,48 @@ impl Interpreter {
                 state.stack.push_bigint(biggest)?;
             }
             OpCodes::OP_WITHIN => {
-                let x = state.stack.pop_bigint()?;
-                let min = state.stack.pop_bigint()?;
-                let max = state.stack.pop_bigint()?;
+                state.stack.require(3)?;
+                let max = state.stack.pop_bigint(config)?;
+                let min = state.stack.pop_bigint(config)?;
+                let x = state.stack.pop_bigint(config)?;
 
-                state.stack.push_bool(x >= min && x <= max)?;
+                state.stack.push_bool(min <= x && x < max)?;
             }
             OpCodes::OP_NUM2BIN => {
-                let length = state.stack.pop_number()?;
+                state.stack.require(2)?;
+                let length = state.stack.pop_bigint(config)?;
                 let bytes = state.stack.pop_bytes()?;
 
-                if length < 1 || length < bytes.len() as i32 {
-                    return Err(InterpreterError::InvalidStackOperation("OP_NUM2BIN failed, provide length was out of range"));
-                }
-
-                // Fill the data in, extend the buffer to the length of the length parameter
-                let (sign, mut bin_array) = stack_trait::to_bigint(&bytes)?.to_bytes_le();
-                bin_array.resize(length as usize, 0);
-                let bin_array_len = bin_array.len();
+                let length = match length.to_usize() {
+                    Some(v) if v <= config.max_script_element_size && v <= config.max_stack_memory_usage => v,
+                    _ => return Err(InterpreterError::PushSize),
+                };
 
-                let full = bin_array[bin_array_len - 1] & 0x80;
-                if full > 0 {
-                    bin_array.push(0x00);
+                let mut bin_array = ScriptNum::minimally_encode(&bytes);
+                if bin_array.len() > length {
+                    return Err(InterpreterError::ImpossibleEncoding);
                 }
 
-                // // Add 0x00 to the end if last byte is positive sign
-                match sign {
-                    Sign::Plus => bin_array[bin_array_len - 1] |= 0x00,
-                    Sign::Minus => bin_array[bin_array_len - 1] |= 0x80,
-                    Sign::NoSign => return Err(InterpreterError::InvalidStackOperation("OP_NUM2BIN failed, invalid sign on bigint.")),
-                };
+                // Move the sign bit to the last byte of the padded result
+                if bin_array.len() < length {
+                    let sign = bin_array.last_mut().map_or(0, |last| {
+                        let sign = *last & 0x80;
+                        *last &= 0x7f;
+                        sign
+                    });
+                    bin_array.resize(length, 0);
+                    bin_array[length - 1] |= sign;
+                }
                 state.stack.push_bytes(bin_array);
             }
             OpCodes::OP_BIN2NUM => {
-                let bigint = state.stack.pop_bigint()?;
-                state.stack.push_bigint(bigint)?;
+                let bytes = state.stack.pop_bytes()?;
+                state.stack.push_bytes(ScriptNum::minimally_encode(&bytes));
+
+                // The input may be padded, only the minimally encoded result has to fit in a number
+                if state.stack.last().map_or(0, |x| x.len()) > config.max_script_num_length {
+                    return Err(InterpreterError::NumberOutOfRange);
+                }
             }
             OpCodes::OP_RIPEMD160 => {
                 let data = state.stack.pop_bytes()?;
@@ -509,70 +713,93 @@ impl Interpreter {
             }
             OpCodes::OP_CODESEPARATOR => state.codeseparator_offset = script_index + 1,
             OpCodes::OP_CHECKSIG => {
-                let mut txscript = match tx {
+                let txscript = match tx {
                     Some(x) => x,
                     None => return Err(InterpreterError::RequiresTransaction(&OpCodes::OP_CHECKSIG)),
                 };
 
-                let is_signature_valid = checksig(state, &mut txscript)?;
+                let is_signature_valid = checksig(state, &txscript, config, observers)?;
                 state.stack.push_bool(is_signature_valid)?;
             }
             OpCodes::OP_CHECKSIGVERIFY => {
-                let mut txscript = match tx {
+                let txscript = match tx {
                     Some(x) => x,
                     None => return Err(InterpreterError::RequiresTransaction(&OpCodes::OP_CHECKSIGVERIFY)),
                 };
 
-                let is_signature_valid = checksig(state, &mut txscript)?;
-                Interpreter::verify(is_signature_valid)?
+                let is_signature_valid = checksig(state, &txscript, config, observers)?;
+                Interpreter::verify(is_signature_valid, InterpreterError::CheckSigVerify)?
             }
             OpCodes::OP_CHECKMULTISIG => {
-                let mut txscript = match tx {
+                let txscript = match tx {
                     Some(x) => x,
                     None => return Err(InterpreterError::RequiresTransaction(&OpCodes::OP_CHECKMULTISIG)),
                 };
 
-                let is_multisig_valid = multisig(state, &mut txscript)?;
+                let is_multisig_valid = multisig(state, &txscript, config, observers)?;
                 state.stack.push_bool(is_multisig_valid)?
             }
             OpCodes::OP_CHECKMULTISIGVERIFY => {
-                let mut txscript = match tx {
+                let txscript = match tx {
                     Some(x) => x,
                     None => return Err(InterpreterError::RequiresTransaction(&OpCodes::OP_CHECKMULTISIGVERIFY)),
                 };
 
-                let is_multisig_valid = multisig(state, &mut txscript)?;
-                Interpreter::verify(is_multisig_valid)?
-            }
-
-            // TODO: Allow enabling of specific opcodes or opcode feature sets
-            OpCodes::OP_CHECKLOCKTIMEVERIFY => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_CHECKLOCKTIMEVERIFY)),
-            OpCodes::OP_CHECKSEQUENCEVERIFY => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_CHECKSEQUENCEVERIFY)),
-
-            OpCodes::OP_VER => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_VER)),
-            OpCodes::OP_VERIF => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_VERIF)),
-            OpCodes::OP_VERNOTIF => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_VERNOTIF)),
-            OpCodes::OP_RESERVED => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_RESERVED)),
-            OpCodes::OP_RESERVED1 => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_RESERVED1)),
-            OpCodes::OP_RESERVED2 => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_RESERVED2)),
-            OpCodes::OP_NOP1 => {}
-            OpCodes::OP_NOP4 => {}
-            OpCodes::OP_NOP5 => {}
-            OpCodes::OP_NOP6 => {}
-            OpCodes::OP_NOP7 => {}
-            OpCodes::OP_NOP8 => {}
-            OpCodes::OP_NOP9 => {}
-            OpCodes::OP_NOP10 => {}
-            OpCodes::OP_2MUL => {
-                let a = state.stack.pop_bigint()?;
+                let is_multisig_valid = multisig(state, &txscript, config, observers)?;
+                Interpreter::verify(is_multisig_valid, InterpreterError::CheckMultisigVerify)?
+            }
+
+            OpCodes::OP_CHECKLOCKTIMEVERIFY if config.flags.check_locktime_verify && !config.flags.is_post_genesis() => {
+                let txscript = match tx {
+                    Some(x) => x,
+                    None => return Err(InterpreterError::RequiresTransaction(&OpCodes::OP_CHECKLOCKTIMEVERIFY)),
+                };
+
+                let locktime = peek_locktime(state, config)?;
+                if !check_locktime(&txscript, locktime) {
+                    return Err(InterpreterError::UnsatisfiedLocktime);
+                }
+            }
+            OpCodes::OP_CHECKSEQUENCEVERIFY if config.flags.check_sequence_verify && !config.flags.is_post_genesis() => {
+                let txscript = match tx {
+                    Some(x) => x,
+                    None => return Err(InterpreterError::RequiresTransaction(&OpCodes::OP_CHECKSEQUENCEVERIFY)),
+                };
+
+                let sequence = peek_locktime(state, config)?;
+                // Relative lock times can be disabled, in which case this is a NOP
+                if sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG == 0 && !check_sequence(&txscript, sequence) {
+                    return Err(InterpreterError::UnsatisfiedLocktime);
+                }
+            }
+            // After Genesis, or without their flags, these are OP_NOP2 and OP_NOP3
+            OpCodes::OP_CHECKLOCKTIMEVERIFY | OpCodes::OP_CHECKSEQUENCEVERIFY => {
+                if config.flags.discourage_upgradable_nops {
+                    return Err(InterpreterError::DiscourageUpgradableNops(*opcode));
+                }
+            }
+
+            // After Chronicle, OP_NOP4 to OP_NOP8 are OP_SUBSTR to OP_RSHIFTNUM
+            OpCodes::OP_NOP1 | OpCodes::OP_NOP4 | OpCodes::OP_NOP5 | OpCodes::OP_NOP6 | OpCodes::OP_NOP7 | OpCodes::OP_NOP8 | OpCodes::OP_NOP9 | OpCodes::OP_NOP10 => {
+                if config.flags.discourage_upgradable_nops {
+                    return Err(InterpreterError::DiscourageUpgradableNops(*opcode));
+                }
+            }
+            OpCodes::OP_VER if config.flags.chronicle => state.stack.push_bytes(tx_version(tx.as_ref(), &OpCodes::OP_VER)?),
+            // Reserved opcodes are only valid in branches that are not executed
+            OpCodes::OP_VER | OpCodes::OP_VERIF | OpCodes::OP_VERNOTIF | OpCodes::OP_RESERVED | OpCodes::OP_RESERVED1 | OpCodes::OP_RESERVED2 => return Err(InterpreterError::InvalidOpcode(*opcode)),
+            OpCodes::OP_2MUL if config.flags.chronicle => {
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bigint(a * 2)?;
             }
-            OpCodes::OP_2DIV => {
-                let a = state.stack.pop_bigint()?;
+            OpCodes::OP_2DIV if config.flags.chronicle => {
+                let a = state.stack.pop_bigint(config)?;
 
                 state.stack.push_bigint(a / 2)?;
             }
+            OpCodes::OP_2MUL => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_2MUL)),
+            OpCodes::OP_2DIV => return Err(InterpreterError::DisabledOpCode(&OpCodes::OP_2DIV)),
 
             _ => return Err(InterpreterError::InvalidOpcode(*opcode)),
         };
@@ -581,91 +808,256 @@ impl Interpreter {
     }
 }
 
-fn checksig(state: &mut State, txscript: &mut TxScript) -> Result<bool, InterpreterError> {
+/// Transaction version as pushed by OP_VER, little endian like the serialised transaction
+fn tx_version(tx: Option<&TxScript>, opcode: &'static OpCodes) -> Result<Vec<u8>, InterpreterError> {
+    match tx {
+        Some(txscript) => Ok(txscript.tx.get_version().to_le_bytes().to_vec()),
+        None => Err(InterpreterError::RequiresTransaction(opcode)),
+    }
+}
+
+/// Shift amounts must not be negative, amounts too large for a usize shift everything out
+fn shift_amount(n: &BigInt) -> Result<usize, InterpreterError> {
+    match n.sign() {
+        Sign::Minus => Err(InterpreterError::InvalidNumberRange),
+        _ => Ok(n.to_usize().unwrap_or(usize::MAX)),
+    }
+}
+
+/// Shifts the bits of a byte string as a big endian bit sequence, filling with zeroes and keeping its length
+fn shift_bytes(data: &[u8], n: usize, left: bool) -> Vec<u8> {
+    let (byte_shift, bit_shift) = (n / 8, n % 8);
+    let byte = |i: Option<usize>| -> u32 { i.and_then(|i| data.get(i)).map_or(0, |b| *b as u32) };
+
+    (0..data.len())
+        .map(|i| match left {
+            true => {
+                let j = i.checked_add(byte_shift);
+                let window = byte(j) << 8 | byte(j.and_then(|j| j.checked_add(1)));
+                (window << bit_shift >> 8) as u8
+            }
+            false => {
+                let j = i.checked_sub(byte_shift);
+                let window = byte(j.and_then(|j| j.checked_sub(1))) << 8 | byte(j);
+                (window >> bit_shift) as u8
+            }
+        })
+        .collect()
+}
+
+const LOCKTIME_THRESHOLD: i64 = 500_000_000;
+const SEQUENCE_LOCKTIME_DISABLE_FLAG: i64 = 1 << 31;
+const SEQUENCE_LOCKTIME_TYPE_FLAG: i64 = 1 << 22;
+const SEQUENCE_LOCKTIME_MASK: i64 = 0x0000ffff;
+
+/// Reads the lock time on top of the stack without removing it, lock times may be up to 5 bytes long.
+fn peek_locktime(state: &State, config: &ScriptConfig) -> Result<i64, InterpreterError> {
+    let top = state.stack.last().ok_or(InterpreterError::EmptyStack)?;
+    // Lock times are 5 byte numbers so they can hold the full range of a u32
+    let locktime = ScriptNum::from_bytes(top, 5, config.flags.minimal_data)?.to_i64().ok_or(InterpreterError::NumberOutOfRange)?;
+    if locktime < 0 {
+        return Err(InterpreterError::NegativeLocktime);
+    }
+
+    Ok(locktime)
+}
+
+/// BIP65, the transaction must be locked until at least the given block height or timestamp.
+fn check_locktime(txscript: &TxScript, locktime: i64) -> bool {
+    let tx_locktime = txscript.tx.get_n_locktime() as i64;
+
+    // Block heights and timestamps cannot be compared
+    if (tx_locktime < LOCKTIME_THRESHOLD) != (locktime < LOCKTIME_THRESHOLD) {
+        return false;
+    }
+
+    if locktime > tx_locktime {
+        return false;
+    }
+
+    // A final input would let the transaction be mined regardless of nLockTime
+    match txscript.tx.get_input(txscript.input_index) {
+        Some(txin) => txin.get_sequence() != u32::MAX,
+        None => false,
+    }
+}
+
+/// BIP112, the input must have a relative lock time of at least the given number of blocks or seconds.
+fn check_sequence(txscript: &TxScript, sequence: i64) -> bool {
+    let tx_sequence = match txscript.tx.get_input(txscript.input_index) {
+        Some(txin) => txin.get_sequence() as i64,
+        None => return false,
+    };
+
+    // Relative lock times are only enforced from version 2 transactions
+    if txscript.tx.get_version() < 2 || tx_sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0 {
+        return false;
+    }
+
+    let mask = SEQUENCE_LOCKTIME_TYPE_FLAG | SEQUENCE_LOCKTIME_MASK;
+    let tx_sequence = tx_sequence & mask;
+    let sequence = sequence & mask;
+
+    // Block counts and time intervals cannot be compared
+    if (tx_sequence < SEQUENCE_LOCKTIME_TYPE_FLAG) != (sequence < SEQUENCE_LOCKTIME_TYPE_FLAG) {
+        return false;
+    }
+
+    sequence <= tx_sequence
+}
+
+fn checksig(state: &mut State, txscript: &TxScript, config: &ScriptConfig, observers: &Observers) -> Result<bool, InterpreterError> {
     let public_key = state.stack.pop_bytes()?;
     let signature = state.stack.pop_bytes()?;
-    let sighash_byte = signature.last().cloned();
-    println!("Sighash Byte: {:#?}", sighash_byte);
-    let sighash = match sighash_byte {
-        Some(x) => SigHash::try_from(x).map_err(|_| InterpreterError::FailedToConvertSighash)?,
-        None => return Err(InterpreterError::InvalidStackOperation("could not read Sighash flag from signature")),
-    };
-    let preimage = calculate_sighash_preimage(txscript, sighash, state.codeseparator_offset)?;
-    let is_signature_valid = verify_tx_signature(&preimage, txscript, &signature, &public_key)?;
+
+    encoding_checks::check_signature_encoding(&signature, &config.flags)?;
+    encoding_checks::check_pubkey_encoding(&public_key, &config.flags)?;
+
+    let is_signature_valid = check_sig(txscript, &signature, &public_key, state.codeseparator_offset, &[&signature], observers)?;
+
+    if !is_signature_valid && config.flags.null_fail && !signature.is_empty() {
+        return Err(InterpreterError::NullFail);
+    }
+
     Ok(is_signature_valid)
 }
 
-fn multisig(state: &mut State, txscript: &mut TxScript) -> Result<bool, InterpreterError> {
-    let pubkey_count = state.stack.pop_number()?;
-    if pubkey_count < 1 {
-        return Err(InterpreterError::InvalidStackOperation("PubKey count must be a positive number"));
+/// Reads a count operand of OP_CHECKMULTISIG at the given depth from the top of the stack.
+fn peek_count(state: &State, depth: usize, config: &ScriptConfig) -> Result<i64, InterpreterError> {
+    let data = state.stack.len().checked_sub(depth).and_then(|i| state.stack.get(i)).ok_or(InterpreterError::EmptyStack)?;
+    ScriptNum::from_stack_element(data, config)?.to_i64().ok_or(InterpreterError::NumberOutOfRange)
+}
+
+/// Follows the node: signatures and public keys are consumed from the top of the stack down, so signatures
+/// must be in the same order as their public keys, and each public key is only tried once.
+fn multisig(state: &mut State, txscript: &TxScript, config: &ScriptConfig, observers: &Observers) -> Result<bool, InterpreterError> {
+    let keys_count = peek_count(state, 1, config)?;
+    if keys_count < 0 || keys_count as u64 > config.max_pubkeys_per_multisig as u64 {
+        return Err(InterpreterError::PubKeyCount);
     }
+    let mut keys_count = keys_count as usize;
 
-    // Slice the correct amount of pubkeys off the stack in reverse order so we can pop them.
-    let mut pubkeys = state.stack.split_off(state.stack.len() - pubkey_count as usize);
-    pubkeys.reverse();
+    state.op_count = state.op_count.saturating_add(keys_count);
+    if state.op_count > config.max_ops_per_script {
+        return Err(InterpreterError::OpCount);
+    }
 
-    println!("Pubkeys: {:?}", pubkeys.iter().map(|x| x.to_hex()).collect::<Vec<String>>());
+    // Depths from the top of the stack, the top item is at depth 1
+    let mut ikey = 2;
+    let sigs_depth = keys_count + 2;
+    let sigs_count = peek_count(state, sigs_depth, config)?;
+    if sigs_count < 0 || sigs_count as usize > keys_count {
+        return Err(InterpreterError::SigCount);
+    }
+    let mut sigs_count = sigs_count as usize;
 
-    let sig_count = state.stack.pop_number()?;
-    if sig_count < 1 {
-        return Err(InterpreterError::InvalidStackOperation("Signature count must be a positive number"));
+    let mut isig = sigs_depth + 1;
+    let args_count = sigs_depth + sigs_count;
+    // The extra dummy element must be on the stack too
+    if state.stack.len() < args_count + 1 {
+        return Err(InterpreterError::EmptyStack);
     }
 
-    if pubkey_count < sig_count {
-        return Err(InterpreterError::InvalidStackOperation("PubKey count must be greater than or equal to Signature count"));
+    let stack_at = |depth: usize| &state.stack[state.stack.len() - depth];
+    let signatures = (sigs_depth + 1..=args_count).map(|depth| stack_at(depth).as_slice()).collect::<Vec<_>>();
+    let mut success = true;
+    while success && sigs_count > 0 {
+        let signature = stack_at(isig);
+        let public_key = stack_at(ikey);
+
+        encoding_checks::check_signature_encoding(signature, &config.flags)?;
+        encoding_checks::check_pubkey_encoding(public_key, &config.flags)?;
+
+        if check_sig(txscript, signature, public_key, state.codeseparator_offset, &signatures, observers)? {
+            isig += 1;
+            sigs_count -= 1;
+        }
+        ikey += 1;
+        keys_count -= 1;
+
+        // More signatures left than keys means too many signatures have failed
+        if sigs_count > keys_count {
+            success = false;
+        }
     }
 
-    let sigs = state.stack.split_off(state.stack.len() - sig_count as usize);
+    // Only signatures may be non empty on failure, the counts and public keys are never checked
+    if !success && config.flags.null_fail && (sigs_depth + 1..=args_count).any(|depth| !stack_at(depth).is_empty()) {
+        return Err(InterpreterError::NullFail);
+    }
 
-    // Implement a bug to accidentally pop another item off the stack :-)
-    state.stack.pop_bytes()?;
+    state.stack.truncate(state.stack.len() - args_count);
 
-    let mut successes = 0;
-    // Compare all Signatures against all public keys
-    for sig in sigs {
-        let sighash = match sig.last().cloned() {
-            Some(x) => SigHash::try_from(x).map_err(|_| InterpreterError::FailedToConvertSighash)?,
-            None => return Err(InterpreterError::InvalidStackOperation("could not read Sighash flag from signature")),
-        };
+    // A bug in the original implementation consumes one extra element, it must be empty under NULLDUMMY
+    let dummy = state.stack.pop_bytes()?;
+    if config.flags.null_dummy && !dummy.is_empty() {
+        return Err(InterpreterError::SigNullDummy);
+    }
 
-        let preimage = calculate_sighash_preimage(txscript, sighash, state.codeseparator_offset)?;
+    Ok(success)
+}
 
-        // Pop each pubkey because they are only to be compared against once.
-        while let Some(public_key) = pubkeys.pop() {
-            let is_signature_valid = verify_tx_signature(&preimage, txscript, &sig, &public_key)?;
-            if is_signature_valid {
-                successes += 1;
-                break;
-            }
+/// Checks a signature against a public key, malformed signatures and public keys fail without an error like they do in the node.
+/// `signatures` are the signatures of the opcode, which are deleted from the signed script for the legacy sighash.
+fn check_sig(txscript: &TxScript, signature: &[u8], public_key: &[u8], codeseparator_offset: usize, signatures: &[&[u8]], observers: &Observers) -> Result<bool, InterpreterError> {
+    // An empty signature is a valid way to fail a signature check
+    let sighash = match signature.last().map(|x| SigHash::try_from(*x)) {
+        Some(Ok(v)) => v,
+        _ => return Ok(false),
+    };
+
+    let digest = match txscript.tx.is_sighash_single_bug(txscript.input_index, sighash) {
+        true => SIGHASH_SINGLE_BUG_DIGEST.to_vec(),
+        false => {
+            let preimage = calculate_sighash_preimage(txscript, sighash, codeseparator_offset, signatures)?;
+            observers.notify(|o| o.on_sighash_preimage(sighash, &preimage));
+            Hash::sha_256d(&preimage).to_bytes()
         }
-    }
-    Ok(successes == sig_count)
+    };
+
+    let is_signature_valid = verify_tx_signature(&digest, signature, public_key).unwrap_or(false);
+    observers.notify(|o| o.on_signature_checked(signature, public_key, is_signature_valid));
+
+    Ok(is_signature_valid)
 }
 
-fn verify_tx_signature(preimage: &[u8], txscript: &mut TxScript, signature: &[u8], public_key: &[u8]) -> Result<bool, InterpreterError> {
-    let sighash_sig = SighashSignature::from_bytes_impl(signature, preimage)?;
-    let is_signature_valid = txscript.tx._verify(&PublicKey::from_bytes_impl(public_key)?, &sighash_sig, false) | txscript.tx._verify(&PublicKey::from_bytes_impl(public_key)?, &sighash_sig, true);
+fn verify_tx_signature(digest: &[u8], signature: &[u8], public_key: &[u8]) -> Result<bool, InterpreterError> {
+    let mut sighash_sig = SighashSignature::from_bytes_impl(signature, &[])?;
+    // Consensus accepts high S signatures, LOW_S is enforced separately by the encoding checks
+    if let Some(low_s_sig) = sighash_sig.signature.sig.normalize_s() {
+        sighash_sig.signature.sig = low_s_sig;
+    }
+    let public_key = PublicKey::from_bytes_impl(public_key)?;
+    let reversed_digest: Vec<u8> = digest.iter().rev().copied().collect();
+    let is_signature_valid = ECDSA::verify_hashbuf(digest, &public_key, &sighash_sig.signature).unwrap_or(false) | ECDSA::verify_hashbuf(&reversed_digest, &public_key, &sighash_sig.signature).unwrap_or(false);
     Ok(is_signature_valid)
 }
 
-fn calculate_sighash_preimage(txscript: &mut TxScript, sighash: SigHash, codeseparator_offset: usize) -> Result<Vec<u8>, InterpreterError> {
+fn calculate_sighash_preimage(txscript: &TxScript, sighash: SigHash, codeseparator_offset: usize, signatures: &[&[u8]]) -> Result<Vec<u8>, InterpreterError> {
     let txin = match txscript.tx.get_input(txscript.input_index) {
         Some(v) => v,
-        _ => return Err(InterpreterError::InvalidStackOperation("could not get TxIn at the provided index")),
+        _ => return Err(InterpreterError::NoTxInProvided),
     };
 
-    let unlock_script_len = txin.get_unlocking_script().to_script_bits().len();
-    let script_offset = codeseparator_offset.saturating_sub(unlock_script_len);
-    let unsigned_script = match txin.get_locking_script() {
-        Some(v) => Script::from_script_bits(v.to_script_bits()[script_offset..].to_vec()),
-        None => return Err(InterpreterError::InvalidStackOperation("TxIn at given index does not have locking script provided")),
+    let script_offset = codeseparator_offset.saturating_sub(txscript.locking_script_offset);
+    let mut unsigned_script = match txin.get_locking_script() {
+        Some(v) => Script::from_script_bits(v.to_flat_script_bits()[script_offset..].to_vec()),
+        None => return Err(InterpreterError::MissingLockingScript),
     };
-    println!("Unsigned script: {}", unsigned_script.to_asm_string());
+
+    // CleanupScriptCode in the node, every signature of the opcode without SIGHASH_FORKID is deleted from the signed script.
+    // An empty signature is pushed as OP_0, so it deletes every OP_0
+    for signature in signatures.iter().filter(|x| x.last().is_none_or(|x| x & SigHash::FORKID as u8 == 0)) {
+        let push = match signature.is_empty() {
+            true => vec![OpCodes::OP_0 as u8],
+            false => Script::encode_pushdata(signature)?,
+        };
+        unsigned_script.find_and_delete(&Script::from_bytes(&push)?);
+    }
 
     let satoshis = match txscript.tx.get_input(txscript.input_index).and_then(|x| x.get_satoshis()) {
         Some(v) => v,
-        _ => return Err(InterpreterError::InvalidStackOperation("TxIn at given index does not have satoshis provided")),
+        _ => return Err(InterpreterError::MissingSatoshis),
     };
 
     txscript
@@ -0,0 +1,114 @@
+use num_bigint::{BigInt, Sign};
+use num_traits::ToPrimitive;
+
+use super::{errors::InterpreterError, script_config::ScriptConfig};
+
+/// A number operand, encoded on the stack as little endian sign magnitude where zero is the empty array.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScriptNum(BigInt);
+
+impl ScriptNum {
+    /// Decodes a stack element, it must be at most `max_length` bytes and, if `require_minimal` is set, minimally encoded.
+    pub fn from_bytes(data: &[u8], max_length: usize, require_minimal: bool) -> Result<ScriptNum, InterpreterError> {
+        if data.len() > max_length {
+            return Err(InterpreterError::NumberOutOfRange);
+        }
+
+        if require_minimal && !ScriptNum::is_minimally_encoded(data) {
+            return Err(InterpreterError::NonMinimalNumber);
+        }
+
+        Ok(ScriptNum::decode(data))
+    }
+
+    /// Decodes a stack element with the number length limit of the config, minimal encoding is required by `minimal_data`.
+    pub fn from_stack_element(data: &[u8], config: &ScriptConfig) -> Result<ScriptNum, InterpreterError> {
+        ScriptNum::from_bytes(data, config.max_script_num_length, config.flags.minimal_data)
+    }
+
+    /// Encodes the number minimally.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (sign, mut bytes) = self.0.to_bytes_le();
+        if sign == Sign::NoSign {
+            return vec![];
+        }
+
+        if bytes[bytes.len() - 1] & 0x80 == 0x80 {
+            bytes.push(match sign {
+                Sign::Minus => 0x80,
+                _ => 0x00,
+            });
+        } else if sign == Sign::Minus {
+            let len = bytes.len();
+            bytes[len - 1] |= 0x80;
+        }
+
+        bytes
+    }
+
+    /// A number is minimally encoded when its last byte is needed, either for its value or to hold the sign bit.
+    /// Negative zero is not minimally encoded.
+    #[must_use]
+    pub fn is_minimally_encoded(data: &[u8]) -> bool {
+        match data {
+            [] => true,
+            [.., last] if last & 0x7f != 0 => true,
+            [.., second_last, _] => second_last & 0x80 != 0,
+            [_] => false,
+        }
+    }
+
+    /// Removes any padding from an encoded number, like OP_BIN2NUM.
+    #[must_use]
+    pub fn minimally_encode(data: &[u8]) -> Vec<u8> {
+        ScriptNum::decode(data).to_bytes()
+    }
+
+    /// Get the value of the number.
+    #[must_use]
+    pub fn value(&self) -> &BigInt {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn into_bigint(self) -> BigInt {
+        self.0
+    }
+
+    #[must_use]
+    pub fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    fn decode(data: &[u8]) -> ScriptNum {
+        let mut data = data.to_vec();
+        let sign = match data.last_mut() {
+            Some(last) if *last & 0x80 == 0x80 => {
+                *last &= 0x7f;
+                Sign::Minus
+            }
+            _ => Sign::Plus,
+        };
+
+        ScriptNum(BigInt::from_bytes_le(sign, &data))
+    }
+}
+
+impl From<BigInt> for ScriptNum {
+    fn from(v: BigInt) -> ScriptNum {
+        ScriptNum(v)
+    }
+}
+
+impl From<i64> for ScriptNum {
+    fn from(v: i64) -> ScriptNum {
+        ScriptNum(BigInt::from(v))
+    }
+}
+
+impl From<ScriptNum> for BigInt {
+    fn from(v: ScriptNum) -> BigInt {
+        v.0
+    }
+}
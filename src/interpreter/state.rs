@@ -3,15 +3,27 @@ use std::fmt::Display;
 use crate::{OpCodes, ToHex};
 use serde::{Deserialize, Serialize};
 
-use crate::Status;
+use crate::{Status, ELEMENT_OVERHEAD};
 
-#[derive(Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct State {
     pub stack: Vec<Vec<u8>>,
     pub alt_stack: Vec<Vec<u8>>,
     pub status: Status,
     pub executed_opcodes: Vec<OpCodes>,
     pub codeseparator_offset: usize,
+    /// Number of non-push operations executed so far
+    pub op_count: usize,
+    /// Condition stack of the enclosing OP_IF/OP_NOTIF blocks, opcodes only execute while every entry is true
+    pub vf_exec: Vec<bool>,
+    /// Whether an OP_ELSE was seen for each entry of vf_exec
+    pub vf_else: Vec<bool>,
+    /// An OP_RETURN was executed inside a conditional block after Genesis, no further opcodes are executed
+    pub non_top_level_return: bool,
+    /// Memory used by the stack and alt stack, each element counts its length plus ELEMENT_OVERHEAD bytes
+    pub stack_memory_usage: usize,
+    /// Running execution cost, every evaluated script bit costs 1 plus the number of bytes it grew the stack memory usage by
+    pub cost: u64,
 }
 
 impl State {
@@ -20,6 +32,16 @@ impl State {
     pub fn stack(&self) -> &[Vec<u8>] {
         self.stack.as_ref()
     }
+
+    pub(crate) fn calculate_stack_memory_usage(&self) -> usize {
+        self.stack.iter().chain(self.alt_stack.iter()).map(|x| x.len() + ELEMENT_OVERHEAD).sum()
+    }
+
+    /// Whether the current opcode is in an executed branch.
+    #[must_use]
+    pub fn is_executing(&self) -> bool {
+        !self.non_top_level_return && self.vf_exec.iter().all(|x| *x)
+    }
 }
 
 impl Display for State {
@@ -0,0 +1,148 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{BSVErrors, Transaction, TxOut};
+
+use super::{
+    script_config::ScriptConfig,
+    verify::{InputVerdict, ScriptFailure},
+};
+
+/// Verifies every input of a transaction against the outputs they spend.
+///
+/// The outputs are attached to the inputs of a single copy of the transaction, which every input is evaluated against,
+/// so signature checks of every input share the same sighash cache.
+#[derive(Debug, Clone)]
+pub struct TransactionVerifier {
+    tx: Arc<Transaction>,
+    config: ScriptConfig,
+    threads: usize,
+}
+
+impl TransactionVerifier {
+    /// `prev_outputs` maps the outpoint of each input, as returned by `TxIn::get_outpoint_bytes(Some(true))`, to the output it spends.
+    pub fn new(tx: &Transaction, prev_outputs: &HashMap<Vec<u8>, TxOut>) -> Result<TransactionVerifier, BSVErrors> {
+        let outputs = (0..tx.get_ninputs())
+            .filter_map(|index| tx.get_input(index).map(|txin| (index, txin)))
+            .map(|(index, txin)| {
+                prev_outputs
+                    .get(&txin.get_outpoint_bytes(Some(true)))
+                    .ok_or_else(|| BSVErrors::OutOfBounds(format!("No previous output provided for input {} spending {}", index, txin.get_outpoint_hex(Some(true)))))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TransactionVerifier::from_prev_outputs(tx, &outputs))
+    }
+
+    /// `prev_outputs` must contain the spent output for each input in order.
+    pub(crate) fn from_prev_outputs(tx: &Transaction, prev_outputs: &[&TxOut]) -> TransactionVerifier {
+        let mut tx = tx.clone();
+        tx.clear_hash_cache();
+        for (index, prev_output) in prev_outputs.iter().enumerate() {
+            if let Some(mut txin) = tx.get_input(index) {
+                txin.set_locking_script(&prev_output.script_pub_key);
+                txin.set_satoshis(prev_output.value);
+                tx.set_input(index, &txin);
+            }
+        }
+
+        TransactionVerifier {
+            tx: Arc::new(tx),
+            config: ScriptConfig::default(),
+            threads: 1,
+        }
+    }
+
+    pub fn set_config(&mut self, config: &ScriptConfig) {
+        self.config = config.clone();
+    }
+
+    /// Number of threads inputs are verified on, defaults to 1. Ignored on wasm, where inputs are always verified in order.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    #[must_use]
+    pub fn config(&self) -> ScriptConfig {
+        self.config.clone()
+    }
+
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn verify_input(&self, index: usize) -> Result<InputVerdict, BSVErrors> {
+        match index < self.tx.get_ninputs() {
+            true => Ok(self.evaluate(index)),
+            false => Err(BSVErrors::OutOfBounds(format!("Could not get TxIn at index {}", index))),
+        }
+    }
+
+    /// Verifies every input, verdicts are returned in input order.
+    pub fn verify(&self) -> Vec<InputVerdict> {
+        let indexes: Vec<usize> = (0..self.tx.get_ninputs()).collect();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.threads > 1 && indexes.len() > 1 {
+            let chunk_size = indexes.len().div_ceil(self.threads);
+            return std::thread::scope(|scope| {
+                let handles: Vec<_> = indexes
+                    .chunks(chunk_size)
+                    .map(|chunk| scope.spawn(move || chunk.iter().map(|index| self.evaluate(*index)).collect::<Vec<_>>()))
+                    .collect();
+
+                handles.into_iter().flat_map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect()
+            });
+        }
+
+        indexes.iter().map(|index| self.evaluate(*index)).collect()
+    }
+
+    fn evaluate(&self, index: usize) -> InputVerdict {
+        let txin = self.tx.get_input(index).unwrap_or_default();
+        let locking_script = txin.get_locking_script().unwrap_or_default();
+        let failure: Option<ScriptFailure> = Transaction::evaluate_input(&self.tx, index, &txin.get_unlocking_script(), &locking_script, &self.config).err();
+
+        InputVerdict { input_index: index, failure }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{transaction::HASHES_CALCULATED, P2PKHAddress, PrivateKey, SigHash, Transaction, TxIn, TxOut};
+
+    use super::TransactionVerifier;
+
+    #[test]
+    fn inputs_share_one_transaction_and_sighash_cache() {
+        let private_key = PrivateKey::from_wif("L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh").unwrap();
+        let public_key = private_key.to_public_key().unwrap();
+        let address = P2PKHAddress::from_pubkey(&public_key).unwrap();
+        let prev_output = TxOut::new(1000, &address.get_locking_script().unwrap());
+
+        let mut tx = Transaction::new(2, 0);
+        let mut prev_outputs = HashMap::new();
+        for vout in 0..5 {
+            let txin = TxIn::new(&[7; 32], vout, &Default::default(), None);
+            prev_outputs.insert(txin.get_outpoint_bytes(Some(true)), prev_output.clone());
+            tx.add_input(&txin);
+        }
+        tx.add_output(&prev_output);
+        for index in 0..5 {
+            let signature = tx.sign(&private_key, SigHash::InputsOutputs, index, &prev_output.get_script_pub_key(), 1000).unwrap();
+            let mut txin = tx.get_input(index).unwrap();
+            txin.set_unlocking_script(&address.get_unlocking_script(&public_key, &signature).unwrap());
+            tx.set_input(index, &txin);
+        }
+
+        let verifier = TransactionVerifier::new(&tx, &prev_outputs).unwrap();
+        let calculated = HASHES_CALCULATED.with(|x| x.get());
+        assert!(verifier.verify().iter().all(|x| x.is_valid()));
+
+        // The prevouts, sequences and outputs are hashed once for all five inputs
+        assert_eq!(HASHES_CALCULATED.with(|x| x.get()) - calculated, 3);
+        assert_eq!(std::sync::Arc::strong_count(&verifier.tx), 1);
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use crate::{BSVErrors, Script, Transaction, TxOut};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    debugger::History,
+    errors::{InterpreterError, ScriptError},
+    observer::Observers,
+    script_config::ScriptConfig,
+    stack_trait::cast_to_bool,
+    state::State,
+    transaction_verifier::TransactionVerifier,
+    Interpreter, TxScript,
+};
+
+/// Which of the two scripts of an input was being evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScriptStage {
+    Unlocking,
+    Locking,
+}
+
+/// Why and where an input failed to verify.
+#[derive(Debug)]
+pub struct ScriptFailure {
+    pub stage: ScriptStage,
+    /// Index of the failing script bit within the script of the given stage.
+    /// Checks made after evaluation report the length of the locking script.
+    pub script_index: usize,
+    pub error: InterpreterError,
+}
+
+impl ScriptFailure {
+    /// The node's error code for this failure.
+    #[must_use]
+    pub fn script_error(&self) -> ScriptError {
+        self.error.script_error()
+    }
+}
+
+impl std::fmt::Display for ScriptFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in {:?} script at index {}: {}", self.script_error(), self.stage, self.script_index, self.error)
+    }
+}
+
+/// Result of verifying a single input against the output it spends.
+#[derive(Debug)]
+pub struct InputVerdict {
+    pub input_index: usize,
+    pub failure: Option<ScriptFailure>,
+}
+
+impl InputVerdict {
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+impl Transaction {
+    pub(crate) fn verify_input_impl(&self, index: usize, prev_output: &TxOut, config: &ScriptConfig) -> Result<InputVerdict, BSVErrors> {
+        let mut txin = self.get_input(index).ok_or_else(|| BSVErrors::OutOfBounds(format!("Could not get TxIn at index {}", index)))?;
+        txin.set_locking_script(&prev_output.script_pub_key);
+        txin.set_satoshis(prev_output.value);
+
+        let mut tx = self.clone();
+        tx.set_input(index, &txin);
+
+        let failure = Transaction::evaluate_input(&Arc::new(tx), index, &txin.get_unlocking_script(), &prev_output.script_pub_key, config).err();
+        Ok(InputVerdict { input_index: index, failure })
+    }
+
+    /// Evaluates the unlocking script, then the locking script on a copy of the resulting stack, like the node does.
+    pub(super) fn evaluate_input(tx: &Arc<Transaction>, index: usize, unlocking_script: &Script, locking_script: &Script, config: &ScriptConfig) -> Result<(), ScriptFailure> {
+        let failure = |stage: ScriptStage, script_index: usize, error: InterpreterError| ScriptFailure { stage, script_index, error };
+
+        let unlocking_bits = unlocking_script.to_flat_script_bits();
+        if config.flags.sig_push_only {
+            if let Some(position) = unlocking_bits.iter().position(|bit| !bit.is_push()) {
+                return Err(failure(ScriptStage::Unlocking, position, InterpreterError::SigPushOnly));
+            }
+        }
+
+        // Stack checks are made once both scripts have been evaluated
+        let mut eval_config = config.clone();
+        eval_config.flags.clean_stack = false;
+
+        let tx_script = TxScript {
+            tx: Arc::clone(tx),
+            input_index: index,
+            locking_script_offset: 0,
+        };
+
+        let mut unlocking = Interpreter {
+            script_bits: unlocking_bits,
+            script_index: 0,
+            state: State::default(),
+            tx_script: Some(tx_script.clone()),
+            config: eval_config.clone(),
+            observers: Observers::default(),
+            breakpoints: vec![],
+            history: History::default(),
+        };
+        unlocking.run_impl().map_err(|e| failure(ScriptStage::Unlocking, unlocking.script_index, e))?;
+
+        // Only the main stack and the cost so far are carried over to the locking script
+        let mut locking = Interpreter {
+            script_bits: locking_script.to_flat_script_bits(),
+            script_index: 0,
+            state: State {
+                stack: unlocking.state.stack,
+                cost: unlocking.state.cost,
+                ..State::default()
+            },
+            tx_script: Some(tx_script),
+            config: eval_config,
+            observers: Observers::default(),
+            breakpoints: vec![],
+            history: History::default(),
+        };
+        locking.state.stack_memory_usage = locking.state.calculate_stack_memory_usage();
+        locking.run_impl().map_err(|e| failure(ScriptStage::Locking, locking.script_index, e))?;
+
+        let end = locking.script_bits.len();
+        match locking.state.stack.last() {
+            Some(top) if cast_to_bool(top) => (),
+            _ => return Err(failure(ScriptStage::Locking, end, InterpreterError::EvalFalse)),
+        }
+
+        if config.flags.clean_stack && locking.state.stack.len() != 1 {
+            return Err(failure(ScriptStage::Locking, end, InterpreterError::CleanStack));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn verify_all_inputs_impl(&self, prev_outputs: &[TxOut], config: &ScriptConfig) -> Result<Vec<InputVerdict>, BSVErrors> {
+        if prev_outputs.len() != self.get_ninputs() {
+            return Err(BSVErrors::OutOfBounds(format!(
+                "Expected {} previous outputs, one for each input, got {}",
+                self.get_ninputs(),
+                prev_outputs.len()
+            )));
+        }
+
+        let mut verifier = TransactionVerifier::from_prev_outputs(self, &prev_outputs.iter().collect::<Vec<_>>());
+        verifier.set_config(config);
+        Ok(verifier.verify())
+    }
+}
+
+impl Transaction {
+    /// Verifies the input at the given index spends the provided previous output, using the default script config.
+    pub fn verify_input(&self, index: usize, prev_output: &TxOut) -> Result<InputVerdict, BSVErrors> {
+        self.verify_input_impl(index, prev_output, &ScriptConfig::default())
+    }
+
+    pub fn verify_input_with_config(&self, index: usize, prev_output: &TxOut, config: &ScriptConfig) -> Result<InputVerdict, BSVErrors> {
+        self.verify_input_impl(index, prev_output, config)
+    }
+
+    /// Verifies every input, `prev_outputs` must contain the spent output for each input in order.
+    pub fn verify_all_inputs(&self, prev_outputs: &[TxOut]) -> Result<Vec<InputVerdict>, BSVErrors> {
+        self.verify_all_inputs_impl(prev_outputs, &ScriptConfig::default())
+    }
+
+    pub fn verify_all_inputs_with_config(&self, prev_outputs: &[TxOut], config: &ScriptConfig) -> Result<Vec<InputVerdict>, BSVErrors> {
+        self.verify_all_inputs_impl(prev_outputs, config)
+    }
+}
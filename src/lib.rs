@@ -46,5 +46,8 @@ pub use bsm::*;
 pub mod ecies;
 pub use ecies::*;
 
+pub mod spv;
+pub use spv::*;
+
 mod interpreter;
 pub use interpreter::*;
@@ -0,0 +1,159 @@
+use crate::{BSVErrors, OpCodes, Script, ScriptBuilder, ScriptItem, ScriptIter, ScriptNum};
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strum_macros::Display;
+
+const SIGHASH_BASE_NAMES: [&str; 3] = ["ALL", "NONE", "SINGLE"];
+const SIGHASH_FORKID: u8 = 0x40;
+const SIGHASH_ANYONECANPAY: u8 = 0x80;
+
+/// The asm formats of other tooling that scripts can be rendered in and parsed from.
+/// Only `Default` is lossless, the others follow the tool they are named after, so for example non minimal pushes are read back as minimal pushes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+pub enum AsmDialect {
+    /// The format of `Script::to_asm_string`
+    #[default]
+    Default,
+    /// ScriptToAsmStr of the node. Pushes of up to 4 bytes and OP_1NEGATE - OP_16 are decimal numbers,
+    /// signatures end with their decoded sighash type, such as `[ALL|FORKID]`, unless the script is unspendable.
+    /// When parsing, positive numbers of 10 digits are read as hex pushes.
+    Bitcoind,
+    /// Script.toASM of bsv.js. OP_0 and OP_1NEGATE are `0` and `-1`, other opcodes use their names and unknown opcodes their hex byte.
+    BsvJs,
+    /// The asm sCrypt compiles contracts to, every opcode including OP_0 uses its name.
+    Scrypt,
+}
+
+impl Script {
+    /// Names a sighash byte the way the node does, None if it is not one of the defined types.
+    fn sighash_type_name(sighash: u8) -> Option<String> {
+        let base = sighash & !(SIGHASH_FORKID | SIGHASH_ANYONECANPAY);
+        let mut name = SIGHASH_BASE_NAMES.get((base as usize).checked_sub(1)?)?.to_string();
+        if sighash & SIGHASH_FORKID != 0 {
+            name.push_str("|FORKID");
+        }
+        if sighash & SIGHASH_ANYONECANPAY != 0 {
+            name.push_str("|ANYONECANPAY");
+        }
+        Some(name)
+    }
+
+    fn sighash_type_from_name(name: &str) -> Option<u8> {
+        let mut parts = name.split('|');
+        let base = SIGHASH_BASE_NAMES.iter().position(|x| Some(*x) == parts.next())? as u8 + 1;
+        parts.try_fold(base, |sighash, flag| match flag {
+            "FORKID" => Some(sighash | SIGHASH_FORKID),
+            "ANYONECANPAY" => Some(sighash | SIGHASH_ANYONECANPAY),
+            _ => None,
+        })
+    }
+
+    fn push_to_asm(data: &[u8], dialect: AsmDialect, decode_sighash: bool) -> String {
+        match dialect {
+            AsmDialect::Bitcoind if data.len() <= 4 => ScriptNum::from_bytes(data, 4, false).map(|x| x.value().to_string()).unwrap_or_default(),
+            AsmDialect::Bitcoind if decode_sighash && crate::interpreter::is_strict_signature_encoding(data) => match Script::sighash_type_name(data[data.len() - 1]) {
+                Some(name) => format!("{}[{}]", hex::encode(&data[..data.len() - 1]), name),
+                None => hex::encode(data),
+            },
+            AsmDialect::BsvJs if data.is_empty() => "0".into(),
+            AsmDialect::Scrypt if data.is_empty() => OpCodes::OP_0.to_string(),
+            _ => hex::encode(data),
+        }
+    }
+
+    fn opcode_to_asm(opcode: u8, dialect: AsmDialect) -> String {
+        let code = match OpCodes::from_u8(opcode) {
+            Some(v) => v,
+            None => {
+                return match dialect {
+                    AsmDialect::Bitcoind => "OP_UNKNOWN".into(),
+                    AsmDialect::BsvJs => format!("{:02x}", opcode),
+                    _ => format!("OP_UNKNOWN{}", opcode),
+                }
+            }
+        };
+
+        match (dialect, code) {
+            (AsmDialect::Bitcoind | AsmDialect::BsvJs, OpCodes::OP_1NEGATE) => "-1".into(),
+            (AsmDialect::Bitcoind, _) if (OpCodes::OP_1 as u8..=OpCodes::OP_16 as u8).contains(&opcode) => (opcode - OpCodes::OP_1 as u8 + 1).to_string(),
+            _ => code.to_string(),
+        }
+    }
+
+    /// Renders the script in the asm of another tool, see `AsmDialect`.
+    /// Bytes after an OP_RETURN are read as instructions like the other tools do, a truncated instruction at the end is `[error]` for `Bitcoind`.
+    pub fn to_asm_string_with_dialect(&self, dialect: AsmDialect) -> String {
+        if dialect == AsmDialect::Default {
+            return self.to_asm_string();
+        }
+
+        let bytes = self.to_bytes();
+        let decode_sighash = dialect == AsmDialect::Bitcoind && !self.is_provably_unspendable(false);
+        ScriptIter::new(&bytes)
+            .map(|item| match item {
+                ScriptItem::Op { opcode, data, .. } if opcode <= OpCodes::OP_PUSHDATA4 as u8 => Script::push_to_asm(data, dialect, decode_sighash),
+                ScriptItem::Op { opcode, .. } => Script::opcode_to_asm(opcode, dialect),
+                ScriptItem::Malformed { bytes, .. } => match dialect {
+                    AsmDialect::Bitcoind => "[error]".into(),
+                    _ => format!("malformed:{}", hex::encode(bytes)),
+                },
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn asm_token_to_bytes(token: &str, dialect: AsmDialect) -> Result<Vec<u8>, BSVErrors> {
+        match (dialect, token) {
+            (AsmDialect::BsvJs, "0") => return Ok(vec![OpCodes::OP_0 as u8]),
+            (AsmDialect::BsvJs, "-1") => return Ok(vec![OpCodes::OP_1NEGATE as u8]),
+            (AsmDialect::BsvJs | AsmDialect::Scrypt, "OP_FALSE") => return Ok(vec![OpCodes::OP_0 as u8]),
+            (AsmDialect::BsvJs | AsmDialect::Scrypt, "OP_TRUE") => return Ok(vec![OpCodes::OP_1 as u8]),
+            _ => (),
+        }
+
+        if dialect == AsmDialect::Bitcoind {
+            // Pushes of up to 4 bytes are written as numbers without leading zeros, longer pushes are hex.
+            // Hex of more than 4 bytes has at least 10 characters, so positive numbers of 10 digits are read as hex and do not round trip
+            let may_be_long_push = !token.starts_with('-') && token.len() >= 10;
+            if let Ok(number) = i64::from_str(token) {
+                if !may_be_long_push && number.unsigned_abs() <= i32::MAX as u64 && (token == "0" || !token.trim_start_matches('-').starts_with('0')) {
+                    return Ok(ScriptBuilder::new().push_int(number).build().to_bytes());
+                }
+            }
+
+            if let Some((signature, sighash)) = token.strip_suffix(']').and_then(|x| x.split_once('[')) {
+                let sighash = Script::sighash_type_from_name(sighash).ok_or_else(|| BSVErrors::DeserialiseScript(format!("Unknown sighash type in {}", token)))?;
+                let mut signature = hex::decode(signature)?;
+                signature.push(sighash);
+                return Script::encode_pushdata(&signature);
+            }
+        }
+
+        if let Ok(code) = OpCodes::from_str(token) {
+            return Ok(vec![code as u8]);
+        }
+
+        if dialect != AsmDialect::Bitcoind {
+            if let Some(Ok(unknown_code)) = token.strip_prefix("OP_UNKNOWN").map(u8::from_str) {
+                return Ok(vec![unknown_code]);
+            }
+
+            if let Some(malformed) = token.strip_prefix("malformed:") {
+                return Ok(hex::decode(malformed)?);
+            }
+        }
+
+        Script::encode_pushdata(&hex::decode(token)?)
+    }
+
+    /// Parses asm in the format of another tool, see `AsmDialect`. Bytes after a top level OP_RETURN become non-script data as in `Script::from_bytes`.
+    pub fn from_asm_string_with_dialect(asm: &str, dialect: AsmDialect) -> Result<Script, BSVErrors> {
+        if dialect == AsmDialect::Default {
+            return Script::from_asm_string(asm);
+        }
+
+        let bytes = asm.split_whitespace().map(|token| Script::asm_token_to_bytes(token, dialect)).collect::<Result<Vec<_>, _>>()?.concat();
+        Script::from_bytes(&bytes)
+    }
+}
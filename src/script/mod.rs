@@ -1,14 +1,9 @@
 use crate::{Hash, OpCodes::OP_0};
 
-use std::{
-    io::{Cursor, Read},
-    slice::Iter,
-    str::FromStr,
-    usize,
-};
+use std::{slice::Iter, str::FromStr, usize};
 
 use crate::{BSVErrors, VarInt};
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::{LittleEndian, WriteBytesExt};
 use num_traits::{FromPrimitive, ToPrimitive};
 
 use serde::{Deserialize, Serialize};
@@ -22,6 +17,18 @@ pub use script_bit::*;
 mod script_template;
 pub use script_template::*;
 
+mod script_analysis;
+pub use script_analysis::*;
+
+mod script_iter;
+pub use script_iter::*;
+
+mod script_builder;
+pub use script_builder::*;
+
+mod asm_dialect;
+pub use asm_dialect::*;
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Script(pub(crate) Vec<ScriptBit>);
 
@@ -70,6 +77,8 @@ impl Script {
                     string_parts.join(" ")
                 }
                 ScriptBit::Coinbase(bytes) => hex::encode(bytes),
+                ScriptBit::UnknownOpCode(code) => format!("OP_UNKNOWN{}", code),
+                ScriptBit::Malformed(bytes) => format!("malformed:{}", hex::encode(bytes)),
             })
             .collect::<Vec<String>>()
             .join(" ")
@@ -116,6 +125,8 @@ impl Script {
                     bytes
                 }
                 ScriptBit::Coinbase(bytes) => bytes.to_vec(),
+                ScriptBit::UnknownOpCode(code) => vec![*code],
+                ScriptBit::Malformed(bytes) => bytes.to_vec(),
             })
             .collect();
 
@@ -154,83 +165,48 @@ impl Script {
         Script::from_bytes(&hex::decode(hex)?)
     }
 
+    /// Parses script bytes, any byte string is accepted and serialises back to the same bytes.
+    /// Unknown opcodes and a truncated instruction at the end are kept as ScriptBit::UnknownOpCode and ScriptBit::Malformed,
+    /// conditionals that do not balance are left flat.
     pub fn from_bytes(bytes: &[u8]) -> Result<Script, BSVErrors> {
-        let mut cursor = Cursor::new(bytes);
-
         let mut bit_accumulator = vec![];
         let mut scope_level = 0;
-        while let Ok(byte) = cursor.read_u8() {
-            if byte.eq(&(OpCodes::OP_IF as u8)) || byte.eq(&(OpCodes::OP_NOTIF as u8)) {
-                scope_level += 1;
-            } else if byte.eq(&(OpCodes::OP_ENDIF as u8)) {
-                scope_level -= 1;
-            } else if byte.eq(&(OpCodes::OP_RETURN as u8)) && scope_level == 0 {
-                bit_accumulator.push(ScriptBit::OpCode(OpCodes::OP_RETURN));
-
-                let len = cursor.get_ref().len();
-
-                let non_script_data_length = len - cursor.position() as usize;
-
-                if non_script_data_length > 0 {
-                    let mut data: Vec<u8> = vec![0; non_script_data_length as usize];
-
-                    match cursor.read(&mut data) {
-                        Ok(_) => {
-                            bit_accumulator.push(ScriptBit::NonScriptData(data));
-                        }
-                        Err(e) => return Err(BSVErrors::DeserialiseScript(format!("Failed to read OP_PUSH data {}", e))),
-                    }
+        for item in ScriptIter::new(bytes) {
+            let (offset, opcode, data) = match item {
+                ScriptItem::Op { offset, opcode, data } => (offset, opcode, data),
+                ScriptItem::Malformed { bytes, .. } => {
+                    bit_accumulator.push(ScriptBit::Malformed(bytes.to_vec()));
+                    break;
                 }
+            };
 
-                break;
-            }
-
-            if byte.ne(&(OpCodes::OP_0 as u8)) && byte.lt(&(OpCodes::OP_PUSHDATA1 as u8)) {
-                let mut data: Vec<u8> = vec![0; byte as usize];
-                match cursor.read(&mut data) {
-                    Ok(len) => {
-                        if len == byte as usize {
-                            bit_accumulator.push(ScriptBit::Push(data));
-                        } else {
-                            return Err(BSVErrors::DeserialiseScript(format!("Failed to read OP_PUSH data")));
-                        }
-                    }
-                    Err(e) => return Err(BSVErrors::DeserialiseScript(format!("Failed to read OP_PUSH data {}", e))),
+            let bit = match OpCodes::from_u8(opcode) {
+                Some(v @ (OpCodes::OP_IF | OpCodes::OP_NOTIF | OpCodes::OP_VERIF | OpCodes::OP_VERNOTIF)) => {
+                    scope_level += 1;
+                    ScriptBit::OpCode(v)
                 }
-                continue;
-            }
-
-            let bit = match OpCodes::from_u8(byte) {
-                Some(v @ (OpCodes::OP_PUSHDATA1 | OpCodes::OP_PUSHDATA2 | OpCodes::OP_PUSHDATA4)) => {
-                    let data_length = match v {
-                        OpCodes::OP_PUSHDATA1 => cursor.read_u8()? as usize,
-                        OpCodes::OP_PUSHDATA2 => cursor.read_u16::<LittleEndian>()? as usize,
-                        _ => cursor.read_u32::<LittleEndian>()? as usize,
-                    };
-
-                    let mut data = vec![0; data_length];
+                Some(OpCodes::OP_ENDIF) => {
+                    scope_level -= 1;
+                    ScriptBit::OpCode(OpCodes::OP_ENDIF)
+                }
+                Some(OpCodes::OP_RETURN) if scope_level == 0 => {
+                    bit_accumulator.push(ScriptBit::OpCode(OpCodes::OP_RETURN));
 
-                    match cursor.read(&mut data) {
-                        Ok(len) => {
-                            if len == data_length as usize {
-                                ScriptBit::PushData(v, data)
-                            } else {
-                                return Err(BSVErrors::DeserialiseScript(format!("Failed to read OP_PUSH data")));
-                            }
-                        }
-                        Err(e) => return Err(BSVErrors::DeserialiseScript(format!("Failed to read OP_PUSH data {}", e))),
+                    if offset + 1 < bytes.len() {
+                        bit_accumulator.push(ScriptBit::NonScriptData(bytes[offset + 1..].to_vec()));
                     }
+                    break;
                 }
+                Some(v @ (OpCodes::OP_PUSHDATA1 | OpCodes::OP_PUSHDATA2 | OpCodes::OP_PUSHDATA4)) => ScriptBit::PushData(v, data.to_vec()),
+                _ if !data.is_empty() => ScriptBit::Push(data.to_vec()),
                 Some(v) => ScriptBit::OpCode(v),
-                None => return Err(BSVErrors::DeserialiseScript(format!("Unknown opcode {}", byte))),
+                None => ScriptBit::UnknownOpCode(opcode),
             };
 
             bit_accumulator.push(bit);
         }
 
-        let nested_bits = Script::if_statement_pass(&mut bit_accumulator.iter())?;
-
-        Ok(Script(nested_bits))
+        Ok(Script::from_flat_script_bits(&bit_accumulator))
     }
 
     pub fn from_coinbase_bytes(bytes: &[u8]) -> Result<Script, BSVErrors> {
@@ -267,6 +243,16 @@ impl Script {
             return Ok(ScriptBit::OpCode(opcode));
         }
 
+        if let Some(Ok(unknown_code)) = code.strip_prefix("OP_UNKNOWN").map(u8::from_str) {
+            if OpCodes::from_u8(unknown_code).is_none() {
+                return Ok(ScriptBit::UnknownOpCode(unknown_code));
+            }
+        }
+
+        if let Some(malformed) = code.strip_prefix("malformed:") {
+            return Ok(ScriptBit::Malformed(hex::decode(malformed)?));
+        }
+
         if code.starts_with("non-script-data:") {
             if is_non_script_data {
                 let non_script_data = hex::decode(code.trim_start_matches("non-script-data:"))?;
@@ -468,8 +454,63 @@ impl Script {
         hex::encode(self.to_bytes())
     }
 
+    /// Removes every OP_CODESEPARATOR the node would parse, including those in branches and after an OP_RETURN, like the legacy sighash serialisation does.
     pub fn remove_codeseparators(&mut self) {
-        self.0 = self.0.clone().into_iter().filter(|x| *x != ScriptBit::OpCode(OpCodes::OP_CODESEPARATOR)).collect();
+        let bytes = self.to_bytes();
+        let mut kept = Vec::with_capacity(bytes.len());
+        let mut start = 0;
+        for item in ScriptIter::new(&bytes) {
+            if let ScriptItem::Op { offset, opcode, .. } = item {
+                if opcode == OpCodes::OP_CODESEPARATOR as u8 {
+                    kept.extend_from_slice(&bytes[start..offset]);
+                    start = offset + 1;
+                }
+            }
+        }
+
+        if start > 0 {
+            kept.extend_from_slice(&bytes[start..]);
+            if let Ok(script) = Script::from_bytes(&kept) {
+                *self = script;
+            }
+        }
+    }
+
+    /// Deletes every occurrence of the serialised `needle` that starts on an instruction boundary and returns how many were deleted.
+    /// Follows FindAndDelete in the node, which removes signatures from the script signed by the legacy sighash.
+    pub fn find_and_delete(&mut self, needle: &Script) -> usize {
+        let needle = needle.to_bytes();
+        if needle.is_empty() {
+            return 0;
+        }
+
+        let bytes = self.to_bytes();
+        let mut kept = Vec::with_capacity(bytes.len());
+        let (mut pc, mut start, mut found) = (0, 0, 0);
+        loop {
+            kept.extend_from_slice(&bytes[start..pc]);
+            while bytes[pc..].starts_with(&needle) {
+                pc += needle.len();
+                found += 1;
+            }
+            start = pc;
+
+            // Step over one instruction, a truncated instruction ends the search like it does in the node
+            let mut instructions = ScriptIter::new(&bytes[pc..]);
+            match instructions.next() {
+                Some(ScriptItem::Op { .. }) => pc += instructions.position(),
+                _ => break,
+            }
+        }
+
+        if found > 0 {
+            kept.extend_from_slice(&bytes[start..]);
+            if let Ok(script) = Script::from_bytes(&kept) {
+                *self = script;
+            }
+        }
+
+        found
     }
 
     pub fn from_chunks(chunks: Vec<Vec<u8>>) -> Result<Script, BSVErrors> {
@@ -517,4 +558,36 @@ impl Script {
     pub fn to_script_bits(&self) -> Vec<ScriptBit> {
         self.0.clone()
     }
+
+    /// Gets the script bits with every ScriptBit::If expanded back into the OP_IF ... OP_ELSE ... OP_ENDIF opcodes, in the order they are executed
+    pub fn to_flat_script_bits(&self) -> Vec<ScriptBit> {
+        Script::flatten_script_bits(&self.0)
+    }
+
+    pub fn flatten_script_bits(codes: &[ScriptBit]) -> Vec<ScriptBit> {
+        let mut flat_bits = vec![];
+        for bit in codes {
+            match bit {
+                ScriptBit::If { code, pass, fail } => {
+                    flat_bits.push(ScriptBit::OpCode(*code));
+                    flat_bits.extend(Script::flatten_script_bits(pass));
+                    if let Some(fail) = fail {
+                        flat_bits.push(ScriptBit::OpCode(OpCodes::OP_ELSE));
+                        flat_bits.extend(Script::flatten_script_bits(fail));
+                    }
+                    flat_bits.push(ScriptBit::OpCode(OpCodes::OP_ENDIF));
+                }
+                o => flat_bits.push(o.clone()),
+            }
+        }
+        flat_bits
+    }
+
+    /// Nests flat script bits into ScriptBit::If blocks for display, unbalanced conditionals are left flat
+    pub(crate) fn from_flat_script_bits(bits: &[ScriptBit]) -> Script {
+        match Script::if_statement_pass(&mut bits.iter()) {
+            Ok(nested_bits) => Script(nested_bits),
+            Err(_) => Script(bits.to_vec()),
+        }
+    }
 }
@@ -174,9 +174,9 @@ pub enum OpCodes {
     OP_DIV = 150,
     /// Returns the remainder after dividing a by b
     OP_MOD = 151,
-    /// Shifts a left b bits, preserving sign
+    /// Shifts the bits of byte string a left by b, keeping its length
     OP_LSHIFT = 152,
-    /// Shifts a right b bits, preserving sign
+    /// Shifts the bits of byte string a right by b, keeping its length
     OP_RSHIFT = 153,
     /// If both a and b are not empty, the output is 1. Otherwise, 0.
     OP_BOOLAND = 154,
@@ -258,11 +258,11 @@ pub enum OpCodes {
     // --------------------------------------------------------------------------------------------
     /// Transaction is invalid unless occuring in an unexecuted OP_IF branch
     OP_RESERVED = 80,
-    /// Transaction is invalid unless occuring in an unexecuted OP_IF branch
+    /// Pushes the transaction version after Chronicle, otherwise invalid unless occuring in an unexecuted OP_IF branch
     OP_VER = 98,
-    /// Transaction is invalid even when occuring in an unexecuted OP_IF branch
+    /// Executes the statements if the top stack item equals the transaction version after Chronicle, otherwise always invalid
     OP_VERIF = 101,
-    /// Transaction is invalid even when occuring in an unexecuted OP_IF branch
+    /// Executes the statements if the top stack item does not equal the transaction version after Chronicle, otherwise always invalid
     OP_VERNOTIF = 102,
     /// Transaction is invalid unless occuring in an unexecuted OP_IF branch
     OP_RESERVED1 = 137,
@@ -270,15 +270,20 @@ pub enum OpCodes {
     OP_RESERVED2 = 138,
     /// The word is ignored. Does not mark transaction as invalid.
     OP_NOP1 = 176,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_SUBSTR after
+    #[strum(serialize = "OP_NOP4", serialize = "OP_SUBSTR")]
     OP_NOP4 = 179,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_LEFT after
+    #[strum(serialize = "OP_NOP5", serialize = "OP_LEFT")]
     OP_NOP5 = 180,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_RIGHT after
+    #[strum(serialize = "OP_NOP6", serialize = "OP_RIGHT")]
     OP_NOP6 = 181,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_LSHIFTNUM after
+    #[strum(serialize = "OP_NOP7", serialize = "OP_LSHIFTNUM")]
     OP_NOP7 = 182,
-    /// The word is ignored. Does not mark transaction as invalid.
+    /// The word is ignored before Chronicle, OP_RSHIFTNUM after
+    #[strum(serialize = "OP_NOP8", serialize = "OP_RSHIFTNUM")]
     OP_NOP8 = 183,
     /// The word is ignored. Does not mark transaction as invalid.
     OP_NOP9 = 184,
@@ -289,7 +294,7 @@ pub enum OpCodes {
     OP_INVALID_ABOVE = 186,
 
     // --------------------------------------------------------------------------------------------
-    // Disabled words
+    // Disabled words, restored by Chronicle
     // --------------------------------------------------------------------------------------------
     /// The input is multiplied by 2
     OP_2MUL = 141,
@@ -297,6 +302,22 @@ pub enum OpCodes {
     OP_2DIV = 142,
 }
 
+// --------------------------------------------------------------------------------------------
+// Chronicle names of OP_NOP4 to OP_NOP8, scripts keep rendering these words by their NOP names
+// --------------------------------------------------------------------------------------------
+impl OpCodes {
+    /// Returns size bytes of x starting at begin
+    pub const OP_SUBSTR: OpCodes = OpCodes::OP_NOP4;
+    /// Returns the first size bytes of x
+    pub const OP_LEFT: OpCodes = OpCodes::OP_NOP5;
+    /// Returns the last size bytes of x
+    pub const OP_RIGHT: OpCodes = OpCodes::OP_NOP6;
+    /// Shifts number a left b bits
+    pub const OP_LSHIFTNUM: OpCodes = OpCodes::OP_NOP7;
+    /// Shifts number a right b bits, preserving sign
+    pub const OP_RSHIFTNUM: OpCodes = OpCodes::OP_NOP8;
+}
+
 impl fmt::Display for OpCodes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
@@ -0,0 +1,174 @@
+use crate::{OpCodes, Script, ScriptBit, ScriptTemplate, MAX_PUBKEYS_PER_MULTISIG_BEFORE_GENESIS, MAX_SCRIPT_SIZE_BEFORE_GENESIS};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// The marker pushed at the start of a 1Sat ordinal inscription envelope.
+pub const ORDINAL_INSCRIPTION_MARKER: &[u8] = b"ord";
+
+const P2PKH_TEMPLATE: &str = "OP_DUP OP_HASH160 OP_PUBKEYHASH OP_EQUALVERIFY OP_CHECKSIG";
+const P2PK_TEMPLATE: &str = "OP_PUBKEY OP_CHECKSIG";
+const P2SH_TEMPLATE: &str = "OP_HASH160 OP_DATA=20 OP_EQUAL";
+/// Extracts R from the DER signature left on the stack by the unlocking script.
+const R_PUZZLE_PREFIX: &str = "OP_OVER OP_3 OP_SPLIT OP_NIP OP_1 OP_SPLIT OP_SWAP OP_SPLIT OP_DROP";
+const R_PUZZLE_HASHES: [&str; 6] = ["", "OP_SHA1 ", "OP_RIPEMD160 ", "OP_SHA256 ", "OP_HASH160 ", "OP_HASH256 "];
+
+/// The standard forms a locking script can take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+pub enum ScriptType {
+    /// OP_DUP OP_HASH160 <pubkeyhash> OP_EQUALVERIFY OP_CHECKSIG
+    P2PKH,
+    /// <pubkey> OP_CHECKSIG
+    P2PK,
+    /// OP_m <pubkey>... OP_n OP_CHECKMULTISIG
+    Multisig {
+        required: usize,
+        keys: usize,
+    },
+    /// OP_FALSE OP_RETURN <data>
+    DataCarrier,
+    /// OP_HASH160 <scripthash> OP_EQUAL, only spendable as P2SH before Genesis.
+    P2SH,
+    /// Locks to the R value of the signature, optionally hashed.
+    RPuzzle,
+    /// Contains an OP_FALSE OP_IF "ord" ... OP_ENDIF envelope, usually around another locking script.
+    OrdinalInscription,
+    NonStandard,
+}
+
+impl ScriptBit {
+    /// Whether the bit only pushes data, OP_RESERVED counts as a push like it does in the node.
+    #[must_use]
+    pub fn is_push(&self) -> bool {
+        match self {
+            ScriptBit::Push(_) | ScriptBit::PushData(_, _) => true,
+            ScriptBit::OpCode(code) => (*code as u8) <= OpCodes::OP_16 as u8,
+            _ => false,
+        }
+    }
+}
+
+impl Script {
+    fn matches_template(&self, asm: &str) -> bool {
+        match ScriptTemplate::from_asm_string_impl(asm) {
+            Ok(template) => self.test_impl(&template),
+            Err(_) => false,
+        }
+    }
+
+    fn small_number(bit: &ScriptBit) -> Option<usize> {
+        match bit {
+            ScriptBit::OpCode(code) if (OpCodes::OP_1 as u8..=OpCodes::OP_16 as u8).contains(&(*code as u8)) => Some(*code as usize - OpCodes::OP_1 as usize + 1),
+            _ => None,
+        }
+    }
+
+    fn multisig_counts(&self) -> Option<(usize, usize)> {
+        let (required, keys) = match self.0.as_slice() {
+            [first, .., last_n, ScriptBit::OpCode(OpCodes::OP_CHECKMULTISIG)] => (Script::small_number(first)?, Script::small_number(last_n)?),
+            _ => return None,
+        };
+
+        if required > keys || self.0.len() != keys + 3 {
+            return None;
+        }
+
+        let template = format!("OP_{} {}OP_{} OP_CHECKMULTISIG", required, "OP_PUBKEY ".repeat(keys), keys);
+        self.matches_template(&template).then_some((required, keys))
+    }
+
+    /// Whether the script carries an OP_FALSE OP_IF "ord" ... OP_ENDIF envelope at the top level.
+    #[must_use]
+    pub fn has_ordinal_inscription(&self) -> bool {
+        self.0.windows(2).any(|bits| match bits {
+            [ScriptBit::OpCode(OpCodes::OP_0), ScriptBit::If {
+                code: OpCodes::OP_IF,
+                pass,
+                fail: None,
+            }] => {
+                matches!(pass.first(), Some(ScriptBit::Push(marker)) if marker == ORDINAL_INSCRIPTION_MARKER)
+            }
+            _ => false,
+        })
+    }
+
+    /// Classifies the script as one of the standard forms.
+    /// An inscription envelope takes precedence over the script it wraps.
+    #[must_use]
+    pub fn classify(&self) -> ScriptType {
+        if self.has_ordinal_inscription() {
+            return ScriptType::OrdinalInscription;
+        }
+
+        if matches!(self.0.as_slice(), [ScriptBit::OpCode(OpCodes::OP_0), ScriptBit::OpCode(OpCodes::OP_RETURN), ..]) {
+            return ScriptType::DataCarrier;
+        }
+
+        if self.matches_template(P2PKH_TEMPLATE) {
+            return ScriptType::P2PKH;
+        }
+
+        if self.matches_template(P2PK_TEMPLATE) {
+            return ScriptType::P2PK;
+        }
+
+        if self.matches_template(P2SH_TEMPLATE) {
+            return ScriptType::P2SH;
+        }
+
+        if let Some((required, keys)) = self.multisig_counts() {
+            return ScriptType::Multisig { required, keys };
+        }
+
+        if R_PUZZLE_HASHES
+            .iter()
+            .any(|hash| self.matches_template(&format!("{} {}OP_DATA OP_EQUALVERIFY OP_CHECKSIG", R_PUZZLE_PREFIX, hash)))
+        {
+            return ScriptType::RPuzzle;
+        }
+
+        ScriptType::NonStandard
+    }
+
+    /// Counts the signature operations in the script, including both branches of every conditional.
+    /// When `accurate` is set an OP_CHECKMULTISIG preceded by OP_1 - OP_16 counts as that many sigops, otherwise it counts as `MAX_PUBKEYS_PER_MULTISIG_BEFORE_GENESIS`.
+    #[must_use]
+    pub fn count_sigops(&self, accurate: bool) -> usize {
+        let mut count = 0;
+        let mut last_bit: Option<ScriptBit> = None;
+
+        for bit in self.to_flat_script_bits() {
+            match bit {
+                ScriptBit::OpCode(OpCodes::OP_CHECKSIG | OpCodes::OP_CHECKSIGVERIFY) => count += 1,
+                ScriptBit::OpCode(OpCodes::OP_CHECKMULTISIG | OpCodes::OP_CHECKMULTISIGVERIFY) => {
+                    count += match last_bit.as_ref().and_then(Script::small_number) {
+                        Some(keys) if accurate => keys,
+                        _ => MAX_PUBKEYS_PER_MULTISIG_BEFORE_GENESIS,
+                    }
+                }
+                _ => (),
+            }
+            last_bit = Some(bit);
+        }
+
+        count
+    }
+
+    /// Whether no unlocking script can ever satisfy this locking script.
+    /// A script starting with OP_FALSE OP_RETURN is always unspendable. Before Genesis so is a script starting with OP_RETURN or one larger than the maximum script size,
+    /// after Genesis a leading OP_RETURN ends the script successfully with whatever the unlocking script left on the stack.
+    #[must_use]
+    pub fn is_provably_unspendable(&self, after_genesis: bool) -> bool {
+        match self.0.as_slice() {
+            [ScriptBit::OpCode(OpCodes::OP_0), ScriptBit::OpCode(OpCodes::OP_RETURN), ..] => true,
+            _ if after_genesis => false,
+            [ScriptBit::OpCode(OpCodes::OP_RETURN), ..] => true,
+            _ => self.to_bytes().len() > MAX_SCRIPT_SIZE_BEFORE_GENESIS,
+        }
+    }
+
+    /// Whether the script only pushes data, as required of unlocking scripts.
+    #[must_use]
+    pub fn is_push_only(&self) -> bool {
+        self.0.iter().all(ScriptBit::is_push)
+    }
+}
@@ -15,4 +15,9 @@ pub enum ScriptBit {
     // https://github.com/bitcoin-sv-specs/protocol/blob/master/updates/genesis-spec.md#formal-grammar-for-bitcoin-script
     NonScriptData(#[serde(serialize_with = "to_hex", deserialize_with = "from_hex")] Vec<u8>),
     Coinbase(#[serde(serialize_with = "to_hex", deserialize_with = "from_hex")] Vec<u8>),
+    // A byte that is not a known opcode, it only fails the script when executed.
+    UnknownOpCode(u8),
+    // Bytes at the end of a script that do not form a complete instruction, such as a push cut short by the end of the script.
+    // They are kept so the script serialises back to the exact bytes it was parsed from.
+    Malformed(#[serde(serialize_with = "to_hex", deserialize_with = "from_hex")] Vec<u8>),
 }
@@ -0,0 +1,116 @@
+use crate::{BSVErrors, OpCodes, PublicKey, Script, ScriptBit, ScriptNum, SighashSignature, VarInt};
+use num_traits::FromPrimitive;
+
+/// Builds a Script one instruction at a time, pushes always use the smallest encoding.
+///
+/// ```
+/// use bsv::{OpCodes, ScriptBuilder};
+///
+/// let script = ScriptBuilder::new()
+///     .push_int(2)
+///     .push_opcode(OpCodes::OP_EQUAL)
+///     .if_else(|pass| pass.push_data(b"yes"), |fail| fail.push_bool(false))
+///     .build();
+///
+/// assert_eq!(script.to_asm_string(), "OP_2 OP_EQUAL OP_IF 796573 OP_ELSE 0 OP_ENDIF");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScriptBuilder(Vec<ScriptBit>);
+
+impl ScriptBuilder {
+    fn minimal_push(data: &[u8]) -> ScriptBit {
+        match data {
+            [] => ScriptBit::OpCode(OpCodes::OP_0),
+            [n @ 1..=16] => ScriptBit::OpCode(OpCodes::from_u8(n + OpCodes::OP_1 as u8 - 1).unwrap()),
+            [0x81] => ScriptBit::OpCode(OpCodes::OP_1NEGATE),
+            _ => match VarInt::get_pushdata_opcode(data.len() as u64) {
+                Some(code) => ScriptBit::PushData(code, data.to_vec()),
+                None => ScriptBit::Push(data.to_vec()),
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn new() -> ScriptBuilder {
+        ScriptBuilder::default()
+    }
+
+    #[must_use]
+    pub fn push_opcode(mut self, code: OpCodes) -> ScriptBuilder {
+        self.0.push(ScriptBit::OpCode(code));
+        self
+    }
+
+    /// Pushes the data with the smallest push, empty data and single byte numbers use OP_0, OP_1NEGATE or OP_1 - OP_16.
+    #[must_use]
+    pub fn push_data(mut self, data: &[u8]) -> ScriptBuilder {
+        self.0.push(ScriptBuilder::minimal_push(data));
+        self
+    }
+
+    /// Pushes the number in script number encoding.
+    #[must_use]
+    pub fn push_int(self, number: i64) -> ScriptBuilder {
+        self.push_data(&ScriptNum::from(number).to_bytes())
+    }
+
+    /// Pushes OP_1 or OP_0.
+    #[must_use]
+    pub fn push_bool(self, value: bool) -> ScriptBuilder {
+        self.push_opcode(match value {
+            true => OpCodes::OP_1,
+            false => OpCodes::OP_0,
+        })
+    }
+
+    pub fn push_pubkey(self, pub_key: &PublicKey) -> Result<ScriptBuilder, BSVErrors> {
+        Ok(self.push_data(&pub_key.to_bytes()?))
+    }
+
+    /// Pushes the DER signature followed by its sighash byte.
+    pub fn push_sig(self, sig: &SighashSignature) -> Result<ScriptBuilder, BSVErrors> {
+        Ok(self.push_data(&sig.to_bytes()?))
+    }
+
+    /// Adds an OP_IF block, the closures build the branches on empty builders.
+    #[must_use]
+    pub fn if_else<P, F>(self, pass: P, fail: F) -> ScriptBuilder
+    where
+        P: FnOnce(ScriptBuilder) -> ScriptBuilder,
+        F: FnOnce(ScriptBuilder) -> ScriptBuilder,
+    {
+        self.push_if(OpCodes::OP_IF, pass(ScriptBuilder::new()), Some(fail(ScriptBuilder::new())))
+    }
+
+    /// Adds an OP_IF block without an OP_ELSE branch.
+    #[must_use]
+    pub fn if_then<P>(self, pass: P) -> ScriptBuilder
+    where
+        P: FnOnce(ScriptBuilder) -> ScriptBuilder,
+    {
+        self.push_if(OpCodes::OP_IF, pass(ScriptBuilder::new()), None)
+    }
+
+    /// Adds a conditional block opened by `code`, such as OP_NOTIF.
+    #[must_use]
+    pub fn push_if(mut self, code: OpCodes, pass: ScriptBuilder, fail: Option<ScriptBuilder>) -> ScriptBuilder {
+        self.0.push(ScriptBit::If {
+            code,
+            pass: pass.0,
+            fail: fail.map(|x| x.0),
+        });
+        self
+    }
+
+    /// Appends the bits of an existing script.
+    #[must_use]
+    pub fn push_script(mut self, script: &Script) -> ScriptBuilder {
+        self.0.extend(script.to_script_bits());
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Script {
+        Script(self.0)
+    }
+}
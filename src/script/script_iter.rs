@@ -0,0 +1,97 @@
+use crate::OpCodes;
+use num_traits::FromPrimitive;
+
+/// An instruction read by `ScriptIter`, borrowing from the script bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptItem<'a> {
+    /// A single opcode at `offset`, `data` holds the bytes pushed and is empty for any other opcode.
+    Op { offset: usize, opcode: u8, data: &'a [u8] },
+    /// Bytes from `offset` to the end of the script that do not form a complete instruction, such as a push running past the end.
+    Malformed { offset: usize, bytes: &'a [u8] },
+}
+
+impl<'a> ScriptItem<'a> {
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        match self {
+            ScriptItem::Op { offset, .. } | ScriptItem::Malformed { offset, .. } => *offset,
+        }
+    }
+
+    /// Gets the opcode, None for malformed bytes or bytes that are not a known opcode.
+    #[must_use]
+    pub fn op_code(&self) -> Option<OpCodes> {
+        match self {
+            ScriptItem::Op { opcode, .. } => OpCodes::from_u8(*opcode),
+            ScriptItem::Malformed { .. } => None,
+        }
+    }
+
+    /// Gets the pushed data, None if the item is not a push.
+    #[must_use]
+    pub fn push_data(&self) -> Option<&'a [u8]> {
+        match self {
+            ScriptItem::Op { opcode, data, .. } if *opcode <= OpCodes::OP_PUSHDATA4 as u8 => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// Iterates over the instructions of raw script bytes without copying them.
+/// Unlike `Script::from_bytes` nothing after an OP_RETURN is treated specially and conditionals do not have to balance,
+/// a truncated instruction at the end is returned as `ScriptItem::Malformed` and ends the iteration.
+#[derive(Debug, Clone)]
+pub struct ScriptIter<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ScriptIter<'a> {
+    #[must_use]
+    pub fn new(bytes: &'a [u8]) -> ScriptIter<'a> {
+        ScriptIter { bytes, offset: 0 }
+    }
+
+    /// Gets the offset of the next instruction.
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.offset
+    }
+
+    /// Reads the pushdata length of `size` bytes and the data following it, None if the script ends first.
+    fn read_push(&self, start: usize, size: usize) -> Option<(&'a [u8], usize)> {
+        let length_bytes = self.bytes.get(start..start + size)?;
+        let length = length_bytes.iter().rev().fold(0_usize, |acc, byte| (acc << 8) | *byte as usize);
+        let data_start = start + size;
+        let data_end = data_start.checked_add(length)?;
+        Some((self.bytes.get(data_start..data_end)?, data_end))
+    }
+}
+
+impl<'a> Iterator for ScriptIter<'a> {
+    type Item = ScriptItem<'a>;
+
+    fn next(&mut self) -> Option<ScriptItem<'a>> {
+        let offset = self.offset;
+        let opcode = *self.bytes.get(offset)?;
+
+        let push = match opcode {
+            0x01..=0x4b => self.bytes.get(offset + 1..offset + 1 + opcode as usize).map(|data| (data, offset + 1 + opcode as usize)),
+            v if v == OpCodes::OP_PUSHDATA1 as u8 => self.read_push(offset + 1, 1),
+            v if v == OpCodes::OP_PUSHDATA2 as u8 => self.read_push(offset + 1, 2),
+            v if v == OpCodes::OP_PUSHDATA4 as u8 => self.read_push(offset + 1, 4),
+            _ => Some((&self.bytes[offset..offset], offset + 1)),
+        };
+
+        match push {
+            Some((data, end)) => {
+                self.offset = end;
+                Some(ScriptItem::Op { offset, opcode, data })
+            }
+            None => {
+                self.offset = self.bytes.len();
+                Some(ScriptItem::Malformed { offset, bytes: &self.bytes[offset..] })
+            }
+        }
+    }
+}
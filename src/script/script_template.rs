@@ -1,7 +1,9 @@
 use crate::OpCodes::OP_0;
 use std::str::FromStr;
 
-use crate::{BSVErrors, OpCodes, PublicKey, Script, ScriptBit, Signature, VarInt};
+use std::collections::{HashMap, HashSet};
+
+use crate::{BSVErrors, OpCodes, P2PKHAddress, PublicKey, Script, ScriptBit, ScriptNum, Signature, VarInt};
 use hex::FromHexError;
 use num_traits::FromPrimitive;
 use serde::{Deserialize, Serialize};
@@ -11,11 +13,14 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum ScriptTemplateErrors {
     #[error("Script did not match template at index {0}. {2} is not equal to {1:?}. Error: {3:?}")]
-    MatchFailure(usize, MatchToken, ScriptBit, BSVErrors),
+    MatchFailure(usize, Box<MatchToken>, Box<ScriptBit>, Box<BSVErrors>),
 
     #[error("Failed to parse OP_DATA code {0}: {1}")]
     OpDataParse(String, String),
 
+    #[error("Failed to parse placeholder {0}: {1}")]
+    PlaceholderParse(String, String),
+
     #[error("Script Template and Script lengths do not match.")]
     LengthsDiffer,
 
@@ -52,21 +57,84 @@ pub enum MatchToken {
     Signature,
     PublicKey,
     PublicKeyHash,
+    /// OP_0, OP_1NEGATE, OP_1 - OP_16 or a minimally encoded number of up to 8 bytes
+    Number,
+
+    /// A named placeholder, `<name:Type>` in template asm, matching `min` to `max` bits in a row
+    Capture {
+        name: String,
+        token: Box<MatchToken>,
+        min: usize,
+        max: usize,
+    },
 }
 
-#[derive(Debug, Clone, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, Display, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MatchDataTypes {
     Data,
     Signature,
     PublicKey,
     PublicKeyHash,
+    Number,
 }
 
 #[derive(Debug, Clone)]
 pub struct ScriptTemplate(Vec<MatchToken>);
 
 impl ScriptTemplate {
+    /// Parses a named placeholder such as `<pkh:PublicKeyHash>`, optionally followed by `?`, `*`, `+`, `{n}`, `{min,}` or `{min,max}`.
+    /// The type is Data, Signature, PublicKey, PublicKeyHash, Number or any other template token, for example `<hash:OP_DATA=32>`.
+    fn map_string_to_capture(code: &str, allowed_non_script_data: bool) -> Result<MatchToken, ScriptTemplateErrors> {
+        let parse_error = |reason: &str| ScriptTemplateErrors::PlaceholderParse(code.to_string(), reason.to_string());
+
+        let (placeholder, quantifier) = code[1..].split_once('>').ok_or_else(|| parse_error("Missing closing >"))?;
+        let (name, kind) = placeholder.split_once(':').ok_or_else(|| parse_error("Expected <name:Type>"))?;
+        if name.is_empty() {
+            return Err(parse_error("Missing name"));
+        }
+
+        let token = match kind {
+            "Data" => MatchToken::AnyData,
+            "Signature" => MatchToken::Signature,
+            "PublicKey" => MatchToken::PublicKey,
+            "PublicKeyHash" => MatchToken::PublicKeyHash,
+            "Number" => MatchToken::Number,
+            _ => ScriptTemplate::map_string_to_match_token(kind, allowed_non_script_data)?,
+        };
+
+        let parse_count = |count: &str| usize::from_str(count).map_err(|e| parse_error(&e.to_string()));
+        let (min, max) = match quantifier {
+            "" => (1, 1),
+            "?" => (0, 1),
+            "*" => (0, usize::MAX),
+            "+" => (1, usize::MAX),
+            _ => match quantifier.strip_prefix('{').and_then(|x| x.strip_suffix('}')).map(|x| x.split_once(',')) {
+                Some(Some((min, ""))) => (parse_count(min)?, usize::MAX),
+                Some(Some((min, max))) => (parse_count(min)?, parse_count(max)?),
+                Some(None) => {
+                    let count = parse_count(&quantifier[1..quantifier.len() - 1])?;
+                    (count, count)
+                }
+                None => return Err(parse_error("Unknown quantifier")),
+            },
+        };
+        if min > max {
+            return Err(parse_error("Minimum count is above the maximum"));
+        }
+
+        Ok(MatchToken::Capture {
+            name: name.to_string(),
+            token: Box::new(token),
+            min,
+            max,
+        })
+    }
+
     fn map_string_to_match_token(code: &str, allowed_non_script_data: bool) -> Result<MatchToken, ScriptTemplateErrors> {
+        if code.starts_with('<') {
+            return ScriptTemplate::map_string_to_capture(code, allowed_non_script_data);
+        }
+
         // Number OP_CODES
         if code.len() < 3 {
             if let Ok(num_code) = u8::from_str(code) {
@@ -198,80 +266,216 @@ impl ScriptTemplate {
 //     }
 // }
 
+impl MatchToken {
+    /// Matches a single script bit, returning the data it extracts. Named captures are matched by `Script::match_tokens`.
+    fn match_bit(&self, bit: &ScriptBit) -> Result<Option<(MatchDataTypes, Vec<u8>)>, BSVErrors> {
+        let extracted = match (self, bit) {
+            (MatchToken::OpCode(tmpl_code), ScriptBit::OpCode(op_code)) if tmpl_code == op_code => None,
+            (MatchToken::Push(tmpl_data), ScriptBit::Push(data)) if tmpl_data == data => None,
+            (MatchToken::PushData(tmpl_op, tmpl_data), ScriptBit::PushData(op, data)) if tmpl_op == op && tmpl_data == data => None,
+
+            (MatchToken::Data(len, constraint), ScriptBit::PushData(_, data) | ScriptBit::Push(data))
+                if match constraint {
+                    DataLengthConstraints::Equals => data.len() == *len,
+                    DataLengthConstraints::GreaterThan => data.len() > *len,
+                    DataLengthConstraints::LessThan => data.len() < *len,
+                    DataLengthConstraints::GreaterThanOrEquals => data.len() >= *len,
+                    DataLengthConstraints::LessThanOrEquals => data.len() <= *len,
+                } =>
+            {
+                Some((MatchDataTypes::Data, data.clone()))
+            }
+
+            (MatchToken::AnyData, ScriptBit::Push(data) | ScriptBit::PushData(_, data) | ScriptBit::NonScriptData(data)) => Some((MatchDataTypes::Data, data.clone())),
+            (MatchToken::Signature, ScriptBit::Push(data)) => {
+                Signature::from_der_impl(data)?;
+                Some((MatchDataTypes::Signature, data.clone()))
+            }
+            (MatchToken::PublicKey, ScriptBit::Push(data)) => {
+                PublicKey::from_bytes_impl(data)?;
+                Some((MatchDataTypes::PublicKey, data.clone()))
+            }
+            (MatchToken::PublicKeyHash, ScriptBit::Push(data)) if data.len() == 20 => Some((MatchDataTypes::PublicKeyHash, data.clone())), // OP_HASH160
+
+            (MatchToken::Number, ScriptBit::OpCode(code)) if matches!(code, OpCodes::OP_0 | OpCodes::OP_1NEGATE) || (OpCodes::OP_1 as u8..=OpCodes::OP_16 as u8).contains(&(*code as u8)) => {
+                let number = match code {
+                    OpCodes::OP_0 => 0,
+                    OpCodes::OP_1NEGATE => -1,
+                    _ => *code as i64 - OpCodes::OP_1 as i64 + 1,
+                };
+                Some((MatchDataTypes::Number, ScriptNum::from(number).to_bytes()))
+            }
+            (MatchToken::Number, ScriptBit::Push(data)) if data.len() <= 8 && ScriptNum::is_minimally_encoded(data) => Some((MatchDataTypes::Number, data.clone())),
+
+            _ => return Err(BSVErrors::GenericError(format!("{} != {}", self, bit))),
+        };
+
+        Ok(extracted)
+    }
+}
+
+/// Data extracted by a template token, with the name of the placeholder that captured it.
+type TemplateMatch = (Option<String>, MatchDataTypes, Vec<u8>);
+
 /**
  * Script Template
  */
 impl Script {
-    pub fn match_impl(&self, script_template: &ScriptTemplate) -> Result<Vec<(MatchDataTypes, Vec<u8>)>, ScriptTemplateErrors> {
-        if self.0.len() != script_template.0.len() {
-            return Err(ScriptTemplateErrors::LengthsDiffer);
+    /// Matches `bits[index..]` against the tokens, trying the longest run of each named capture first.
+    /// On failure `failure` holds the error at the furthest bit any attempt reached.
+    /// `failed` remembers the (remaining tokens, bit index) pairs that cannot match, so each is only tried once.
+    fn match_tokens(tokens: &[MatchToken], bits: &[ScriptBit], index: usize, matches: &mut Vec<TemplateMatch>, failure: &mut Option<(usize, ScriptTemplateErrors)>, failed: &mut HashSet<(usize, usize)>) -> bool {
+        if failed.contains(&(tokens.len(), index)) {
+            return false;
         }
 
-        let mut matches = vec![];
+        let mut fail = |at: usize, error: ScriptTemplateErrors| {
+            if failure.as_ref().is_none_or(|(furthest, _)| at >= *furthest) {
+                *failure = Some((at, error));
+            }
+            false
+        };
 
-        for (i, (template, script)) in script_template.0.iter().zip(self.0.iter()).enumerate() {
-            let is_match = match (template, script) {
-                (MatchToken::OpCode(tmpl_code), ScriptBit::OpCode(op_code)) => Ok(tmpl_code == op_code),
-                (MatchToken::Push(tmpl_data), ScriptBit::Push(data)) => Ok(*tmpl_data == *data),
-                (MatchToken::PushData(tmpl_op, tmpl_data), ScriptBit::PushData(op, data)) => Ok(tmpl_op == op && tmpl_data == data),
-
-                (MatchToken::Data(len, constraint), ScriptBit::PushData(_, data) | ScriptBit::Push(data)) => match constraint {
-                    DataLengthConstraints::Equals => Ok(&data.len() == len),
-                    DataLengthConstraints::GreaterThan => Ok(&data.len() > len),
-                    DataLengthConstraints::LessThan => Ok(&data.len() < len),
-                    DataLengthConstraints::GreaterThanOrEquals => Ok(&data.len() >= len),
-                    DataLengthConstraints::LessThanOrEquals => Ok(&data.len() <= len),
-                },
-
-                (MatchToken::AnyData, ScriptBit::Push(_)) => Ok(true),
-                (MatchToken::AnyData, ScriptBit::PushData(_, _)) => Ok(true),
-                (MatchToken::AnyData, ScriptBit::NonScriptData(_)) => Ok(true),
-                (MatchToken::Signature, ScriptBit::Push(sig_buf)) => Signature::from_der_impl(sig_buf).map(|_| true),
-
-                (MatchToken::PublicKey, ScriptBit::Push(pubkey_buf)) => PublicKey::from_bytes_impl(pubkey_buf).map(|_| true),
-
-                (MatchToken::PublicKeyHash, ScriptBit::Push(pubkeyhash_buf)) => Ok(pubkeyhash_buf.len() == 20), // OP_HASH160
-
-                _ => Ok(false),
-            };
-
-            match is_match {
-                Ok(true) => (),
-                Ok(false) => {
-                    return Err(ScriptTemplateErrors::MatchFailure(
-                        i,
-                        template.clone(),
-                        script.clone(),
-                        BSVErrors::GenericError(format!("{} != {}", template, script)),
-                    ));
-                }
+        let (token, rest) = match tokens.split_first() {
+            Some(v) => v,
+            None if index == bits.len() => return true,
+            None => return fail(index, ScriptTemplateErrors::LengthsDiffer),
+        };
+
+        let (inner, name, min, max) = match token {
+            MatchToken::Capture { name, token, min, max } => (token.as_ref(), Some(name), *min, *max),
+            _ => (token, None, 1, 1),
+        };
+
+        let mut extracted = vec![];
+        for (offset, bit) in bits[index..].iter().enumerate().take(max) {
+            match inner.match_bit(bit) {
+                Ok(data) => extracted.push(data),
                 Err(e) => {
-                    return Err(ScriptTemplateErrors::MatchFailure(i, template.clone(), script.clone(), e));
+                    fail(index + offset, ScriptTemplateErrors::MatchFailure(index + offset, Box::new(token.clone()), Box::new(bit.clone()), Box::new(e)));
+                    break;
                 }
             }
+        }
+        if extracted.len() < min && index + extracted.len() == bits.len() {
+            return fail(bits.len(), ScriptTemplateErrors::LengthsDiffer);
+        }
 
-            // Now that we know script bit is a match, we can add the data parts to the matches array.
-            match (template, script) {
-                (MatchToken::Data(_, _), ScriptBit::PushData(_, data) | ScriptBit::Push(data)) => matches.push((MatchDataTypes::Data, data.clone())),
+        for count in (min..=extracted.len()).rev() {
+            let matched = matches.len();
+            matches.extend(extracted[..count].iter().flatten().map(|(data_type, data)| (name.cloned(), data_type.clone(), data.clone())));
+            if Script::match_tokens(rest, bits, index + count, matches, failure, failed) {
+                return true;
+            }
+            matches.truncate(matched);
+        }
 
-                (MatchToken::AnyData, ScriptBit::Push(data)) => matches.push((MatchDataTypes::Data, data.clone())),
-                (MatchToken::AnyData, ScriptBit::PushData(_, data)) => matches.push((MatchDataTypes::Data, data.clone())),
-                (MatchToken::AnyData, ScriptBit::NonScriptData(data)) => matches.push((MatchDataTypes::Data, data.clone())),
-                (MatchToken::Signature, ScriptBit::Push(data)) => matches.push((MatchDataTypes::Signature, data.clone())),
+        failed.insert((tokens.len(), index));
+        false
+    }
 
-                (MatchToken::PublicKey, ScriptBit::Push(data)) => matches.push((MatchDataTypes::PublicKey, data.clone())),
+    fn match_template(&self, script_template: &ScriptTemplate) -> Result<Vec<TemplateMatch>, ScriptTemplateErrors> {
+        let bits = self.to_flat_script_bits();
+        let (min_len, max_len) = script_template.0.iter().fold((0_usize, 0_usize), |(min_len, max_len), token| match token {
+            MatchToken::Capture { min, max, .. } => (min_len.saturating_add(*min), max_len.saturating_add(*max)),
+            _ => (min_len + 1, max_len.saturating_add(1)),
+        });
+        if bits.len() < min_len || bits.len() > max_len {
+            return Err(ScriptTemplateErrors::LengthsDiffer);
+        }
 
-                (MatchToken::PublicKeyHash, ScriptBit::Push(data)) => matches.push((MatchDataTypes::PublicKeyHash, data.clone())), // OP_HASH160
-                _ => (),
-            }
+        let mut matches = vec![];
+        let mut failure = None;
+        match Script::match_tokens(&script_template.0, &bits, 0, &mut matches, &mut failure, &mut HashSet::new()) {
+            true => Ok(matches),
+            false => Err(failure.map(|(_, e)| e).unwrap_or(ScriptTemplateErrors::LengthsDiffer)),
         }
+    }
 
-        Ok(matches)
+    pub fn match_impl(&self, script_template: &ScriptTemplate) -> Result<Vec<(MatchDataTypes, Vec<u8>)>, ScriptTemplateErrors> {
+        Ok(self.match_template(script_template)?.into_iter().map(|(_, data_type, data)| (data_type, data)).collect())
     }
 
     pub fn test_impl(&self, script_template: &ScriptTemplate) -> bool {
         self.match_impl(script_template).is_ok()
     }
+
+    pub fn captures_impl(&self, script_template: &ScriptTemplate) -> Result<TemplateCaptures, ScriptTemplateErrors> {
+        let mut captures: HashMap<String, Vec<(MatchDataTypes, Vec<u8>)>> = script_template
+            .0
+            .iter()
+            .filter_map(|token| match token {
+                MatchToken::Capture { name, .. } => Some((name.clone(), vec![])),
+                _ => None,
+            })
+            .collect();
+
+        for (name, data_type, data) in self.match_template(script_template)? {
+            if let Some(values) = name.and_then(|name| captures.get_mut(&name)) {
+                values.push((data_type, data));
+            }
+        }
+
+        Ok(TemplateCaptures(captures))
+    }
+}
+
+/// The data captured by the named placeholders of a ScriptTemplate, every placeholder of the template has an entry even if it matched nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateCaptures(HashMap<String, Vec<(MatchDataTypes, Vec<u8>)>>);
+
+impl TemplateCaptures {
+    /// Gets every value captured by the placeholder in script order.
+    pub fn get_all(&self, name: &str) -> Result<Vec<Vec<u8>>, BSVErrors> {
+        match self.0.get(name) {
+            Some(values) => Ok(values.iter().map(|(_, data)| data.clone()).collect()),
+            None => Err(BSVErrors::OutOfBounds(format!("Template has no placeholder named {}", name))),
+        }
+    }
+
+    /// Gets the first value captured by the placeholder.
+    pub fn get(&self, name: &str) -> Result<Vec<u8>, BSVErrors> {
+        self.get_all(name)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| BSVErrors::OutOfBounds(format!("Placeholder {} did not capture anything", name)))
+    }
+
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        self.0.keys().cloned().collect()
+    }
+
+    pub fn public_key(&self, name: &str) -> Result<PublicKey, BSVErrors> {
+        PublicKey::from_bytes_impl(&self.get(name)?)
+    }
+
+    pub fn public_keys(&self, name: &str) -> Result<Vec<PublicKey>, BSVErrors> {
+        self.get_all(name)?.iter().map(|x| PublicKey::from_bytes_impl(x)).collect()
+    }
+
+    /// Gets the signature without its sighash byte.
+    pub fn signature(&self, name: &str) -> Result<Signature, BSVErrors> {
+        Signature::from_der_impl(&self.get(name)?)
+    }
+
+    /// Gets the address of a captured public key hash or public key.
+    pub fn address(&self, name: &str) -> Result<P2PKHAddress, BSVErrors> {
+        let data = self.get(name)?;
+        match data.len() {
+            20 => P2PKHAddress::from_pubkey_hash_impl(&data),
+            _ => P2PKHAddress::from_pubkey_impl(&PublicKey::from_bytes_impl(&data)?),
+        }
+    }
+
+    /// Decodes the capture as a script number.
+    pub fn int(&self, name: &str) -> Result<i64, BSVErrors> {
+        let data = self.get(name)?;
+        ScriptNum::from_bytes(&data, 8, false)
+            .ok()
+            .and_then(|x| x.to_i64())
+            .ok_or_else(|| BSVErrors::OutOfBounds(format!("Placeholder {} did not capture a 64 bit number", name)))
+    }
 }
 
 impl Script {
@@ -307,6 +511,22 @@ impl Script {
     pub fn is_match(&self, script_template: &ScriptTemplate) -> bool {
         self.test_impl(script_template)
     }
+
+    /// Matches the Script against the provided ScriptTemplate and returns the data captured by its named placeholders.
+    ///
+    /// # Example
+    /// ```
+    /// use bsv::{Script, ScriptTemplate};
+    ///
+    /// let script = Script::from_asm_string("OP_DUP OP_HASH160 f9dfc5a4ae5256e5938c2d819738f7b57e4d7b46 OP_EQUALVERIFY OP_CHECKSIG").unwrap();
+    /// let script_template = ScriptTemplate::from_asm_string("OP_DUP OP_HASH160 <receiver:PublicKeyHash> OP_EQUALVERIFY OP_CHECKSIG").unwrap();
+    ///
+    /// let captures = script.captures(&script_template).unwrap();
+    /// assert_eq!(captures.get("receiver").unwrap(), hex::decode("f9dfc5a4ae5256e5938c2d819738f7b57e4d7b46").unwrap());
+    /// ```
+    pub fn captures(&self, script_template: &ScriptTemplate) -> Result<TemplateCaptures, ScriptTemplateErrors> {
+        self.captures_impl(script_template)
+    }
 }
 
 //#[cfg(all(feature = "wasm-bindgen-script-template"))]
@@ -0,0 +1,289 @@
+use crate::{BSVErrors, Hash, VarIntReader, VarIntWriter};
+use byteorder::{ReadBytesExt, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Cursor, Read, Write};
+
+const FLAG_DUPLICATE: u8 = 0x01;
+const FLAG_TXID: u8 = 0x02;
+/// Offsets are u64, so no tree is taller than 64 levels below its root
+const MAX_TREE_HEIGHT: usize = 64;
+
+/// A node of a merkle tree, identified by its offset within its level.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerklePathLeaf {
+    pub offset: u64,
+    /// Hash in the byte order of `Transaction::get_id_bytes`, None if the node is the last of an odd length level and duplicates its sibling
+    pub hash: Option<Vec<u8>>,
+    /// Whether the hash is the id of a transaction the path proves
+    pub txid: bool,
+}
+
+impl MerklePathLeaf {
+    #[must_use]
+    pub fn new(offset: u64, hash: &[u8], txid: bool) -> MerklePathLeaf {
+        MerklePathLeaf {
+            offset,
+            hash: Some(hash.to_vec()),
+            txid,
+        }
+    }
+
+    #[must_use]
+    pub fn duplicate(offset: u64) -> MerklePathLeaf {
+        MerklePathLeaf { offset, hash: None, txid: false }
+    }
+}
+
+/// The BSV Unified Merkle Path (BUMP, BRC-74), proving that transactions are included in the block at a height.
+/// `path[0]` holds the transactions and their siblings, each later level the nodes needed above them that cannot be computed from the level below.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerklePath {
+    pub block_height: u64,
+    pub path: Vec<Vec<MerklePathLeaf>>,
+}
+
+impl MerklePath {
+    fn reversed(hash: &[u8]) -> Vec<u8> {
+        hash.iter().rev().copied().collect()
+    }
+
+    /// Hashes two nodes given in the byte order of `Transaction::get_id_bytes`.
+    fn hash_nodes(left: &[u8], right: &[u8]) -> Vec<u8> {
+        let mut hash = Hash::sha_256d(&[MerklePath::reversed(left), MerklePath::reversed(right)].concat()).to_bytes();
+        hash.reverse();
+        hash
+    }
+
+    /// Whether the block only holds its coinbase, which is then the merkle root.
+    fn is_single_transaction(&self) -> bool {
+        self.path.len() == 1 && self.path[0].len() == 1
+    }
+
+    /// Gets the hash of the node at the offset, computing it from the level below if it is not in the path.
+    /// None if the node duplicates its sibling.
+    fn node(&self, height: usize, offset: u64) -> Result<Option<Vec<u8>>, BSVErrors> {
+        if let Some(leaf) = self.path[height].iter().find(|x| x.offset == offset) {
+            return Ok(leaf.hash.clone());
+        }
+
+        if height == 0 {
+            return Err(BSVErrors::MerklePath(format!("Missing the node at height {} offset {}", height, offset)));
+        }
+
+        let left = self
+            .node(height - 1, offset * 2)?
+            .ok_or_else(|| BSVErrors::MerklePath(format!("The node at height {} offset {} cannot be a duplicate", height - 1, offset * 2)))?;
+        let right = self.node(height - 1, offset * 2 + 1)?.unwrap_or_else(|| left.clone());
+        Ok(Some(MerklePath::hash_nodes(&left, &right)))
+    }
+
+    fn find_txid_offset(&self, txid: &[u8]) -> Result<u64, BSVErrors> {
+        self.path[0]
+            .iter()
+            .find(|x| x.hash.as_deref() == Some(txid))
+            .map(|x| x.offset)
+            .ok_or_else(|| BSVErrors::MerklePath(format!("Transaction {} is not in the path", hex::encode(txid))))
+    }
+}
+
+impl MerklePath {
+    /// Creates a merkle path, sorting each level by offset.
+    pub fn new(block_height: u64, path: Vec<Vec<MerklePathLeaf>>) -> Result<MerklePath, BSVErrors> {
+        if path.first().is_none_or(|x| x.is_empty()) {
+            return Err(BSVErrors::MerklePath("The first level of the path must hold a transaction".into()));
+        }
+        if path.len() > MAX_TREE_HEIGHT {
+            return Err(BSVErrors::MerklePath(format!("A path cannot have more than {} levels", MAX_TREE_HEIGHT)));
+        }
+
+        let mut path = path;
+        for (height, level) in path.iter_mut().enumerate() {
+            level.sort_by_key(|x| x.offset);
+            if level.windows(2).any(|x| x[0].offset == x[1].offset) {
+                return Err(BSVErrors::MerklePath(format!("Repeated offset at height {}", height)));
+            }
+            if level.iter().any(|x| x.hash.as_ref().is_some_and(|x| x.len() != 32)) {
+                return Err(BSVErrors::MerklePath(format!("Hashes at height {} must be 32 bytes", height)));
+            }
+        }
+
+        Ok(MerklePath { block_height, path })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<MerklePath, BSVErrors> {
+        let mut cursor = Cursor::new(bytes.to_vec());
+
+        // Block Height - VarInt
+        let block_height = match cursor.read_varint() {
+            Ok(v) => v,
+            Err(e) => return Err(BSVErrors::DeserialiseMerklePath("block_height".to_string(), e)),
+        };
+
+        // Tree Height - 1 byte
+        let tree_height = match cursor.read_u8() {
+            Ok(v) => v,
+            Err(e) => return Err(BSVErrors::DeserialiseMerklePath("tree_height".to_string(), e)),
+        };
+
+        let mut path = vec![];
+        for _ in 0..tree_height {
+            // Leaf Count - VarInt
+            let n_leaves = match cursor.read_varint() {
+                Ok(v) => v,
+                Err(e) => return Err(BSVErrors::DeserialiseMerklePath("n_leaves".to_string(), e)),
+            };
+
+            let mut level = vec![];
+            for _ in 0..n_leaves {
+                // Offset - VarInt
+                let offset = match cursor.read_varint() {
+                    Ok(v) => v,
+                    Err(e) => return Err(BSVErrors::DeserialiseMerklePath("offset".to_string(), e)),
+                };
+
+                // Flags - 1 byte
+                let flags = match cursor.read_u8() {
+                    Ok(v) => v,
+                    Err(e) => return Err(BSVErrors::DeserialiseMerklePath("flags".to_string(), e)),
+                };
+
+                if flags & FLAG_DUPLICATE != 0 {
+                    level.push(MerklePathLeaf::duplicate(offset));
+                    continue;
+                }
+
+                // Hash - 32 bytes, written in reverse like txids in TxIns
+                let mut hash = vec![0; 32];
+                if let Err(e) = cursor.read_exact(&mut hash) {
+                    return Err(BSVErrors::DeserialiseMerklePath("hash".to_string(), e));
+                }
+                hash.reverse();
+                level.push(MerklePathLeaf::new(offset, &hash, flags & FLAG_TXID != 0));
+            }
+            path.push(level);
+        }
+
+        MerklePath::new(block_height, path)
+    }
+
+    pub fn from_hex(hex_str: &str) -> Result<MerklePath, BSVErrors> {
+        MerklePath::from_bytes(&hex::decode(hex_str)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BSVErrors> {
+        let mut buffer = vec![];
+        buffer.write_varint(self.block_height)?;
+        buffer.write_u8(self.path.len() as u8)?;
+
+        for level in &self.path {
+            buffer.write_varint(level.len() as u64)?;
+            for leaf in level {
+                buffer.write_varint(leaf.offset)?;
+                match &leaf.hash {
+                    Some(hash) => {
+                        buffer.write_u8(if leaf.txid { FLAG_TXID } else { 0 })?;
+                        buffer.write_all(&MerklePath::reversed(hash))?;
+                    }
+                    None => buffer.write_u8(FLAG_DUPLICATE)?,
+                }
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    pub fn to_hex(&self) -> Result<String, BSVErrors> {
+        Ok(hex::encode(self.to_bytes()?))
+    }
+
+    /// Gets the ids of the transactions the path proves.
+    #[must_use]
+    pub fn txids(&self) -> Vec<Vec<u8>> {
+        self.path[0].iter().filter(|x| x.txid).filter_map(|x| x.hash.clone()).collect()
+    }
+
+    /// Computes the merkle root of the block from the transaction id, in the byte order of `Transaction::get_id_bytes`.
+    /// The root only proves the transaction is in the block once it is checked against the block header at `block_height`.
+    pub fn compute_root(&self, txid: &[u8]) -> Result<Vec<u8>, BSVErrors> {
+        let offset = self.find_txid_offset(txid)?;
+        if self.is_single_transaction() {
+            return Ok(txid.to_vec());
+        }
+
+        let mut root = txid.to_vec();
+        for height in 0..self.path.len() {
+            let position = offset >> height;
+            let sibling = self.node(height, position ^ 1)?.unwrap_or_else(|| root.clone());
+            root = match position & 1 {
+                0 => MerklePath::hash_nodes(&root, &sibling),
+                _ => MerklePath::hash_nodes(&sibling, &root),
+            };
+        }
+
+        Ok(root)
+    }
+
+    pub fn compute_root_hex(&self, txid_hex: &str) -> Result<String, BSVErrors> {
+        Ok(hex::encode(self.compute_root(&hex::decode(txid_hex)?)?))
+    }
+
+    /// Creates the smallest path that proves the given transactions, which must be in this path.
+    pub fn extract(&self, txids: &[Vec<u8>]) -> Result<MerklePath, BSVErrors> {
+        let offsets = txids.iter().map(|x| Ok((self.find_txid_offset(x)?, x.clone()))).collect::<Result<BTreeMap<_, _>, BSVErrors>>()?;
+        if self.is_single_transaction() {
+            return Ok(self.clone());
+        }
+
+        let mut path = vec![];
+        for height in 0..self.path.len() {
+            // Nodes on the way up from a transaction are computed, only their siblings are needed
+            let positions = offsets.keys().map(|x| x >> height).collect::<BTreeSet<_>>();
+            let mut level = match height {
+                0 => offsets.iter().map(|(offset, txid)| MerklePathLeaf::new(*offset, txid, true)).collect(),
+                _ => vec![],
+            };
+
+            for sibling in positions.iter().map(|x| x ^ 1).filter(|x| !positions.contains(x)) {
+                level.push(match self.node(height, sibling)? {
+                    Some(hash) => MerklePathLeaf::new(sibling, &hash, false),
+                    None => MerklePathLeaf::duplicate(sibling),
+                });
+            }
+            path.push(level);
+        }
+
+        MerklePath::new(self.block_height, path)
+    }
+
+    /// Combines paths of the same block into one proving the transactions of both.
+    pub fn combine(&self, other: &MerklePath) -> Result<MerklePath, BSVErrors> {
+        if self.block_height != other.block_height {
+            return Err(BSVErrors::MerklePath(format!("Unable to combine paths of blocks {} and {}", self.block_height, other.block_height)));
+        }
+
+        let roots = [self, other]
+            .iter()
+            .map(|x| match x.txids().first() {
+                Some(txid) => x.compute_root(txid),
+                None => Err(BSVErrors::MerklePath("Unable to combine a path without transactions".into())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if roots[0] != roots[1] || self.path.len() != other.path.len() {
+            return Err(BSVErrors::MerklePath("Unable to combine paths with different merkle roots".into()));
+        }
+
+        let mut path = self.path.clone();
+        for (level, other_level) in path.iter_mut().zip(&other.path) {
+            for leaf in other_level {
+                match level.iter_mut().find(|x| x.offset == leaf.offset) {
+                    Some(existing) => existing.txid |= leaf.txid,
+                    None => level.push(leaf.clone()),
+                }
+            }
+        }
+
+        let combined = MerklePath::new(self.block_height, path)?;
+        combined.extract(&combined.txids())
+    }
+}
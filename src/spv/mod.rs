@@ -0,0 +1,3 @@
+mod merkle_path;
+
+pub use merkle_path::*;
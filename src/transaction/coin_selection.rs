@@ -0,0 +1,325 @@
+use crate::{BSVErrors, FeeModel, Script, SizeEstimator, Transaction, TxOut, Utxo, DEFAULT_DUST_LIMIT};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Number of branches branch and bound explores before settling for the best selection found so far.
+const BRANCH_AND_BOUND_TRIES: usize = 100_000;
+
+/// Size of a P2PKH output, the satoshis, the script length and the 25 byte locking script.
+pub const P2PKH_OUTPUT_SIZE: usize = 8 + 1 + 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CoinSelectionStrategy {
+    /// Spends the largest UTXOs first, using as few inputs as possible.
+    LargestFirst,
+    /// Spends the smallest UTXOs first, consolidating small UTXOs.
+    SmallestFirst,
+    /// Searches for a set of UTXOs that pays the outputs and fee exactly enough that no change output is needed.
+    BranchAndBound,
+    /// Picks random UTXOs until the outputs are paid, then adds more while they bring the change closer to the amount paid.
+    /// Leaves change outputs of similar size to the payments, which keeps the UTXO set useful for later payments.
+    RandomImprove,
+}
+
+/// The UTXOs chosen to pay for a set of outputs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoinSelection {
+    pub utxos: Vec<Utxo>,
+    /// Fee paid by the transaction, including any amount left to the miner when change would be dust
+    pub fee: u64,
+    /// Satoshis for the change output, None if the transaction has no change
+    pub change: Option<u64>,
+}
+
+impl CoinSelection {
+    /// Gets the total satoshis of the selected UTXOs.
+    #[must_use]
+    pub fn satoshis(&self) -> u64 {
+        self.utxos.iter().map(|x| x.satoshis).sum()
+    }
+}
+
+/// A UTXO with the bytes spending it adds to the transaction.
+struct Candidate<'a> {
+    utxo: &'a Utxo,
+    size: usize,
+}
+
+/// The outputs being paid for, with the bytes of the transaction before any inputs are added.
+struct Payment {
+    satoshis_out: u64,
+    standard_size: usize,
+    data_size: usize,
+}
+
+impl Payment {
+    fn new(outputs: &[TxOut]) -> Result<Payment, BSVErrors> {
+        let mut tx = Transaction::new(1, 0);
+        tx.add_outputs(outputs.to_vec());
+        let data_size = FeeModel::data_size(&tx);
+        Ok(Payment {
+            satoshis_out: tx.satoshis_out(),
+            // Without the input count, which depends on the selection
+            standard_size: tx.get_size()? - data_size - 1,
+            data_size,
+        })
+    }
+}
+
+/// Selects UTXOs to pay for outputs, accounting for the fee every input adds and the change output.
+#[derive(Debug, Clone)]
+pub struct CoinSelector {
+    fee_model: FeeModel,
+    dust_limit: u64,
+    size_estimator: SizeEstimator,
+    change_output_size: usize,
+}
+
+impl Default for CoinSelector {
+    fn default() -> CoinSelector {
+        CoinSelector::new(&FeeModel::default())
+    }
+}
+
+impl CoinSelector {
+    fn varint_size(length: usize) -> usize {
+        match length {
+            0..=0xfc => 1,
+            0xfd..=0xffff => 3,
+            0x10000..=0xffffffff => 5,
+            _ => 9,
+        }
+    }
+
+    fn input_size(&self, utxo: &Utxo) -> Result<usize, BSVErrors> {
+        let unlocking_script_size = self
+            .size_estimator
+            .unlocking_script_size(&utxo.locking_script)
+            .ok_or_else(|| BSVErrors::CoinSelection(format!("Unable to estimate the unlocking script size of {}:{}", hex::encode(&utxo.tx_id), utxo.vout)))?;
+
+        // Outpoint, unlocking script and sequence
+        Ok(32 + 4 + CoinSelector::varint_size(unlocking_script_size) + unlocking_script_size + 4)
+    }
+
+    /// Gets the size of the transaction spending the selection, without a change output.
+    fn standard_size(payment: &Payment, selection: &[&Candidate]) -> usize {
+        payment.standard_size + CoinSelector::varint_size(selection.len()) + selection.iter().map(|x| x.size).sum::<usize>()
+    }
+
+    /// Gets the fee and change of spending the selection, None if it does not cover the outputs and fee.
+    fn evaluate(&self, payment: &Payment, selection: &[&Candidate]) -> Option<(u64, Option<u64>)> {
+        let standard_size = CoinSelector::standard_size(payment, selection);
+        let satoshis_in = selection.iter().map(|x| x.utxo.satoshis).sum::<u64>();
+
+        let fee_with_change = self.fee_model.fee_for_size(standard_size + self.change_output_size, payment.data_size);
+        if let Some(change) = satoshis_in.checked_sub(payment.satoshis_out + fee_with_change).filter(|x| *x >= self.dust_limit) {
+            return Some((fee_with_change, Some(change)));
+        }
+
+        let fee = self.fee_model.fee_for_size(standard_size, payment.data_size);
+        (satoshis_in >= payment.satoshis_out + fee).then(|| (satoshis_in - payment.satoshis_out, None))
+    }
+
+    /// Value of a candidate after the fee for spending it, in thousandths of a satoshi.
+    fn effective_value(&self, candidate: &Candidate) -> i128 {
+        candidate.utxo.satoshis as i128 * 1000 - candidate.size as i128 * self.fee_model.standard_rate as i128
+    }
+
+    fn to_selection(selection: &[&Candidate], (fee, change): (u64, Option<u64>)) -> CoinSelection {
+        CoinSelection {
+            utxos: selection.iter().map(|x| x.utxo.clone()).collect(),
+            fee,
+            change,
+        }
+    }
+
+    /// Adds candidates in order until the outputs and fee are paid.
+    fn select_in_order(&self, payment: &Payment, candidates: &[Candidate]) -> Option<CoinSelection> {
+        let mut selection = vec![];
+        for candidate in candidates {
+            selection.push(candidate);
+            if let Some(result) = self.evaluate(payment, &selection) {
+                return Some(CoinSelector::to_selection(&selection, result));
+            }
+        }
+        None
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn branch_and_bound(
+        &self,
+        effective_values: &[i128],
+        index: usize,
+        current: i128,
+        remaining: i128,
+        (target, upper_bound): (i128, i128),
+        selected: &mut Vec<usize>,
+        best: &mut Option<(i128, Vec<usize>)>,
+        tries: &mut usize,
+    ) {
+        if *tries == 0 || current > upper_bound || current + remaining < target {
+            return;
+        }
+        *tries -= 1;
+
+        if current >= target {
+            if best.as_ref().is_none_or(|(excess, _)| current - target < *excess) {
+                *best = Some((current - target, selected.clone()));
+            }
+            return;
+        }
+
+        let Some(value) = effective_values.get(index) else {
+            return;
+        };
+
+        selected.push(index);
+        self.branch_and_bound(effective_values, index + 1, current + value, remaining - value, (target, upper_bound), selected, best, tries);
+        selected.pop();
+        self.branch_and_bound(effective_values, index + 1, current, remaining - value, (target, upper_bound), selected, best, tries);
+    }
+
+    fn select_branch_and_bound(&self, payment: &Payment, candidates: &[Candidate]) -> Option<CoinSelection> {
+        let base_fee = CoinSelector::standard_size(payment, &[]) as i128 * self.fee_model.standard_rate as i128 + payment.data_size as i128 * self.fee_model.data_rate as i128;
+        let target = payment.satoshis_out as i128 * 1000 + base_fee;
+        // Any more than this and the excess would pay for a change output above the dust limit
+        let upper_bound = target + self.change_output_size as i128 * self.fee_model.standard_rate as i128 + self.dust_limit as i128 * 1000;
+
+        let effective_values = candidates.iter().map(|x| self.effective_value(x)).collect::<Vec<_>>();
+        let mut best = None;
+        let mut tries = BRANCH_AND_BOUND_TRIES;
+        self.branch_and_bound(&effective_values, 0, 0, effective_values.iter().sum(), (target, upper_bound), &mut vec![], &mut best, &mut tries);
+
+        // The bounds leave out the bytes of a larger input count, so the selection is checked again
+        let selection = best?.1.iter().map(|i| &candidates[*i]).collect::<Vec<_>>();
+        match self.evaluate(payment, &selection) {
+            Some(result @ (_, None)) => Some(CoinSelector::to_selection(&selection, result)),
+            _ => None,
+        }
+    }
+
+    fn select_random_improve(&self, payment: &Payment, candidates: &[Candidate], rng: &mut impl RngCore) -> Option<CoinSelection> {
+        let mut shuffled = candidates.iter().collect::<Vec<_>>();
+        for i in (1..shuffled.len()).rev() {
+            shuffled.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+        }
+
+        let mut selection = vec![];
+        let mut remaining = shuffled.into_iter();
+        let mut result = None;
+        for candidate in remaining.by_ref() {
+            selection.push(candidate);
+            result = self.evaluate(payment, &selection);
+            if result.is_some() {
+                break;
+            }
+        }
+        let mut result = result?;
+
+        // Aim for change about the size of the payment, without spending more than three times the payment
+        let ideal = payment.satoshis_out.saturating_mul(2);
+        let maximum = payment.satoshis_out.saturating_mul(3);
+        let mut satoshis_in = selection.iter().map(|x| x.utxo.satoshis).sum::<u64>();
+        for candidate in remaining {
+            let improved = satoshis_in + candidate.utxo.satoshis;
+            if improved > maximum || improved.abs_diff(ideal) >= satoshis_in.abs_diff(ideal) {
+                continue;
+            }
+
+            selection.push(candidate);
+            match self.evaluate(payment, &selection) {
+                Some(improved_result) => {
+                    result = improved_result;
+                    satoshis_in = improved;
+                }
+                None => {
+                    selection.pop();
+                }
+            }
+        }
+
+        Some(CoinSelector::to_selection(&selection, result))
+    }
+}
+
+impl CoinSelector {
+    /// Creates a selector for P2PKH UTXOs with P2PKH change.
+    #[must_use]
+    pub fn new(fee_model: &FeeModel) -> CoinSelector {
+        CoinSelector {
+            fee_model: *fee_model,
+            dust_limit: DEFAULT_DUST_LIMIT,
+            size_estimator: SizeEstimator::default(),
+            change_output_size: P2PKH_OUTPUT_SIZE,
+        }
+    }
+
+    /// Change below the dust limit is left to the miner, defaults to `DEFAULT_DUST_LIMIT`.
+    #[must_use]
+    pub fn set_dust_limit(mut self, dust_limit: u64) -> CoinSelector {
+        self.dust_limit = dust_limit;
+        self
+    }
+
+    /// Sets the estimator that gives the unlocking script size of each UTXO, UTXOs it does not know are an error.
+    #[must_use]
+    pub fn set_size_estimator(mut self, size_estimator: &SizeEstimator) -> CoinSelector {
+        self.size_estimator = size_estimator.clone();
+        self
+    }
+
+    /// Sets the script change is paid to, used for the size of the change output.
+    #[must_use]
+    pub fn set_change_script(mut self, change_script: &Script) -> CoinSelector {
+        let script_size = change_script.to_bytes().len();
+        self.change_output_size = 8 + CoinSelector::varint_size(script_size) + script_size;
+        self
+    }
+
+    /// Selects UTXOs from the candidates to pay the outputs and fee.
+    /// UTXOs costing more in fees than they are worth are never selected.
+    pub fn select(&self, candidates: &[Utxo], outputs: &[TxOut], strategy: CoinSelectionStrategy) -> Result<CoinSelection, BSVErrors> {
+        self.select_with_rng(candidates, outputs, strategy, &mut OsRng)
+    }
+
+    /// Selects UTXOs as `select` does, with the randomness for `CoinSelectionStrategy::RandomImprove` from `rng`.
+    pub fn select_with_rng(&self, candidates: &[Utxo], outputs: &[TxOut], strategy: CoinSelectionStrategy, rng: &mut impl RngCore) -> Result<CoinSelection, BSVErrors> {
+        let payment = Payment::new(outputs)?;
+
+        let mut useful = vec![];
+        for utxo in candidates {
+            let candidate = Candidate { utxo, size: self.input_size(utxo)? };
+            if self.effective_value(&candidate) > 0 {
+                useful.push(candidate);
+            }
+        }
+
+        let selection = match strategy {
+            CoinSelectionStrategy::LargestFirst => {
+                useful.sort_by_key(|x| std::cmp::Reverse(x.utxo.satoshis));
+                self.select_in_order(&payment, &useful)
+            }
+            CoinSelectionStrategy::SmallestFirst => {
+                useful.sort_by_key(|x| x.utxo.satoshis);
+                self.select_in_order(&payment, &useful)
+            }
+            CoinSelectionStrategy::BranchAndBound => {
+                useful.sort_by_key(|x| std::cmp::Reverse(self.effective_value(x)));
+                self.select_branch_and_bound(&payment, &useful)
+            }
+            CoinSelectionStrategy::RandomImprove => self.select_random_improve(&payment, &useful, rng),
+        };
+
+        if let Some(selection) = selection {
+            return Ok(selection);
+        }
+
+        let all = useful.iter().collect::<Vec<_>>();
+        if self.evaluate(&payment, &all).is_some() {
+            return Err(BSVErrors::CoinSelection("No selection of the UTXOs pays the outputs without change".into()));
+        }
+        let available = all.iter().map(|x| x.utxo.satoshis).sum();
+        let fee = self.fee_model.fee_for_size(CoinSelector::standard_size(&payment, &all), payment.data_size);
+        Err(BSVErrors::InsufficientFunds(available, payment.satoshis_out + fee))
+    }
+}
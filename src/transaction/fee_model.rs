@@ -0,0 +1,134 @@
+use crate::{BSVErrors, OpCodes, Script, ScriptBit, ScriptTemplate, Transaction};
+use serde::{Deserialize, Serialize};
+
+/// Default fee rate in satoshis per 1000 bytes.
+pub const DEFAULT_FEE_RATE: u64 = 500;
+
+/// Size of a P2PKH unlocking script, a push of the largest low S signature and a push of a compressed public key.
+pub const P2PKH_UNLOCKING_SCRIPT_SIZE: usize = 1 + 72 + 1 + 33;
+
+/// Fee rates in satoshis per 1000 bytes, with data outputs charged at their own rate as miners publish in their fee quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeModel {
+    pub standard_rate: u64,
+    /// Rate for the locking script bytes of OP_RETURN and OP_FALSE OP_RETURN outputs
+    pub data_rate: u64,
+}
+
+impl Default for FeeModel {
+    fn default() -> FeeModel {
+        FeeModel::flat(DEFAULT_FEE_RATE)
+    }
+}
+
+impl FeeModel {
+    #[must_use]
+    pub fn new(standard_rate: u64, data_rate: u64) -> FeeModel {
+        FeeModel { standard_rate, data_rate }
+    }
+
+    /// Charges every byte at the same rate.
+    #[must_use]
+    pub fn flat(rate: u64) -> FeeModel {
+        FeeModel::new(rate, rate)
+    }
+
+    /// Gets the fee for the given number of standard and data bytes, rounded up.
+    #[must_use]
+    pub fn fee_for_size(&self, standard_bytes: usize, data_bytes: usize) -> u64 {
+        (standard_bytes as u64 * self.standard_rate + data_bytes as u64 * self.data_rate).div_ceil(1000)
+    }
+
+    /// Gets the number of bytes in the transaction charged at the data rate.
+    #[must_use]
+    pub fn data_size(tx: &Transaction) -> usize {
+        tx.outputs
+            .iter()
+            .filter(|x| x.script_pub_key.is_provably_unspendable(true) || matches!(x.script_pub_key.0.first(), Some(ScriptBit::OpCode(OpCodes::OP_RETURN))))
+            .map(|x| x.script_pub_key.to_bytes().len())
+            .sum()
+    }
+
+    /// Gets the fee for the transaction as it is serialised now.
+    pub fn fee(&self, tx: &Transaction) -> Result<u64, BSVErrors> {
+        let data_size = FeeModel::data_size(tx);
+        Ok(self.fee_for_size(tx.get_size()? - data_size, data_size))
+    }
+
+    /// Gets the fee for the transaction once its unsigned inputs are signed, see `SizeEstimator`.
+    pub fn estimate_fee(&self, tx: &Transaction, estimator: &SizeEstimator) -> Result<u64, BSVErrors> {
+        self.fee(&estimator.estimate_signed_transaction(tx)?)
+    }
+
+    /// Whether the transaction pays at least the fee for its size. Needs the satoshis of every input.
+    pub fn is_paid_by(&self, tx: &Transaction) -> Result<bool, BSVErrors> {
+        let satoshis_in = tx
+            .satoshis_in()
+            .ok_or_else(|| BSVErrors::GenericError("Satoshis of every input are needed to work out the fee paid".into()))?;
+        let fee = self.fee(tx)?;
+        Ok(satoshis_in.checked_sub(tx.satoshis_out()).is_some_and(|paid| paid >= fee))
+    }
+}
+
+/// Predicts the size of a transaction once it is signed from the unlocking script sizes of known locking script templates.
+#[derive(Debug, Clone)]
+pub struct SizeEstimator {
+    templates: Vec<(ScriptTemplate, usize)>,
+}
+
+impl Default for SizeEstimator {
+    /// Knows P2PKH outputs spent with a compressed public key.
+    fn default() -> SizeEstimator {
+        let p2pkh = ScriptTemplate::from_asm_string("OP_DUP OP_HASH160 OP_PUBKEYHASH OP_EQUALVERIFY OP_CHECKSIG").unwrap();
+        SizeEstimator::empty().add_template(&p2pkh, P2PKH_UNLOCKING_SCRIPT_SIZE)
+    }
+}
+
+impl SizeEstimator {
+    #[must_use]
+    pub fn new() -> SizeEstimator {
+        SizeEstimator::default()
+    }
+
+    /// Creates an estimator without the P2PKH template.
+    #[must_use]
+    pub fn empty() -> SizeEstimator {
+        SizeEstimator { templates: vec![] }
+    }
+
+    /// Declares the unlocking script size of locking scripts matching the template. Earlier templates take precedence.
+    #[must_use]
+    pub fn add_template(mut self, template: &ScriptTemplate, unlocking_script_size: usize) -> SizeEstimator {
+        self.templates.push((template.clone(), unlocking_script_size));
+        self
+    }
+
+    /// Gets the unlocking script size declared for the locking script, None if no template matches.
+    #[must_use]
+    pub fn unlocking_script_size(&self, locking_script: &Script) -> Option<usize> {
+        self.templates.iter().find(|(template, _)| locking_script.is_match(template)).map(|(_, size)| *size)
+    }
+
+    /// Replaces every empty unlocking script with one of the declared size, inputs that are already signed are left as they are.
+    fn estimate_signed_transaction(&self, tx: &Transaction) -> Result<Transaction, BSVErrors> {
+        let mut estimate = tx.clone();
+        for (index, txin) in estimate.inputs.iter_mut().enumerate() {
+            if !txin.unlocking_script.0.is_empty() {
+                continue;
+            }
+
+            let size = txin
+                .get_locking_script()
+                .and_then(|x| self.unlocking_script_size(&x))
+                .ok_or_else(|| BSVErrors::GenericError(format!("Unable to estimate the unlocking script size of input {}, its locking script is unknown", index)))?;
+            txin.set_unlocking_script(&Script::from_bytes(&vec![OpCodes::OP_0 as u8; size])?);
+        }
+        Ok(estimate)
+    }
+
+    /// Estimates the size of the transaction once its unsigned inputs are signed.
+    /// Unsigned inputs need their locking script set, see `TxIn::set_locking_script`.
+    pub fn estimate_size(&self, tx: &Transaction) -> Result<usize, BSVErrors> {
+        self.estimate_signed_transaction(tx)?.get_size()
+    }
+}
@@ -1,4 +1,5 @@
 use std::io::Cursor;
+use std::io::Read;
 use std::io::Write;
 
 use crate::BSVErrors;
@@ -8,16 +9,25 @@ use crate::VarIntWriter;
 use byteorder::*;
 use serde::{Deserialize, Serialize};
 
+mod coin_selection;
+mod fee_model;
 mod match_criteria;
 mod sighash;
+mod transaction_builder;
 mod txin;
 mod txout;
 
+pub use coin_selection::*;
+pub use fee_model::*;
 pub use match_criteria::*;
 pub use sighash::*;
+pub use transaction_builder::*;
 pub use txin::*;
 pub use txout::*;
 
+/// Follows the version in the Extended Format (BRC-30), where a standard transaction would have its input count.
+pub const EXTENDED_FORMAT_MARKER: [u8; 6] = [0x00, 0x00, 0x00, 0x00, 0x00, 0xef];
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     pub(super) version: u32,
@@ -52,6 +62,14 @@ impl Transaction {
     }
 
     pub(crate) fn from_bytes_impl(tx_bytes: &[u8]) -> Result<Transaction, BSVErrors> {
+        Transaction::read_in(tx_bytes, false)
+    }
+
+    pub(crate) fn from_extended_bytes_impl(tx_bytes: &[u8]) -> Result<Transaction, BSVErrors> {
+        Transaction::read_in(tx_bytes, true)
+    }
+
+    fn read_in(tx_bytes: &[u8], extended: bool) -> Result<Transaction, BSVErrors> {
         let mut cursor = Cursor::new(tx_bytes.to_vec());
 
         // Version - 4 bytes
@@ -60,6 +78,21 @@ impl Transaction {
             Err(e) => return Err(BSVErrors::DeserialiseTransaction("version".to_string(), e)),
         };
 
+        // Extended Format Marker - 6 bytes
+        if extended {
+            let mut marker = [0; 6];
+            if let Err(e) = cursor.read_exact(&mut marker) {
+                return Err(BSVErrors::DeserialiseTransaction("extended_format_marker".to_string(), e));
+            }
+            if marker != EXTENDED_FORMAT_MARKER {
+                let e = std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("expected {} but found {}", hex::encode(EXTENDED_FORMAT_MARKER), hex::encode(marker)),
+                );
+                return Err(BSVErrors::DeserialiseTransaction("extended_format_marker".to_string(), e));
+            }
+        }
+
         // In Counter - 1-9 tx_bytes
         let n_inputs = match cursor.read_varint() {
             Ok(v) => v,
@@ -69,7 +102,10 @@ impl Transaction {
         let mut inputs: Vec<TxIn> = Vec::new();
         // List of Inputs
         for _ in 0..n_inputs {
-            let tx_in = TxIn::read_in(&mut cursor)?;
+            let tx_in = match extended {
+                true => TxIn::read_in_extended(&mut cursor)?,
+                false => TxIn::read_in(&mut cursor)?,
+            };
             inputs.push(tx_in);
         }
 
@@ -102,6 +138,14 @@ impl Transaction {
     }
 
     pub(crate) fn to_bytes_impl(&self) -> Result<Vec<u8>, BSVErrors> {
+        self.write_out(false)
+    }
+
+    pub(crate) fn to_extended_bytes_impl(&self) -> Result<Vec<u8>, BSVErrors> {
+        self.write_out(true)
+    }
+
+    fn write_out(&self, extended: bool) -> Result<Vec<u8>, BSVErrors> {
         let mut buffer = Vec::new();
 
         // Version - 4 bytes
@@ -109,6 +153,13 @@ impl Transaction {
             return Err(BSVErrors::SerialiseTransaction("version".to_string(), e));
         }
 
+        // Extended Format Marker - 6 bytes
+        if extended {
+            if let Err(e) = buffer.write_all(&EXTENDED_FORMAT_MARKER) {
+                return Err(BSVErrors::SerialiseTransaction("extended_format_marker".to_string(), e));
+            }
+        }
+
         // In Counter - 1-9 tx_bytes
         if let Err(e) = buffer.write_varint(self.get_ninputs() as u64) {
             return Err(BSVErrors::SerialiseTransaction("n_inputs".to_string(), e));
@@ -117,7 +168,10 @@ impl Transaction {
         // Inputs
         for i in 0..self.get_ninputs() {
             let input = &self.inputs[i];
-            let input_bytes = input.to_bytes_impl()?;
+            let input_bytes = match extended {
+                true => input.to_extended_bytes_impl()?,
+                false => input.to_bytes_impl()?,
+            };
 
             if let Err(e) = buffer.write_all(&input_bytes) {
                 return Err(BSVErrors::SerialiseTransaction(format!("input {}", i), e));
@@ -289,45 +343,45 @@ impl Transaction {
     pub fn add_input(&mut self, input: &TxIn) {
         self.inputs.push(input.clone());
         // Transaction has been changed, need to recalculate inputs hashes
-        self.hash_cache.hash_inputs = None;
-        self.hash_cache.hash_sequence = None;
+        self.hash_cache.hash_inputs.take();
+        self.hash_cache.hash_sequence.take();
     }
 
     // #[cfg_attr(all(feature = "wasm-bindgen-transaction"), wasm_bindgen(js_name = prependInput))]
     pub fn prepend_input(&mut self, input: &TxIn) {
         self.inputs.insert(0, input.clone());
         // Transaction has been changed, need to recalculate inputs hashes
-        self.hash_cache.hash_inputs = None;
-        self.hash_cache.hash_sequence = None;
+        self.hash_cache.hash_inputs.take();
+        self.hash_cache.hash_sequence.take();
     }
 
     // #[cfg_attr(all(feature = "wasm-bindgen-transaction"), wasm_bindgen(js_name = insertInput))]
     pub fn insert_input(&mut self, index: usize, input: &TxIn) {
         self.inputs.insert(index, input.clone());
         // Transaction has been changed, need to recalculate inputs hashes
-        self.hash_cache.hash_inputs = None;
-        self.hash_cache.hash_sequence = None;
+        self.hash_cache.hash_inputs.take();
+        self.hash_cache.hash_sequence.take();
     }
 
     // #[cfg_attr(all(feature = "wasm-bindgen-transaction"), wasm_bindgen(js_name = addOutput))]
     pub fn add_output(&mut self, output: &TxOut) {
         self.outputs.push(output.clone());
         // Transaction has been changed, need to recalculate outputs hashes
-        self.hash_cache.hash_outputs = None;
+        self.hash_cache.hash_outputs.take();
     }
 
     // #[cfg_attr(all(feature = "wasm-bindgen-transaction"), wasm_bindgen(js_name = prependOutput))]
     pub fn prepend_output(&mut self, output: &TxOut) {
         self.outputs.insert(0, output.clone());
         // Transaction has been changed, need to recalculate outputs hashes
-        self.hash_cache.hash_outputs = None;
+        self.hash_cache.hash_outputs.take();
     }
 
     // #[cfg_attr(all(feature = "wasm-bindgen-transaction"), wasm_bindgen(js_name = insertOutput))]
     pub fn insert_output(&mut self, index: usize, output: &TxOut) {
         self.outputs.insert(index, output.clone());
         // Transaction has been changed, need to recalculate outputs hashes
-        self.hash_cache.hash_outputs = None;
+        self.hash_cache.hash_outputs.take();
     }
 
     // #[cfg_attr(all(feature = "wasm-bindgen-transaction"), wasm_bindgen(js_name = setInput))]
@@ -464,6 +518,29 @@ impl Transaction {
         Transaction::from_compact_bytes_impl(&hex::decode(compact_hex)?)
     }
 
+    /**
+     * Serialises to the Extended Format (BRC-30), which adds the satoshis and locking script of the spent output to every input.
+     * Every input needs its satoshis and locking script set.
+     */
+    pub fn to_extended_bytes(&self) -> Result<Vec<u8>, BSVErrors> {
+        self.to_extended_bytes_impl()
+    }
+
+    pub fn to_extended_hex(&self) -> Result<String, BSVErrors> {
+        Ok(hex::encode(self.to_extended_bytes_impl()?))
+    }
+
+    /**
+     * Deserialises a transaction in the Extended Format (BRC-30), setting the satoshis and locking script of every input.
+     */
+    pub fn from_extended_bytes(tx_bytes: &[u8]) -> Result<Transaction, BSVErrors> {
+        Transaction::from_extended_bytes_impl(tx_bytes)
+    }
+
+    pub fn from_extended_hex(hex_str: &str) -> Result<Transaction, BSVErrors> {
+        Transaction::from_extended_bytes_impl(&hex::decode(hex_str)?)
+    }
+
     pub fn is_coinbase(&self) -> bool {
         self.is_coinbase_impl()
     }
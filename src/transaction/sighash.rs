@@ -4,6 +4,7 @@ use crate::ReversibleDigest;
 use crate::ECDSA;
 use std::convert::TryFrom;
 use std::io::Write;
+use std::sync::OnceLock;
 
 use crate::{transaction::*, Hash, PrivateKey, PublicKey, Script, Signature};
 use byteorder::{LittleEndian, WriteBytesExt};
@@ -87,21 +88,42 @@ impl std::ops::BitAnd for SigHash {
     }
 }
 
+/// The digest the node signs for a legacy SIGHASH_SINGLE input without an output at its index, the number one.
+/// This bug of the original client is part of consensus, such a signature is valid for any transaction spending the same output.
+pub const SIGHASH_SINGLE_BUG_DIGEST: [u8; 32] = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// Each hash is stored once, so a transaction shared between threads fills a single cache.
+/// Threads that miss the same empty slot at the same time may each calculate the hash, only the first result is kept.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct HashCache {
-    pub(super) hash_inputs: Option<Hash>,
-    pub(super) hash_sequence: Option<Hash>,
-    pub(super) hash_outputs: Option<Hash>,
+    pub(super) hash_inputs: OnceLock<Hash>,
+    pub(super) hash_sequence: OnceLock<Hash>,
+    pub(super) hash_outputs: OnceLock<Hash>,
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of hashes the sighash caches calculated on this thread
+    pub(crate) static HASHES_CALCULATED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
 }
 
 impl HashCache {
     /// Creates a new cache
     pub fn new() -> Self {
-        HashCache {
-            hash_inputs: None,
-            hash_sequence: None,
-            hash_outputs: None,
+        HashCache::default()
+    }
+
+    /// Returns the cached hash, otherwise hashes the data and caches it.
+    /// Another thread may fill the slot in the meantime, both hashes are the same.
+    fn get_or_hash(slot: &OnceLock<Hash>, data: impl FnOnce() -> Result<Vec<u8>, BSVErrors>) -> Result<Vec<u8>, BSVErrors> {
+        if let Some(hash) = slot.get() {
+            return Ok(hash.to_bytes());
         }
+
+        let hash = Hash::sha_256d(&data()?);
+        #[cfg(test)]
+        HASHES_CALCULATED.with(|x| x.set(x.get() + 1));
+        Ok(slot.get_or_init(|| hash).to_bytes())
     }
 }
 
@@ -147,7 +169,7 @@ impl Transaction {
     /**
      * Calculates the SIGHASH Buffer to be signed
      */
-    pub(crate) fn sighash_preimage_impl(&mut self, n_tx_in: usize, sighash: SigHash, unsigned_script: &Script, value: u64) -> Result<Vec<u8>, BSVErrors> {
+    pub(crate) fn sighash_preimage_impl(&self, n_tx_in: usize, sighash: SigHash, unsigned_script: &Script, value: u64) -> Result<Vec<u8>, BSVErrors> {
         // If uses any of the FORK_ID sighash variants
         // Gross, fix this. Maybe a nice method on SigHash enum to check if contains another SigHash type
         match sighash {
@@ -158,7 +180,7 @@ impl Transaction {
         }
     }
 
-    pub(crate) fn sighash_legacy(&mut self, n_tx_in: usize, sighash: SigHash, unsigned_script: &Script) -> Result<Vec<u8>, BSVErrors> {
+    pub(crate) fn sighash_legacy(&self, n_tx_in: usize, sighash: SigHash, unsigned_script: &Script) -> Result<Vec<u8>, BSVErrors> {
         let mut tx = self.clone();
         let mut script = unsigned_script.clone();
         script.remove_codeseparators();
@@ -172,20 +194,15 @@ impl Transaction {
 
         match sighash {
             SigHash::SINGLE | SigHash::Legacy_InputOutput => {
-                // Not supporting the SIGHASH_SINGLE bug. Sue me craig.
-                // // This if statement is needed because of Consensus SIGHASH_SINGLE bug
-                // // https://bitcoinfiles.org/t/9a3a165cc7881bb2e37567dec5eaab64568a889e83e6b850b42f347e1d96a555
-                // if n_tx_in >= tx.outputs.len() {
-                //   return Ok(hex::decode("0000000000000000000000000000000000000000000000000000000000000001").map_err(|e| anyhow!(e))?)
-                // }
-
-                let txout = tx.get_output(n_tx_in).ok_or_else(|| BSVErrors::OutOfBounds(format!("Could not get TxOut at index {}", n_tx_in)))?;
-                tx.outputs = vec![txout];
-
-                for i in 0..tx.outputs.len() {
-                    if i < n_tx_in {
-                        tx.set_output(i, &TxOut::new(0xffffffffffffffff, &Script::default()));
-                    }
+                // There is no preimage for the SIGHASH_SINGLE bug, see SIGHASH_SINGLE_BUG_DIGEST
+                if n_tx_in >= tx.outputs.len() {
+                    return Err(BSVErrors::OutOfBounds(format!("Could not get TxOut at index {}, the signed digest is SIGHASH_SINGLE_BUG_DIGEST", n_tx_in)));
+                }
+
+                // Outputs after the signed one are dropped, the ones before it are blanked
+                tx.outputs.truncate(n_tx_in + 1);
+                for i in 0..n_tx_in {
+                    tx.set_output(i, &TxOut::new(0xffffffffffffffff, &Script::default()));
                 }
 
                 for i in 0..tx.inputs.len() {
@@ -224,7 +241,7 @@ impl Transaction {
         Ok(buffer)
     }
 
-    pub(crate) fn sighash_bip143(&mut self, n_tx_in: usize, sighash: SigHash, unsigned_script: &Script, value: u64) -> Result<Vec<u8>, BSVErrors> {
+    pub(crate) fn sighash_bip143(&self, n_tx_in: usize, sighash: SigHash, unsigned_script: &Script, value: u64) -> Result<Vec<u8>, BSVErrors> {
         let mut buffer: Vec<u8> = vec![];
 
         let input = self.get_input(n_tx_in).ok_or_else(|| BSVErrors::OutOfBounds(format!("Could not get TxIn at index {}", n_tx_in)))?;
@@ -248,19 +265,24 @@ impl Transaction {
         Ok(buffer)
     }
 
+    /// Whether the input signs SIGHASH_SINGLE_BUG_DIGEST instead of a preimage with the sighash type.
+    pub(crate) fn is_sighash_single_bug(&self, n_tx_in: usize, sighash: SigHash) -> bool {
+        matches!(sighash, SigHash::SINGLE | SigHash::Legacy_InputOutput) && n_tx_in >= self.outputs.len()
+    }
+
+    /// Drops the cached hashes, which `set_input` and `set_output` do not update.
+    pub(crate) fn clear_hash_cache(&mut self) {
+        self.hash_cache = HashCache::new();
+    }
+
     /**
      * Checks the hash cache to see if there already are hashed sequence, otherwise calculates the hash and adds it to the cache
      */
-    fn hash_sequence(&mut self, sighash: SigHash) -> Vec<u8> {
+    fn hash_sequence(&self, sighash: SigHash) -> Vec<u8> {
         match sighash {
             SigHash::ALL | SigHash::InputsOutputs => {
-                if let Some(x) = &self.hash_cache.hash_sequence {
-                    return x.to_bytes();
-                }
-                let input_sequences: Vec<u8> = self.inputs.iter().flat_map(|x| x.get_sequence_as_bytes()).collect();
-                let hash = Hash::sha_256d(&input_sequences);
-                self.hash_cache.hash_sequence = Some(hash.clone());
-                hash.to_bytes()
+                let input_sequences = || Ok(self.inputs.iter().flat_map(|x| x.get_sequence_as_bytes()).collect());
+                HashCache::get_or_hash(&self.hash_cache.hash_sequence, input_sequences).unwrap_or_default()
             }
             _ => [0; 32].to_vec(),
         }
@@ -269,7 +291,7 @@ impl Transaction {
     /**
      * Checks the hash cache to see if there already are hashed outputs, otherwise calculates the hash and adds it to the cache
      */
-    fn hash_outputs(&mut self, sighash: SigHash, n_tx_in: usize) -> Result<Vec<u8>, BSVErrors> {
+    fn hash_outputs(&self, sighash: SigHash, n_tx_in: usize) -> Result<Vec<u8>, BSVErrors> {
         match sighash {
             // Only sign the output at the same index as the given txin
             SigHash::SINGLE | SigHash::InputOutput | SigHash::Legacy_InputOutput | SigHash::InputsOutput => {
@@ -283,16 +305,14 @@ impl Transaction {
             }
             // Sign all outputs
             SigHash::ALL | SigHash::InputOutputs | SigHash::Legacy_InputOutputs | SigHash::InputsOutputs => {
-                if let Some(x) = &self.hash_cache.hash_outputs {
-                    return Ok(x.to_bytes());
-                }
-                let mut txout_bytes = Vec::new();
-                for output in &self.outputs {
-                    txout_bytes.write_all(&output.to_bytes_impl()?)?;
-                }
-                let hash = Hash::sha_256d(&txout_bytes);
-                self.hash_cache.hash_outputs = Some(hash.clone());
-                Ok(hash.to_bytes())
+                let txout_bytes = || {
+                    let mut txout_bytes = Vec::new();
+                    for output in &self.outputs {
+                        txout_bytes.write_all(&output.to_bytes_impl()?)?;
+                    }
+                    Ok(txout_bytes)
+                };
+                HashCache::get_or_hash(&self.hash_cache.hash_outputs, txout_bytes)
             }
             _ => Ok([0; 32].to_vec()),
         }
@@ -306,19 +326,12 @@ impl Transaction {
      * - If SigHash does not contain ANYONECANPAY, SHA256d all input outpoints
      * - Else 32 bytes of zeroes
      */
-    pub fn hash_inputs(&mut self, sighash: SigHash) -> Vec<u8> {
+    pub fn hash_inputs(&self, sighash: SigHash) -> Vec<u8> {
         match sighash {
             SigHash::ANYONECANPAY | SigHash::Input | SigHash::InputOutput | SigHash::Legacy_Input | SigHash::Legacy_InputOutput | SigHash::InputOutputs => [0; 32].to_vec(),
             _ => {
-                if let Some(x) = &self.hash_cache.hash_inputs {
-                    return x.to_bytes();
-                }
-                let input_bytes: Vec<u8> = self.inputs.iter().flat_map(|txin| txin.get_outpoint_bytes(Some(true))).collect();
-
-                let hash = Hash::sha_256d(&input_bytes);
-                self.hash_cache.hash_inputs = Some(hash.clone());
-
-                hash.to_bytes()
+                let input_bytes = || Ok(self.inputs.iter().flat_map(|txin| txin.get_outpoint_bytes(Some(true))).collect());
+                HashCache::get_or_hash(&self.hash_cache.hash_inputs, input_bytes).unwrap_or_default()
             }
         }
     }
@@ -0,0 +1,218 @@
+use crate::{BSVErrors, FeeModel, P2PKHAddress, PrivateKey, Script, ScriptBuilder, SigHash, Transaction, TxIn, TxOut};
+use serde::{Deserialize, Serialize};
+
+/// Change below this many satoshis is left to the miner instead of creating an output.
+pub const DEFAULT_DUST_LIMIT: u64 = 1;
+
+/// Length of the largest low S DER signature plus its sighash byte.
+pub const MAX_P2PKH_SIGNATURE_SIZE: usize = 72;
+
+/// An unspent output that a TransactionBuilder can spend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Utxo {
+    /// Transaction id in the byte order `TxIn::new` takes
+    pub tx_id: Vec<u8>,
+    pub vout: u32,
+    pub locking_script: Script,
+    pub satoshis: u64,
+}
+
+impl Utxo {
+    #[must_use]
+    pub fn new(tx_id: &[u8], vout: u32, locking_script: &Script, satoshis: u64) -> Utxo {
+        Utxo {
+            tx_id: tx_id.to_vec(),
+            vout,
+            locking_script: locking_script.clone(),
+            satoshis,
+        }
+    }
+
+    /// Creates an input spending the output, with the locking script and satoshis attached.
+    #[must_use]
+    pub fn to_txin(&self, unlocking_script: &Script) -> TxIn {
+        let mut txin = TxIn::new(&self.tx_id, self.vout, unlocking_script, None);
+        txin.set_locking_script(&self.locking_script);
+        txin.set_satoshis(self.satoshis);
+        txin
+    }
+}
+
+/// Builds and signs a transaction spending P2PKH outputs, adding change once the fee is known.
+///
+/// ```
+/// use bsv::{P2PKHAddress, PrivateKey, TransactionBuilder, Utxo};
+///
+/// let private_key = PrivateKey::from_bytes(&[1; 32]).unwrap();
+/// let address = P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap();
+/// let utxo = Utxo::new(&[7; 32], 0, &address.get_locking_script().unwrap(), 10_000);
+///
+/// let tx = TransactionBuilder::new()
+///     .add_input(&utxo, &private_key)
+///     .pay_to_address(&address, 2_000)
+///     .unwrap()
+///     .set_change_address(&address)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(tx.get_noutputs(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    version: u32,
+    n_locktime: u32,
+    inputs: Vec<(Utxo, PrivateKey)>,
+    outputs: Vec<TxOut>,
+    change_script: Option<Script>,
+    fee_model: FeeModel,
+    dust_limit: u64,
+    sighash: SigHash,
+}
+
+impl Default for TransactionBuilder {
+    fn default() -> TransactionBuilder {
+        TransactionBuilder {
+            version: 1,
+            n_locktime: 0,
+            inputs: vec![],
+            outputs: vec![],
+            change_script: None,
+            fee_model: FeeModel::default(),
+            dust_limit: DEFAULT_DUST_LIMIT,
+            sighash: SigHash::InputsOutputs,
+        }
+    }
+}
+
+impl TransactionBuilder {
+    /// Creates the transaction with placeholder unlocking scripts of the largest size a P2PKH signature can produce.
+    fn unsigned_transaction(&self, change: Option<u64>) -> Result<Transaction, BSVErrors> {
+        let mut tx = Transaction::new(self.version, self.n_locktime);
+        for (utxo, private_key) in &self.inputs {
+            let public_key = private_key.to_public_key()?.to_bytes()?;
+            let placeholder = ScriptBuilder::new().push_data(&[0; MAX_P2PKH_SIGNATURE_SIZE]).push_data(&public_key).build();
+            tx.add_input(&utxo.to_txin(&placeholder));
+        }
+        tx.add_outputs(self.outputs.clone());
+        if let (Some(change), Some(change_script)) = (change, &self.change_script) {
+            tx.add_output(&TxOut::new(change, change_script));
+        }
+        Ok(tx)
+    }
+
+    #[must_use]
+    pub fn new() -> TransactionBuilder {
+        TransactionBuilder::default()
+    }
+
+    #[must_use]
+    pub fn set_version(mut self, version: u32) -> TransactionBuilder {
+        self.version = version;
+        self
+    }
+
+    #[must_use]
+    pub fn set_nlocktime(mut self, n_locktime: u32) -> TransactionBuilder {
+        self.n_locktime = n_locktime;
+        self
+    }
+
+    /// Spends a P2PKH output locked to the key.
+    #[must_use]
+    pub fn add_input(mut self, utxo: &Utxo, private_key: &PrivateKey) -> TransactionBuilder {
+        self.inputs.push((utxo.clone(), private_key.clone()));
+        self
+    }
+
+    #[must_use]
+    pub fn add_output(mut self, output: &TxOut) -> TransactionBuilder {
+        self.outputs.push(output.clone());
+        self
+    }
+
+    pub fn pay_to_address(self, address: &P2PKHAddress, satoshis: u64) -> Result<TransactionBuilder, BSVErrors> {
+        Ok(self.add_output(&TxOut::new(satoshis, &address.get_locking_script()?)))
+    }
+
+    /// Sends whatever is left after the outputs and fee to the script, unless it is below the dust limit.
+    #[must_use]
+    pub fn set_change_script(mut self, script: &Script) -> TransactionBuilder {
+        self.change_script = Some(script.clone());
+        self
+    }
+
+    pub fn set_change_address(self, address: &P2PKHAddress) -> Result<TransactionBuilder, BSVErrors> {
+        Ok(self.set_change_script(&address.get_locking_script()?))
+    }
+
+    /// Charges every byte at the fee rate in satoshis per 1000 bytes, defaults to `DEFAULT_FEE_RATE`.
+    #[must_use]
+    pub fn set_fee_rate(self, satoshis_per_kb: u64) -> TransactionBuilder {
+        self.set_fee_model(&FeeModel::flat(satoshis_per_kb))
+    }
+
+    #[must_use]
+    pub fn set_fee_model(mut self, fee_model: &FeeModel) -> TransactionBuilder {
+        self.fee_model = *fee_model;
+        self
+    }
+
+    #[must_use]
+    pub fn set_dust_limit(mut self, dust_limit: u64) -> TransactionBuilder {
+        self.dust_limit = dust_limit;
+        self
+    }
+
+    /// Sets the sighash every input is signed with, defaults to `SigHash::InputsOutputs`.
+    #[must_use]
+    pub fn set_sighash(mut self, sighash: SigHash) -> TransactionBuilder {
+        self.sighash = sighash;
+        self
+    }
+
+    /// Estimates the size of the signed transaction, including the change output if one will be added.
+    pub fn estimate_size(&self) -> Result<usize, BSVErrors> {
+        let change = self.change()?;
+        self.unsigned_transaction(change)?.get_size()
+    }
+
+    /// Estimates the fee the signed transaction pays under the fee model.
+    pub fn estimate_fee(&self) -> Result<u64, BSVErrors> {
+        let change = self.change()?;
+        self.fee_model.fee(&self.unsigned_transaction(change)?)
+    }
+
+    /// Gets the change output amount, None if there is no change script or the change would be dust.
+    pub fn change(&self) -> Result<Option<u64>, BSVErrors> {
+        let satoshis_in = self.inputs.iter().map(|(utxo, _)| utxo.satoshis).sum::<u64>();
+        let satoshis_out = self.outputs.iter().map(|x| x.get_satoshis()).sum::<u64>();
+
+        let required = satoshis_out + self.fee_model.fee(&self.unsigned_transaction(None)?)?;
+        if satoshis_in < required {
+            return Err(BSVErrors::InsufficientFunds(satoshis_in, required));
+        }
+
+        if self.change_script.is_none() {
+            return Ok(None);
+        }
+
+        let fee = self.fee_model.fee(&self.unsigned_transaction(Some(0))?)?;
+        Ok(satoshis_in.checked_sub(satoshis_out + fee).filter(|change| *change >= self.dust_limit))
+    }
+
+    /// Adds the change output and signs every input.
+    pub fn build(&self) -> Result<Transaction, BSVErrors> {
+        let mut tx = self.unsigned_transaction(self.change()?)?;
+
+        for (index, (utxo, private_key)) in self.inputs.iter().enumerate() {
+            let signature = tx.sign(private_key, self.sighash, index, &utxo.locking_script, utxo.satoshis)?;
+            let public_key = private_key.to_public_key()?;
+            let unlocking_script = P2PKHAddress::from_pubkey(&public_key)?.get_unlocking_script(&public_key, &signature)?;
+
+            tx.set_input(index, &utxo.to_txin(&unlocking_script));
+        }
+
+        Ok(tx)
+    }
+}
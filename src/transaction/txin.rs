@@ -131,6 +131,62 @@ impl TxIn {
         })
     }
 
+    /**
+     * Reads an input in the extended format, followed by the satoshis and locking script of the output it spends.
+     */
+    pub(crate) fn read_in_extended(cursor: &mut Cursor<Vec<u8>>) -> Result<TxIn, BSVErrors> {
+        let mut tx_in = TxIn::read_in(cursor)?;
+
+        // Previous Satoshis - 8 bytes
+        let satoshis = match cursor.read_u64::<LittleEndian>() {
+            Ok(v) => v,
+            Err(e) => return Err(BSVErrors::DeserialiseTxIn("satoshis".to_string(), e)),
+        };
+
+        // Previous Locking Script Size - VarInt
+        let locking_script_size = match cursor.read_varint() {
+            Ok(v) => v,
+            Err(e) => return Err(BSVErrors::DeserialiseTxIn("locking_script_size".to_string(), e)),
+        };
+
+        // Previous Locking Script
+        let mut locking_script = vec![0; locking_script_size as usize];
+        if let Err(e) = cursor.read_exact(&mut locking_script) {
+            return Err(BSVErrors::DeserialiseTxIn("locking_script".to_string(), e));
+        }
+
+        tx_in.satoshis = Some(satoshis);
+        tx_in.locking_script = Some(Script::from_bytes(&locking_script)?);
+        Ok(tx_in)
+    }
+
+    /**
+     * Serialises the input in the extended format, which needs the satoshis and locking script of the output it spends.
+     */
+    pub(crate) fn to_extended_bytes_impl(&self) -> Result<Vec<u8>, BSVErrors> {
+        let satoshis = self.satoshis.ok_or_else(|| BSVErrors::SerialiseExtendedTxIn("satoshis".to_string()))?;
+        let locking_script = self.locking_script.as_ref().ok_or_else(|| BSVErrors::SerialiseExtendedTxIn("locking_script".to_string()))?.to_bytes();
+
+        let mut buffer = self.to_bytes_impl()?;
+
+        // Previous Satoshis
+        if let Err(e) = buffer.write_u64::<LittleEndian>(satoshis) {
+            return Err(BSVErrors::SerialiseTxIn("satoshis".to_string(), e));
+        }
+
+        // Previous Locking Script Size
+        if let Err(e) = buffer.write_varint(locking_script.len() as u64) {
+            return Err(BSVErrors::SerialiseTxIn("locking_script_size".to_string(), e));
+        }
+
+        // Previous Locking Script
+        if let Err(e) = buffer.write_all(&locking_script) {
+            return Err(BSVErrors::SerialiseTxIn("locking_script".to_string(), e));
+        }
+
+        Ok(buffer)
+    }
+
     pub(crate) fn to_bytes_impl(&self) -> Result<Vec<u8>, BSVErrors> {
         let mut buffer = vec![];
 
@@ -0,0 +1,126 @@
+mod common;
+
+#[cfg(test)]
+mod coin_selection_tests {
+    use bsv::{BSVErrors, CoinSelectionStrategy, CoinSelector, FeeModel, Script, ScriptTemplate, SizeEstimator, TransactionBuilder, TxOut, Utxo};
+
+    use crate::common::{key_and_address, utxo};
+
+    // At 1 satoshi a byte a P2PKH input costs 148 satoshis and a P2PKH change output 34
+    const BASE_SIZE: u64 = 4 + 1 + 1 + 34 + 4;
+    const INPUT_SIZE: u64 = 148;
+    const CHANGE_SIZE: u64 = 34;
+
+    fn utxos(values: &[u64]) -> Vec<Utxo> {
+        let (_, address) = key_and_address(4);
+        values.iter().enumerate().map(|(vout, satoshis)| utxo(&address, vout as u32, *satoshis)).collect()
+    }
+
+    fn pay(satoshis: u64) -> Vec<TxOut> {
+        let (_, address) = key_and_address(4);
+        vec![TxOut::new(satoshis, &address.get_locking_script().unwrap())]
+    }
+
+    fn values(utxos: &[Utxo]) -> Vec<u64> {
+        utxos.iter().map(|x| x.satoshis).collect()
+    }
+
+    #[test]
+    fn largest_and_smallest_first() {
+        let selector = CoinSelector::new(&FeeModel::flat(1_000));
+        let candidates = utxos(&[1_000, 5_000, 20_000, 300]);
+
+        let selection = selector.select(&candidates, &pay(4_000), CoinSelectionStrategy::LargestFirst).unwrap();
+        assert_eq!(values(&selection.utxos), vec![20_000]);
+        assert_eq!(selection.fee, BASE_SIZE + INPUT_SIZE + CHANGE_SIZE);
+        assert_eq!(selection.change, Some(20_000 - 4_000 - selection.fee));
+
+        let selection = selector.select(&candidates, &pay(4_000), CoinSelectionStrategy::SmallestFirst).unwrap();
+        assert_eq!(values(&selection.utxos), vec![300, 1_000, 5_000]);
+        assert_eq!(selection.fee, BASE_SIZE + 3 * INPUT_SIZE + CHANGE_SIZE);
+        assert_eq!(selection.satoshis(), 4_000 + selection.fee + selection.change.unwrap());
+
+        // The builder agrees on the change once the selection is signed
+        let (private_key, address) = key_and_address(4);
+        let builder = selection
+            .utxos
+            .iter()
+            .fold(TransactionBuilder::new(), |builder, utxo| builder.add_input(utxo, &private_key))
+            .pay_to_address(&address, 4_000)
+            .unwrap()
+            .set_change_address(&address)
+            .unwrap()
+            .set_fee_rate(1_000);
+        assert_eq!(builder.change().unwrap(), selection.change);
+    }
+
+    #[test]
+    fn skips_utxos_worth_less_than_their_fee() {
+        let selector = CoinSelector::new(&FeeModel::flat(1_000));
+        let selection = selector.select(&utxos(&[100, 100, 10_000]), &pay(1_000), CoinSelectionStrategy::SmallestFirst).unwrap();
+        assert_eq!(values(&selection.utxos), vec![10_000]);
+
+        let result = selector.select(&utxos(&[100, 148, 1_000]), &pay(1_000), CoinSelectionStrategy::LargestFirst);
+        assert!(matches!(result, Err(BSVErrors::InsufficientFunds(1_000, required)) if required == 1_000 + BASE_SIZE + INPUT_SIZE));
+
+        // Change below the dust limit goes to the fee
+        let selection = selector.set_dust_limit(500).select(&utxos(&[1_600]), &pay(1_000), CoinSelectionStrategy::LargestFirst).unwrap();
+        assert_eq!(selection.change, None);
+        assert_eq!(selection.fee, 600);
+    }
+
+    #[test]
+    fn branch_and_bound_avoids_change() {
+        let selector = CoinSelector::new(&FeeModel::flat(1_000));
+        let exact = 10_000 + BASE_SIZE + 2 * INPUT_SIZE;
+        let candidates = utxos(&[9_000, 50_000, 6_000, 2_500, exact - 6_000]);
+
+        let selection = selector.select(&candidates, &pay(10_000), CoinSelectionStrategy::BranchAndBound).unwrap();
+        let mut selected = values(&selection.utxos);
+        selected.sort();
+        assert_eq!(selected, vec![exact - 6_000, 6_000]);
+        assert_eq!(selection.change, None);
+        assert_eq!(selection.fee, BASE_SIZE + 2 * INPUT_SIZE);
+
+        // Excess below the cost of a change output is left as fee
+        let selection = selector
+            .select(&utxos(&[exact + CHANGE_SIZE - INPUT_SIZE]), &pay(10_000), CoinSelectionStrategy::BranchAndBound)
+            .unwrap();
+        assert_eq!(selection.change, None);
+
+        let result = selector.select(&utxos(&[50_000, 2_500]), &pay(10_000), CoinSelectionStrategy::BranchAndBound);
+        assert!(matches!(result, Err(BSVErrors::CoinSelection(_))));
+        let result = selector.select(&utxos(&[5_000, 2_500]), &pay(10_000), CoinSelectionStrategy::BranchAndBound);
+        assert!(matches!(result, Err(BSVErrors::InsufficientFunds(7_500, _))));
+    }
+
+    #[test]
+    fn random_improve_aims_for_change_the_size_of_the_payment() {
+        let selector = CoinSelector::new(&FeeModel::flat(1_000));
+        let selection = selector.select(&utxos(&[1_000; 20]), &pay(3_000), CoinSelectionStrategy::RandomImprove).unwrap();
+        assert_eq!(selection.utxos.len(), 6);
+        assert_eq!(selection.change, Some(6_000 - 3_000 - (BASE_SIZE + 6 * INPUT_SIZE + CHANGE_SIZE)));
+
+        // A second UTXO would take the inputs further from twice the payment
+        let selection = selector.select(&utxos(&[1_500, 20_000, 1_500, 1_500]), &pay(1_000), CoinSelectionStrategy::RandomImprove).unwrap();
+        assert_eq!(selection.utxos.len(), 1);
+    }
+
+    #[test]
+    fn uses_the_size_estimator_for_other_scripts() {
+        let candidates = vec![Utxo::new(&[6; 32], 0, &Script::from_asm_string("OP_1").unwrap(), 10_000)];
+        let selector = CoinSelector::new(&FeeModel::flat(1_000));
+        assert!(matches!(
+            selector.select(&candidates, &pay(1_000), CoinSelectionStrategy::LargestFirst),
+            Err(BSVErrors::CoinSelection(_))
+        ));
+
+        let estimator = SizeEstimator::new().add_template(&ScriptTemplate::from_asm_string("OP_1").unwrap(), 0);
+        let selection = selector
+            .set_size_estimator(&estimator)
+            .set_change_script(&Script::from_asm_string("OP_1").unwrap())
+            .select(&candidates, &pay(1_000), CoinSelectionStrategy::LargestFirst)
+            .unwrap();
+        assert_eq!(selection.fee, BASE_SIZE + 41 + 10);
+    }
+}
@@ -0,0 +1,12 @@
+use bsv::{P2PKHAddress, PrivateKey, Utxo};
+
+pub fn key_and_address(seed: u8) -> (PrivateKey, P2PKHAddress) {
+    let private_key = PrivateKey::from_bytes(&[seed; 32]).unwrap();
+    let address = P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap();
+    (private_key, address)
+}
+
+/// A P2PKH UTXO of the address, every UTXO is an output of the same transaction.
+pub fn utxo(address: &P2PKHAddress, vout: u32, satoshis: u64) -> Utxo {
+    Utxo::new(&[9; 32], vout, &address.get_locking_script().unwrap(), satoshis)
+}
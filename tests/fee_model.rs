@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod fee_model_tests {
+    use bsv::{FeeModel, P2PKHAddress, PrivateKey, Script, ScriptTemplate, SizeEstimator, Transaction, TransactionBuilder, TxIn, TxOut, Utxo, P2PKH_UNLOCKING_SCRIPT_SIZE};
+
+    fn signed_p2pkh_transaction(fee_model: &FeeModel) -> Transaction {
+        let private_key = PrivateKey::from_bytes(&[1; 32]).unwrap();
+        let address = P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap();
+        let locking_script = address.get_locking_script().unwrap();
+        TransactionBuilder::new()
+            .add_input(&Utxo::new(&[5; 32], 0, &locking_script, 5_000), &private_key)
+            .add_input(&Utxo::new(&[5; 32], 1, &locking_script, 5_000), &private_key)
+            .pay_to_address(&address, 3_000)
+            .unwrap()
+            .set_change_address(&address)
+            .unwrap()
+            .set_fee_model(fee_model)
+            .build()
+            .unwrap()
+    }
+
+    fn without_unlocking_scripts(tx: &Transaction) -> Transaction {
+        let mut unsigned = tx.clone();
+        for i in 0..tx.get_ninputs() {
+            let mut txin = tx.get_input(i).unwrap();
+            txin.set_unlocking_script(&Script::default());
+            unsigned.set_input(i, &txin);
+        }
+        unsigned
+    }
+
+    #[test]
+    fn estimates_p2pkh_unlocking_scripts() {
+        let signed = signed_p2pkh_transaction(&FeeModel::default());
+        let unsigned = without_unlocking_scripts(&signed);
+        assert!(unsigned.get_size().unwrap() + 2 * 100 < signed.get_size().unwrap());
+
+        // Signatures are at most 72 bytes, usually 71 or 72
+        let estimate = SizeEstimator::new().estimate_size(&unsigned).unwrap();
+        assert!(estimate >= signed.get_size().unwrap());
+        assert!(estimate <= signed.get_size().unwrap() + 2);
+        assert_eq!(estimate, unsigned.get_size().unwrap() + 2 * P2PKH_UNLOCKING_SCRIPT_SIZE);
+
+        // Signed inputs are measured as they are
+        assert_eq!(SizeEstimator::new().estimate_size(&signed).unwrap(), signed.get_size().unwrap());
+        assert_eq!(FeeModel::flat(1_000).estimate_fee(&unsigned, &SizeEstimator::new()).unwrap(), estimate as u64);
+    }
+
+    #[test]
+    fn estimates_custom_templates() {
+        let locking_script = Script::from_asm_string("OP_HASH160 0000000000000000000000000000000000000000 OP_EQUAL").unwrap();
+        let mut txin = TxIn::new(&[5; 32], 0, &Script::default(), None);
+        txin.set_locking_script(&locking_script);
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&txin);
+        tx.add_output(&TxOut::new(500, &Script::default()));
+
+        // Unsigned inputs need a known locking script
+        assert!(SizeEstimator::new().estimate_size(&tx).is_err());
+        let mut no_locking_script = tx.clone();
+        no_locking_script.set_input(0, &TxIn::new(&[5; 32], 0, &Script::default(), None));
+        assert!(SizeEstimator::new().estimate_size(&no_locking_script).is_err());
+
+        let template = ScriptTemplate::from_asm_string("OP_HASH160 OP_DATA=20 OP_EQUAL").unwrap();
+        let estimator = SizeEstimator::new().add_template(&template, 300);
+        assert_eq!(estimator.unlocking_script_size(&locking_script), Some(300));
+        assert_eq!(estimator.unlocking_script_size(&Script::default()), None);
+        // 300 bytes also takes the unlocking script length from 1 to 3 bytes
+        assert_eq!(estimator.estimate_size(&tx).unwrap(), tx.get_size().unwrap() + 300 + 2);
+    }
+
+    #[test]
+    fn charges_data_outputs_at_the_data_rate() {
+        let data = Script::from_asm_string(&format!("OP_0 OP_RETURN {}", "ab".repeat(100))).unwrap();
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&TxIn::new(&[5; 32], 0, &Script::default(), None));
+        tx.add_output(&TxOut::new(0, &data));
+        tx.add_output(&TxOut::new(0, &Script::from_asm_string("OP_RETURN 00").unwrap()));
+        tx.add_output(&TxOut::new(500, &Script::from_asm_string("OP_1").unwrap()));
+
+        assert_eq!(FeeModel::data_size(&tx), data.to_bytes().len() + 3);
+        let size = tx.get_size().unwrap() as u64;
+        let data_size = FeeModel::data_size(&tx) as u64;
+        assert_eq!(FeeModel::new(1_000, 0).fee(&tx).unwrap(), size - data_size);
+        assert_eq!(FeeModel::new(0, 1_000).fee(&tx).unwrap(), data_size);
+        assert_eq!(FeeModel::new(500, 250).fee(&tx).unwrap(), ((size - data_size) * 500 + data_size * 250).div_ceil(1000));
+        assert_eq!(FeeModel::default(), FeeModel::flat(500));
+    }
+
+    #[test]
+    fn checks_finished_transactions_pay_the_fee() {
+        let fee_model = FeeModel::new(1_000, 100);
+        let tx = signed_p2pkh_transaction(&fee_model);
+        assert!(fee_model.is_paid_by(&tx).unwrap());
+        assert!(!FeeModel::flat(2_000).is_paid_by(&tx).unwrap());
+
+        // The fee paid is unknown without the satoshis of every input
+        let mut tx = tx;
+        tx.set_input(0, &TxIn::new(&[5; 32], 0, &Script::default(), None));
+        assert!(fee_model.is_paid_by(&tx).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod interpreter_chronicle_tests {
+    use bsv::{Interpreter, InterpreterError, OpCodes, Script, ScriptBit, ScriptConfig, Transaction, TxIn, VerifyFlags};
+
+    fn run_with_config(asm: &str, config: &ScriptConfig) -> Result<Vec<Vec<u8>>, InterpreterError> {
+        let mut interpreter = Interpreter::from_script_with_config(&Script::from_asm_string(asm).unwrap(), config);
+        interpreter.run()?;
+        Ok(interpreter.state().stack().to_vec())
+    }
+
+    fn run(asm: &str) -> Result<Vec<Vec<u8>>, InterpreterError> {
+        run_with_config(asm, &ScriptConfig::chronicle())
+    }
+
+    /// Runs the script against a version 2 transaction
+    fn run_in_tx(asm: &str, config: &ScriptConfig) -> Result<Vec<Vec<u8>>, InterpreterError> {
+        let mut tx = Transaction::new(2, 0);
+        tx.add_input(&TxIn::default());
+
+        let mut interpreter = Interpreter::from_transaction_and_script_bits(tx, 0, Script::from_asm_string(asm).unwrap().to_script_bits());
+        interpreter.set_config(config);
+        interpreter.run()?;
+        Ok(interpreter.state().stack().to_vec())
+    }
+
+    #[test]
+    fn lshift_and_rshift_keep_byte_length() {
+        assert_eq!(run("0180 OP_1 OP_LSHIFT").unwrap(), vec![vec![0x03, 0x00]]);
+        assert_eq!(run("0180 OP_1 OP_RSHIFT").unwrap(), vec![vec![0x00, 0xc0]]);
+        assert_eq!(run("9f OP_0 OP_LSHIFT").unwrap(), vec![vec![0x9f]]);
+        assert_eq!(run("abcdef OP_12 OP_LSHIFT").unwrap(), vec![vec![0xde, 0xf0, 0x00]]);
+        assert_eq!(run("abcdef OP_12 OP_RSHIFT").unwrap(), vec![vec![0x00, 0x0a, 0xbc]]);
+        assert_eq!(run("abcdef OP_16 OP_8 OP_ADD OP_LSHIFT").unwrap(), vec![vec![0x00, 0x00, 0x00]]);
+        // Shifts are not arithmetic, the sign bit is shifted like any other
+        assert_eq!(run("81 OP_1 OP_RSHIFT").unwrap(), vec![vec![0x40]]);
+    }
+
+    #[test]
+    fn shifts_by_negative_amounts_fail() {
+        assert!(matches!(run("0180 OP_1NEGATE OP_LSHIFT"), Err(InterpreterError::InvalidNumberRange)));
+        assert!(matches!(run("0180 OP_1NEGATE OP_RSHIFT"), Err(InterpreterError::InvalidNumberRange)));
+        assert!(matches!(run("OP_1 OP_1NEGATE OP_LSHIFTNUM"), Err(InterpreterError::InvalidNumberRange)));
+    }
+
+    #[test]
+    fn byte_shifts_do_not_need_chronicle() {
+        assert_eq!(run_with_config("0180 OP_1 OP_LSHIFT", &ScriptConfig::pre_genesis()).unwrap(), vec![vec![0x03, 0x00]]);
+    }
+
+    #[test]
+    fn numeric_shifts() {
+        assert_eq!(run("OP_3 OP_2 OP_LSHIFTNUM").unwrap(), vec![vec![12]]);
+        assert_eq!(run("OP_13 OP_2 OP_RSHIFTNUM").unwrap(), vec![vec![3]]);
+        // The sign is kept, rounding towards zero
+        assert_eq!(run("OP_3 OP_NEGATE OP_2 OP_LSHIFTNUM").unwrap(), vec![vec![0x8c]]);
+        assert_eq!(run("OP_13 OP_NEGATE OP_2 OP_RSHIFTNUM").unwrap(), vec![vec![0x83]]);
+        assert_eq!(run("OP_1NEGATE OP_1 OP_RSHIFTNUM").unwrap(), vec![Vec::<u8>::new()]);
+    }
+
+    #[test]
+    fn mul_and_div_by_two() {
+        assert_eq!(run("OP_16 OP_2MUL").unwrap(), vec![vec![32]]);
+        assert_eq!(run("OP_5 OP_2DIV").unwrap(), vec![vec![2]]);
+        assert_eq!(run("OP_5 OP_NEGATE OP_2DIV").unwrap(), vec![vec![0x82]]);
+    }
+
+    #[test]
+    fn mul_and_div_by_two_are_disabled_before_chronicle() {
+        assert!(matches!(run_with_config("OP_1 OP_2MUL", &ScriptConfig::default()), Err(InterpreterError::DisabledOpCode(_))));
+        assert!(matches!(run_with_config("OP_1 OP_2DIV", &ScriptConfig::default()), Err(InterpreterError::DisabledOpCode(_))));
+
+        // Disabled opcodes only fail in branches that are not executed before Genesis
+        assert!(run_with_config("OP_0 OP_IF OP_2MUL OP_ENDIF OP_1", &ScriptConfig::default()).is_ok());
+        assert!(matches!(
+            run_with_config("OP_0 OP_IF OP_2MUL OP_ENDIF OP_1", &ScriptConfig::pre_genesis()),
+            Err(InterpreterError::DisabledOpCode(_))
+        ));
+    }
+
+    #[test]
+    fn substr_left_and_right() {
+        assert_eq!(run("0011223344 OP_1 OP_3 OP_SUBSTR").unwrap(), vec![vec![0x11, 0x22, 0x33]]);
+        assert_eq!(run("0011223344 OP_5 OP_0 OP_SUBSTR").unwrap(), vec![Vec::<u8>::new()]);
+        assert_eq!(run("0011223344 OP_2 OP_LEFT").unwrap(), vec![vec![0x00, 0x11]]);
+        assert_eq!(run("0011223344 OP_2 OP_RIGHT").unwrap(), vec![vec![0x33, 0x44]]);
+
+        assert!(matches!(run("0011223344 OP_3 OP_3 OP_SUBSTR"), Err(InterpreterError::SplitRange)));
+        assert!(matches!(run("0011223344 OP_1NEGATE OP_1 OP_SUBSTR"), Err(InterpreterError::SplitRange)));
+        assert!(matches!(run("0011223344 OP_6 OP_LEFT"), Err(InterpreterError::SplitRange)));
+        assert!(matches!(run("0011223344 OP_6 OP_RIGHT"), Err(InterpreterError::SplitRange)));
+    }
+
+    #[test]
+    fn restored_opcodes_are_nops_before_chronicle() {
+        let config = ScriptConfig::default();
+        assert_eq!(run_with_config("0011 OP_1 OP_1 OP_SUBSTR", &config).unwrap(), vec![vec![0x00, 0x11], vec![1], vec![1]]);
+        assert_eq!(run_with_config("OP_1 OP_1 OP_LSHIFTNUM", &config).unwrap(), vec![vec![1], vec![1]]);
+
+        let config = ScriptConfig::new(VerifyFlags {
+            discourage_upgradable_nops: true,
+            ..VerifyFlags::post_genesis()
+        });
+        assert!(matches!(run_with_config("OP_1 OP_LEFT", &config), Err(InterpreterError::DiscourageUpgradableNops(_))));
+    }
+
+    #[test]
+    fn ver_pushes_the_transaction_version() {
+        assert_eq!(run_in_tx("OP_VER", &ScriptConfig::chronicle()).unwrap(), vec![vec![2, 0, 0, 0]]);
+        assert!(matches!(run_in_tx("OP_VER", &ScriptConfig::default()), Err(InterpreterError::InvalidOpcode(OpCodes::OP_VER))));
+        assert!(matches!(run("OP_VER"), Err(InterpreterError::RequiresTransaction(_))));
+    }
+
+    #[test]
+    fn verif_and_vernotif_branch_on_the_transaction_version() {
+        let config = ScriptConfig::chronicle();
+        assert_eq!(run_in_tx("02000000 OP_VERIF OP_1 OP_ELSE OP_2 OP_ENDIF", &config).unwrap(), vec![vec![1]]);
+        assert_eq!(run_in_tx("01000000 OP_VERIF OP_1 OP_ELSE OP_2 OP_ENDIF", &config).unwrap(), vec![vec![2]]);
+        assert_eq!(run_in_tx("01000000 OP_VERNOTIF OP_1 OP_ELSE OP_2 OP_ENDIF", &config).unwrap(), vec![vec![1]]);
+        // Not executed branches only track nesting
+        assert_eq!(run_in_tx("OP_0 OP_IF OP_VERIF OP_ENDIF OP_ENDIF OP_3", &config).unwrap(), vec![vec![3]]);
+    }
+
+    #[test]
+    fn verif_fails_even_when_not_executed_before_chronicle() {
+        assert!(matches!(
+            run_in_tx("OP_0 OP_IF OP_VERIF OP_ENDIF OP_ENDIF OP_1", &ScriptConfig::default()),
+            Err(InterpreterError::InvalidOpcode(OpCodes::OP_VERIF))
+        ));
+    }
+
+    #[test]
+    fn chronicle_names_are_aliases_of_the_nops() {
+        let script = Script::from_asm_string("OP_SUBSTR OP_LEFT OP_RIGHT OP_LSHIFTNUM OP_RSHIFTNUM").unwrap();
+        assert_eq!(script.to_bytes(), vec![0xb3, 0xb4, 0xb5, 0xb6, 0xb7]);
+        assert_eq!(script.to_asm_string(), "OP_NOP4 OP_NOP5 OP_NOP6 OP_NOP7 OP_NOP8");
+        assert_eq!(Script::from_asm_string("OP_NOP4").unwrap().to_script_bits(), vec![ScriptBit::OpCode(OpCodes::OP_SUBSTR)]);
+        assert_eq!(OpCodes::OP_SUBSTR.to_string(), "OP_NOP4");
+    }
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod interpreter_conditionals_tests {
+    use bsv::{Interpreter, InterpreterError, OpCodes, Script, ScriptBit, ScriptConfig};
+
+    fn run(asm: &str) -> Result<Interpreter, InterpreterError> {
+        let mut interpreter = Interpreter::from_script(&Script::from_asm_string(asm).unwrap());
+        interpreter.run()?;
+        Ok(interpreter)
+    }
+
+    fn run_bits(bits: Vec<ScriptBit>, config: &ScriptConfig) -> Result<Interpreter, InterpreterError> {
+        let mut interpreter = Interpreter::from_script_with_config(&Script::from_script_bits(bits), config);
+        interpreter.run()?;
+        Ok(interpreter)
+    }
+
+    #[test]
+    fn op_if_executes_pass_branch() {
+        let interpreter = run("OP_1 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![2_u8]]);
+
+        let interpreter = run("OP_0 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+    }
+
+    #[test]
+    fn op_notif_inverts_predicate() {
+        let interpreter = run("OP_1 OP_NOTIF OP_2 OP_ELSE OP_3 OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+
+        let interpreter = run("OP_0 OP_NOTIF OP_2 OP_ELSE OP_3 OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![2_u8]]);
+    }
+
+    #[test]
+    fn op_if_casts_any_non_zero_value_to_true() {
+        let interpreter = run("OP_1NEGATE OP_IF OP_2 OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![2_u8]]);
+
+        // Negative zero is false
+        let interpreter = run("80 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+    }
+
+    #[test]
+    fn nested_conditionals_skip_inner_branches() {
+        let interpreter = run("OP_0 OP_IF OP_1 OP_IF OP_2 OP_ENDIF OP_DROP OP_DROP OP_ELSE OP_4 OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![4_u8]]);
+
+        let interpreter = run("OP_1 OP_IF OP_0 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF OP_ENDIF").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+    }
+
+    #[test]
+    fn script_index_follows_flat_script() {
+        let mut interpreter = Interpreter::from_script(&Script::from_asm_string("OP_0 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF").unwrap());
+        assert_eq!(interpreter.script_bits().len(), 6);
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.script_index(), 6);
+
+        // The nested tree is kept for display
+        assert!(matches!(interpreter.script().to_script_bits()[1], ScriptBit::If { code: OpCodes::OP_IF, .. }));
+    }
+
+    #[test]
+    fn unbalanced_conditionals_fail() {
+        let config = ScriptConfig::default();
+
+        let missing_endif = vec![ScriptBit::OpCode(OpCodes::OP_1), ScriptBit::OpCode(OpCodes::OP_IF), ScriptBit::OpCode(OpCodes::OP_1)];
+        assert!(matches!(run_bits(missing_endif, &config), Err(InterpreterError::UnbalancedConditional)));
+
+        let lone_endif = vec![ScriptBit::OpCode(OpCodes::OP_1), ScriptBit::OpCode(OpCodes::OP_ENDIF)];
+        assert!(matches!(run_bits(lone_endif, &config), Err(InterpreterError::UnbalancedConditional)));
+
+        let lone_else = vec![ScriptBit::OpCode(OpCodes::OP_1), ScriptBit::OpCode(OpCodes::OP_ELSE)];
+        assert!(matches!(run_bits(lone_else, &config), Err(InterpreterError::UnbalancedConditional)));
+
+        // OP_IF on an empty stack is reported as unbalanced, like the node does
+        assert!(matches!(run("OP_IF OP_ENDIF"), Err(InterpreterError::UnbalancedConditional)));
+    }
+
+    #[test]
+    fn multiple_else_toggle_before_genesis() {
+        let bits = vec![
+            ScriptBit::OpCode(OpCodes::OP_1),
+            ScriptBit::OpCode(OpCodes::OP_IF),
+            ScriptBit::OpCode(OpCodes::OP_2),
+            ScriptBit::OpCode(OpCodes::OP_ELSE),
+            ScriptBit::OpCode(OpCodes::OP_3),
+            ScriptBit::OpCode(OpCodes::OP_ELSE),
+            ScriptBit::OpCode(OpCodes::OP_4),
+            ScriptBit::OpCode(OpCodes::OP_ENDIF),
+        ];
+
+        let interpreter = run_bits(bits.clone(), &ScriptConfig::pre_genesis()).unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![2_u8], vec![4_u8]]);
+
+        assert!(matches!(run_bits(bits, &ScriptConfig::post_genesis()), Err(InterpreterError::UnbalancedConditional)));
+    }
+
+    #[test]
+    fn op_return_after_genesis() {
+        // Top level OP_RETURN ends the script, the trailing data is never evaluated
+        let interpreter = run("OP_1 OP_RETURN 010203").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8]]);
+
+        // Inside a branch only the conditionals keep being checked
+        let interpreter = run("OP_1 OP_1 OP_IF OP_RETURN OP_2 OP_ENDIF OP_3").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8]]);
+
+        // Not executed, so OP_RETURN has no effect
+        let interpreter = run("OP_0 OP_IF OP_RETURN OP_ENDIF OP_3").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+    }
+
+    #[test]
+    fn unexecuted_branch_is_not_evaluated() {
+        let interpreter = run("OP_0 OP_IF OP_DROP OP_DROP OP_VERIFY OP_ENDIF OP_1").unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8]]);
+
+        // Still counted towards the operation limit
+        let config = ScriptConfig {
+            max_ops_per_script: 3,
+            ..ScriptConfig::default()
+        };
+        let script = Script::from_asm_string("OP_0 OP_IF OP_DROP OP_DROP OP_ENDIF OP_1").unwrap();
+        let mut interpreter = Interpreter::from_script_with_config(&script, &config);
+        assert!(matches!(interpreter.run(), Err(InterpreterError::OpCount)));
+    }
+}
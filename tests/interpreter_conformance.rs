@@ -0,0 +1,310 @@
+#[cfg(test)]
+mod interpreter_conformance_tests {
+    use std::str::FromStr;
+
+    use bsv::{InterpreterError, OpCodes, Script, ScriptConfig, ScriptError, ScriptFailure, Transaction, TxIn, TxOut, VerifyFlags};
+    use serde_json::Value;
+
+    /// Where the unmodified bitcoin-sv src/test/data/{script_tests,tx_valid,tx_invalid}.json files are vendored.
+    const VENDORED_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/bitcoin-sv");
+
+    /// Vectors of the vendored files this crate knowingly disagrees with. Each entry is a prefix of the string fields of
+    /// the vector, `[scriptSig, scriptPubKey, flags]` for script tests and `[serializedTransaction, flags]` for transaction tests,
+    /// and the reason it is skipped.
+    const SKIPPED: &[(&[&str], &str)] = &[];
+
+    fn script_num(value: i64) -> Vec<u8> {
+        let mut abs = value.unsigned_abs();
+        let mut bytes = vec![];
+        while abs > 0 {
+            bytes.push((abs & 0xff) as u8);
+            abs >>= 8;
+        }
+
+        match bytes.last() {
+            Some(last) if last & 0x80 != 0 => bytes.push(if value < 0 { 0x80 } else { 0x00 }),
+            Some(_) if value < 0 => *bytes.last_mut().unwrap() |= 0x80,
+            _ => (),
+        }
+
+        bytes
+    }
+
+    fn push(data: &[u8]) -> Vec<u8> {
+        let mut bytes = match data.len() {
+            len if len < OpCodes::OP_PUSHDATA1 as usize => vec![len as u8],
+            len if len <= 0xff => vec![OpCodes::OP_PUSHDATA1 as u8, len as u8],
+            len if len <= 0xffff => [vec![OpCodes::OP_PUSHDATA2 as u8], (len as u16).to_le_bytes().to_vec()].concat(),
+            len => [vec![OpCodes::OP_PUSHDATA4 as u8], (len as u32).to_le_bytes().to_vec()].concat(),
+        };
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn opcode(name: &str) -> OpCodes {
+        let name = name.strip_prefix("OP_").unwrap_or(name);
+        match name {
+            "FALSE" => OpCodes::OP_0,
+            "TRUE" => OpCodes::OP_1,
+            "NOP2" => OpCodes::OP_CHECKLOCKTIMEVERIFY,
+            "NOP3" => OpCodes::OP_CHECKSEQUENCEVERIFY,
+            _ => OpCodes::from_str(&format!("OP_{}", name)).unwrap_or_else(|_| panic!("Unknown opcode {}", name)),
+        }
+    }
+
+    /// Parses the script format of the node's test vectors. Numbers are pushed as script numbers,
+    /// `'text'` is pushed as bytes, `0x` prefixed hex is inserted as raw script bytes and anything else is an opcode name.
+    fn parse_script(asm: &str) -> Script {
+        let mut bytes = vec![];
+        for token in asm.split_whitespace() {
+            if let Ok(value) = token.parse::<i64>() {
+                match value {
+                    0 => bytes.push(OpCodes::OP_0 as u8),
+                    -1 | 1..=16 => bytes.push((OpCodes::OP_1 as i64 - 1 + value) as u8),
+                    _ => bytes.extend(push(&script_num(value))),
+                }
+            } else if let Some(hex) = token.strip_prefix("0x") {
+                bytes.extend(hex::decode(hex).unwrap());
+            } else if token.len() >= 2 && token.starts_with('\'') && token.ends_with('\'') {
+                bytes.extend(push(&token.as_bytes()[1..token.len() - 1]));
+            } else {
+                bytes.push(opcode(token) as u8);
+            }
+        }
+
+        // Malformed bytes are kept and reported by the interpreter when it reaches them
+        Script::from_bytes(&bytes).unwrap()
+    }
+
+    fn parse_config(flags: &str) -> ScriptConfig {
+        let mut verify_flags = VerifyFlags {
+            check_locktime_verify: false,
+            check_sequence_verify: false,
+            ..VerifyFlags::pre_genesis()
+        };
+
+        for flag in flags.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            match flag {
+                // P2SH is not part of the BSV script rules
+                "NONE" | "P2SH" | "SIGHASH_FORKID" | "MONOLITH_OPCODES" => (),
+                "STRICTENC" | "DERSIG" => verify_flags.strict_enc = true,
+                "LOW_S" => verify_flags.low_s = true,
+                "NULLFAIL" => verify_flags.null_fail = true,
+                "NULLDUMMY" => verify_flags.null_dummy = true,
+                "MINIMALDATA" => verify_flags.minimal_data = true,
+                "SIGPUSHONLY" => verify_flags.sig_push_only = true,
+                "CLEANSTACK" => verify_flags.clean_stack = true,
+                "DISCOURAGE_UPGRADABLE_NOPS" => verify_flags.discourage_upgradable_nops = true,
+                "CHECKLOCKTIMEVERIFY" => verify_flags.check_locktime_verify = true,
+                "CHECKSEQUENCEVERIFY" => verify_flags.check_sequence_verify = true,
+                "GENESIS" => verify_flags.genesis = true,
+                "UTXO_AFTER_GENESIS" => {
+                    verify_flags.genesis = true;
+                    verify_flags.utxo_after_genesis = true;
+                }
+                _ => panic!("Unknown flag {}", flag),
+            }
+        }
+
+        ScriptConfig::new(verify_flags)
+    }
+
+    /// Whether the error matches the node's expected error code.
+    fn is_expected_error(expected: &str, error: &InterpreterError) -> bool {
+        let error = error.script_error();
+        match expected {
+            // The node raises invalid script numbers as exceptions, which surface as UNKNOWN_ERROR
+            "UNKNOWN_ERROR" => matches!(error, ScriptError::ScriptnumOverflow | ScriptError::ScriptnumMinencode),
+            _ => error.as_str() == expected,
+        }
+    }
+
+    fn string_fields(vector: &[Value]) -> Vec<&str> {
+        vector.iter().filter_map(Value::as_str).collect()
+    }
+
+    /// The reason the vector is skipped, if it is on the skip list.
+    fn skip_reason(vector: &[Value]) -> Option<&'static str> {
+        let fields = string_fields(vector);
+        SKIPPED.iter().find(|(prefix, _)| fields.starts_with(prefix)).map(|(_, reason)| *reason)
+    }
+
+    /// Builds the transactions the node's script tests spend between, see BuildCreditingTransaction and BuildSpendingTransaction.
+    fn run_script_test(script_sig: &str, script_pub_key: &str, flags: &str, amount: u64) -> Result<(), InterpreterError> {
+        let (script_sig, script_pub_key) = (parse_script(script_sig), parse_script(script_pub_key));
+
+        let mut credit_tx = Transaction::new(1, 0);
+        credit_tx.add_input(&TxIn::new(&[0; 32], u32::MAX, &Script::from_bytes(&[0, 0]).unwrap(), None));
+        let prev_output = TxOut::new(amount, &script_pub_key);
+        credit_tx.add_output(&prev_output);
+
+        let mut spend_tx = Transaction::new(1, 0);
+        spend_tx.add_input(&TxIn::new(&credit_tx.get_id_bytes().unwrap(), 0, &script_sig, None));
+        spend_tx.add_output(&TxOut::new(amount, &Script::default()));
+
+        match spend_tx.verify_input_with_config(0, &prev_output, &parse_config(flags)).unwrap().failure {
+            Some(failure) => Err(failure.error),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs every vector of a file in the format of the node's script_tests.json.
+    fn script_tests(json: &str, skip: bool) {
+        let vectors: Vec<Vec<Value>> = serde_json::from_str(json).unwrap();
+
+        let mut count = 0;
+        let mut failures = vec![];
+        for vector in vectors.iter().filter(|x| x.len() > 1) {
+            if skip && skip_reason(vector).is_some() {
+                continue;
+            }
+
+            // An array of witness data and the amount in BSV may come first
+            let (amount, fields) = match &vector[0] {
+                Value::Array(witness) => ((witness.last().unwrap().as_f64().unwrap() * 100_000_000.0).round() as u64, &vector[1..]),
+                _ => (0, &vector[..]),
+            };
+            let field = |i: usize| fields[i].as_str().unwrap();
+
+            let outcome = run_script_test(field(0), field(1), field(2), amount);
+            let matches = match &outcome {
+                Ok(()) => field(3) == "OK",
+                Err(e) => is_expected_error(field(3), e),
+            };
+            if !matches {
+                failures.push(format!("{:?}: expected {}, got {:?}", fields, field(3), outcome));
+            }
+            count += 1;
+        }
+
+        assert!(count > 0);
+        assert!(failures.is_empty(), "{} of {} script tests failed:\n{}", failures.len(), count, failures.join("\n"));
+    }
+
+    /// Why a transaction vector did not verify.
+    #[derive(Debug)]
+    enum TxFailure {
+        Script(ScriptFailure),
+        /// The vector itself could not be used
+        Vector(String),
+    }
+
+    /// Verifies every input of the transaction vector, returns the first failure.
+    fn run_tx_test(vector: &[Value]) -> Result<(), TxFailure> {
+        let prev_outputs = vector[0].as_array().unwrap();
+        let tx = Transaction::from_hex(vector[1].as_str().unwrap()).map_err(|e| TxFailure::Vector(format!("Could not parse transaction: {}", e)))?;
+        let config = parse_config(vector[2].as_str().unwrap());
+
+        for index in 0..tx.get_ninputs() {
+            let txin = tx.get_input(index).unwrap();
+            let prev_output = prev_outputs
+                .iter()
+                .map(|x| x.as_array().unwrap())
+                .find(|x| x[0].as_str().unwrap() == txin.get_prev_tx_id_hex(None) && x[1].as_i64().unwrap() == txin.get_vout() as i64)
+                .ok_or_else(|| TxFailure::Vector(format!("Missing previous output for input {}", index)))?;
+
+            let script_pub_key = parse_script(prev_output[2].as_str().unwrap());
+            let amount = prev_output.get(3).and_then(Value::as_u64).unwrap_or_default();
+
+            let verdict = tx.verify_input_with_config(index, &TxOut::new(amount, &script_pub_key), &config).unwrap();
+            if let Some(failure) = verdict.failure {
+                return Err(TxFailure::Script(failure));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn tx_vectors(json: &str) -> Vec<Vec<Value>> {
+        let vectors: Vec<Vec<Value>> = serde_json::from_str(json).unwrap();
+        vectors.into_iter().filter(|x| x.len() > 1).collect()
+    }
+
+    fn tx_valid(json: &str, skip: bool) {
+        let vectors = tx_vectors(json);
+        assert!(!vectors.is_empty());
+
+        for vector in vectors.iter().filter(|x| !skip || skip_reason(x).is_none()) {
+            if let Err(e) = run_tx_test(vector) {
+                panic!("{:?} should be valid: {:?}", vector, e);
+            }
+        }
+    }
+
+    /// Every vector must fail one of its scripts. The node's files do not say which error is expected,
+    /// the vectors written for this crate give it after the flags.
+    fn tx_invalid(json: &str, skip: bool) {
+        let vectors = tx_vectors(json);
+        assert!(!vectors.is_empty());
+
+        for vector in vectors.iter().filter(|x| !skip || skip_reason(x).is_none()) {
+            let failure = match run_tx_test(vector) {
+                Err(TxFailure::Script(failure)) => failure,
+                Err(TxFailure::Vector(e)) => panic!("{:?} could not be run: {}", vector, e),
+                Ok(()) => panic!("{:?} should be invalid", vector),
+            };
+
+            if let Some(expected) = vector.get(3).and_then(Value::as_str) {
+                assert!(is_expected_error(expected, &failure.error), "{:?}: expected {}, got {}", vector, expected, failure);
+            }
+        }
+    }
+
+    fn vendored(name: &str) -> String {
+        let path = format!("{}/{}", VENDORED_DIR, name);
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Could not read {}: {}", path, e))
+    }
+
+    #[test]
+    fn interpreter_script_tests() {
+        script_tests(include_str!("data/interpreter_script_tests.json"), false);
+    }
+
+    #[test]
+    fn genesis_script_tests() {
+        script_tests(include_str!("data/genesis_script_tests.json"), false);
+    }
+
+    #[test]
+    fn script_num_tests() {
+        script_tests(include_str!("data/script_num_tests.json"), false);
+    }
+
+    #[test]
+    fn interpreter_tx_valid() {
+        tx_valid(include_str!("data/interpreter_tx_valid.json"), false);
+    }
+
+    #[test]
+    fn genesis_tx_valid() {
+        tx_valid(include_str!("data/genesis_tx_valid.json"), false);
+    }
+
+    #[test]
+    fn interpreter_tx_invalid() {
+        tx_invalid(include_str!("data/interpreter_tx_invalid.json"), false);
+    }
+
+    #[test]
+    fn genesis_tx_invalid() {
+        tx_invalid(include_str!("data/genesis_tx_invalid.json"), false);
+    }
+
+    #[test]
+    #[ignore = "needs the bitcoin-sv src/test/data files vendored into tests/data/bitcoin-sv"]
+    fn bitcoin_sv_script_tests() {
+        script_tests(&vendored("script_tests.json"), true);
+    }
+
+    #[test]
+    #[ignore = "needs the bitcoin-sv src/test/data files vendored into tests/data/bitcoin-sv"]
+    fn bitcoin_sv_tx_valid() {
+        tx_valid(&vendored("tx_valid.json"), true);
+    }
+
+    #[test]
+    #[ignore = "needs the bitcoin-sv src/test/data files vendored into tests/data/bitcoin-sv"]
+    fn bitcoin_sv_tx_invalid() {
+        tx_invalid(&vendored("tx_invalid.json"), true);
+    }
+}
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod interpreter_debugger_tests {
+    use bsv::{Breakpoint, Interpreter, OpCodes, Script, Status};
+
+    fn interpreter(asm: &str) -> Interpreter {
+        Interpreter::from_script(&Script::from_asm_string(asm).unwrap())
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut interpreter = interpreter("OP_1 OP_2 OP_ADD");
+        interpreter.next().unwrap().unwrap();
+        let snapshot = interpreter.snapshot();
+
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+
+        interpreter.restore(&snapshot);
+        assert_eq!(interpreter.script_index(), 1);
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8]]);
+
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+    }
+
+    #[test]
+    fn step_back_undoes_steps() {
+        let mut interpreter = interpreter("OP_1 OP_2 OP_ADD");
+        while let Some(state) = interpreter.step() {
+            state.unwrap();
+        }
+        assert_eq!(interpreter.state().status, Status::Finished);
+
+        assert!(interpreter.step_back());
+        assert_eq!(interpreter.state().status, Status::Running);
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+
+        assert!(interpreter.step_back());
+        assert_eq!(interpreter.script_index(), 2);
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8], vec![2_u8]]);
+
+        assert!(interpreter.step_back());
+        assert!(interpreter.step_back());
+        assert_eq!(interpreter.script_index(), 0);
+        assert!(interpreter.state().stack().is_empty());
+        assert!(!interpreter.step_back());
+    }
+
+    #[test]
+    fn history_is_limited() {
+        let mut interpreter = interpreter("OP_1 OP_2 OP_3 OP_4 OP_5");
+        interpreter.set_history_limit(2);
+        while let Some(state) = interpreter.step() {
+            state.unwrap();
+        }
+
+        // Only the last two steps, finishing and OP_5, can be undone
+        assert_eq!(interpreter.history().len(), 2);
+        assert!(interpreter.step_back());
+        assert!(interpreter.step_back());
+        assert_eq!(interpreter.script_index(), 4);
+        assert!(!interpreter.step_back());
+
+        interpreter.set_history_limit(0);
+        interpreter.step().unwrap().unwrap();
+        assert!(interpreter.history().is_empty());
+    }
+
+    #[test]
+    fn step_over_skips_if_block() {
+        let mut interpreter = interpreter("OP_1 OP_1 OP_IF OP_2 OP_0 OP_IF OP_3 OP_ENDIF OP_ELSE OP_4 OP_ENDIF OP_5");
+        interpreter.step_over().unwrap().unwrap();
+        interpreter.step_over().unwrap().unwrap();
+        assert_eq!(interpreter.script_index(), 2);
+
+        interpreter.step_over().unwrap().unwrap();
+        assert_eq!(interpreter.script_index(), 11);
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8], vec![2_u8]]);
+
+        // The whole block is undone one step at a time
+        assert!(interpreter.step_back());
+        assert_eq!(interpreter.script_index(), 10);
+    }
+
+    #[test]
+    fn run_until_breakpoint() {
+        let mut interpreter = interpreter("OP_1 OP_2 OP_ADD OP_3 OP_ADD");
+
+        let hit = interpreter.run_until(&Breakpoint::ScriptIndex(3)).unwrap();
+        assert_eq!(hit, Some(Breakpoint::ScriptIndex(3)));
+        assert_eq!(interpreter.state().stack(), &[vec![3_u8]]);
+
+        assert_eq!(interpreter.run_until(&Breakpoint::ScriptIndex(1)).unwrap(), None);
+        assert_eq!(interpreter.state().stack(), &[vec![6_u8]]);
+    }
+
+    #[test]
+    fn resume_stops_at_each_breakpoint() {
+        let mut interpreter = interpreter("OP_1 OP_2 OP_ADD OP_3 OP_ADD");
+        interpreter.add_breakpoint(&Breakpoint::OpCode(OpCodes::OP_ADD));
+        interpreter.add_breakpoint(&Breakpoint::OpCode(OpCodes::OP_ADD));
+        assert_eq!(interpreter.breakpoints().len(), 1);
+
+        assert_eq!(interpreter.resume().unwrap(), Some(Breakpoint::OpCode(OpCodes::OP_ADD)));
+        assert_eq!(interpreter.script_index(), 2);
+
+        // Resuming from a breakpoint moves past it
+        assert_eq!(interpreter.resume().unwrap(), Some(Breakpoint::OpCode(OpCodes::OP_ADD)));
+        assert_eq!(interpreter.script_index(), 4);
+
+        interpreter.remove_breakpoint(&Breakpoint::OpCode(OpCodes::OP_ADD));
+        assert_eq!(interpreter.resume().unwrap(), None);
+        assert_eq!(interpreter.state().stack(), &[vec![6_u8]]);
+    }
+
+    #[test]
+    fn resume_returns_script_errors() {
+        let mut interpreter = interpreter("OP_1 OP_ADD OP_1");
+        interpreter.add_breakpoint(&Breakpoint::ScriptIndex(2));
+        assert!(interpreter.resume().is_err());
+        assert_eq!(interpreter.script_index(), 1);
+    }
+}
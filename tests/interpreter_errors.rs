@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod interpreter_error_tests {
+    use bsv::{Interpreter, InterpreterError, Script, ScriptError, Transaction, TxIn};
+
+    /// Runs the script and returns the error code and the index of the failing script bit
+    fn fail(asm: &str) -> (ScriptError, usize) {
+        let mut interpreter = Interpreter::from_script(&Script::from_asm_string(asm).unwrap());
+        let error = interpreter.run().unwrap_err();
+        (error.script_error(), interpreter.script_index())
+    }
+
+    #[test]
+    fn verify_opcodes_have_distinct_codes() {
+        assert_eq!(fail("OP_0 OP_VERIFY"), (ScriptError::Verify, 1));
+        assert_eq!(fail("OP_1 OP_2 OP_EQUALVERIFY"), (ScriptError::Equalverify, 2));
+        assert_eq!(fail("OP_1 OP_2 OP_NUMEQUALVERIFY"), (ScriptError::Numequalverify, 2));
+    }
+
+    #[test]
+    fn stack_errors_are_split_by_stack() {
+        assert_eq!(fail("OP_1 OP_DROP OP_DROP"), (ScriptError::InvalidStackOperation, 2));
+        assert_eq!(fail("OP_1 OP_FROMALTSTACK"), (ScriptError::InvalidAltstackOperation, 1));
+        assert_eq!(fail("OP_1 OP_5 OP_PICK"), (ScriptError::InvalidStackOperation, 2));
+    }
+
+    #[test]
+    fn codes_use_node_names() {
+        assert_eq!(ScriptError::Equalverify.to_string(), "EQUALVERIFY");
+        assert_eq!(ScriptError::InvalidAltstackOperation.as_str(), "INVALID_ALTSTACK_OPERATION");
+        assert_eq!(serde_json::to_string(&ScriptError::SigHighS).unwrap(), "\"SIG_HIGH_S\"");
+        assert_eq!(InterpreterError::NumberOutOfRange.script_error(), ScriptError::ScriptnumOverflow);
+    }
+
+    #[test]
+    fn missing_spent_output_is_reported() {
+        let mut tx = Transaction::new(2, 0);
+        let mut txin = TxIn::default();
+        txin.set_unlocking_script(&Script::from_asm_string("3006020101020101 OP_1").unwrap());
+        tx.add_input(&txin);
+
+        let script = Script::from_asm_string("3006020101020101 OP_1 OP_CHECKSIG").unwrap();
+        let mut interpreter = Interpreter::from_transaction_and_script_bits(tx, 0, script.to_script_bits());
+        let error = interpreter.run().unwrap_err();
+        assert!(matches!(error, InterpreterError::MissingLockingScript), "{:?}", error);
+        assert_eq!(error.script_error(), ScriptError::UnknownError);
+    }
+}
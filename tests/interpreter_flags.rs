@@ -0,0 +1,235 @@
+#[cfg(test)]
+mod interpreter_flags_tests {
+    use bsv::{Interpreter, InterpreterError, PrivateKey, Script, ScriptConfig, SigHash, Transaction, TxIn, VerifyFlags};
+
+    fn run_with_config(asm: &str, config: &ScriptConfig) -> Result<(), InterpreterError> {
+        let script = Script::from_asm_string(asm).unwrap();
+        let mut interpreter = Interpreter::from_script_with_config(&script, config);
+        interpreter.run()
+    }
+
+    fn flags_with(update: impl FnOnce(&mut VerifyFlags)) -> ScriptConfig {
+        let mut flags = VerifyFlags::post_genesis();
+        update(&mut flags);
+        ScriptConfig::new(flags)
+    }
+
+    /// Builds a P2PK spend of input 0 with the given unlocking script asm, `{sig}` and `{pubkey}` are substituted.
+    fn p2pk_transaction(unlocking_asm: &str, sighash: SigHash) -> Transaction {
+        let private_key = PrivateKey::from_wif("L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh").unwrap();
+        let pubkey = private_key.to_public_key().unwrap();
+        let mut tx = Transaction::new(2, 0);
+
+        let locking_script = Script::from_asm_string("OP_CHECKSIG").unwrap();
+
+        let mut txin = TxIn::default();
+        txin.set_satoshis(0);
+        txin.set_locking_script(&locking_script);
+        tx.add_input(&txin);
+
+        let signature = tx.sign(&private_key, sighash, 0, &locking_script, 0).unwrap();
+        let unlocking_asm = unlocking_asm.replace("{sig}", &signature.to_hex().unwrap()).replace("{pubkey}", &pubkey.to_hex().unwrap());
+
+        txin.set_unlocking_script(&Script::from_asm_string(&unlocking_asm).unwrap());
+        tx.set_input(0, &txin);
+        tx
+    }
+
+    #[test]
+    fn post_genesis_is_the_default() {
+        let interpreter = Interpreter::from_script(&Script::default());
+        assert_eq!(interpreter.config(), ScriptConfig::post_genesis());
+        assert!(interpreter.config().flags.is_post_genesis());
+    }
+
+    #[test]
+    fn upgradable_nops_are_ignored_by_default() {
+        assert!(run_with_config("OP_1 OP_NOP1 OP_NOP10", &ScriptConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn upgradable_nops_fail_when_discouraged() {
+        let config = flags_with(|f| f.discourage_upgradable_nops = true);
+        assert!(matches!(run_with_config("OP_1 OP_NOP4", &config), Err(InterpreterError::DiscourageUpgradableNops(_))));
+    }
+
+    #[test]
+    fn checklocktimeverify_is_nop_after_genesis() {
+        assert!(run_with_config("OP_1 OP_CHECKLOCKTIMEVERIFY OP_CHECKSEQUENCEVERIFY", &ScriptConfig::post_genesis()).is_ok());
+
+        let config = flags_with(|f| f.discourage_upgradable_nops = true);
+        assert!(matches!(run_with_config("OP_1 OP_CHECKLOCKTIMEVERIFY", &config), Err(InterpreterError::DiscourageUpgradableNops(_))));
+    }
+
+    #[test]
+    fn op_return_fails_before_genesis() {
+        assert!(matches!(run_with_config("OP_1 OP_RETURN", &ScriptConfig::pre_genesis()), Err(InterpreterError::OpReturn)));
+    }
+
+    #[test]
+    fn minimal_data_rejects_non_minimal_push() {
+        // 0x01 should be pushed with OP_1
+        let script = Script::from_hex("0101").unwrap();
+
+        let mut interpreter = Interpreter::from_script(&script);
+        assert!(interpreter.run().is_ok());
+
+        let mut interpreter = Interpreter::from_script_with_config(&script, &flags_with(|f| f.minimal_data = true));
+        assert!(matches!(interpreter.run(), Err(InterpreterError::MinimalData)));
+    }
+
+    #[test]
+    fn minimal_data_rejects_oversized_pushdata() {
+        // 2 bytes pushed with OP_PUSHDATA1
+        let script = Script::from_hex("4c022222").unwrap();
+
+        let mut interpreter = Interpreter::from_script_with_config(&script, &flags_with(|f| f.minimal_data = true));
+        assert!(matches!(interpreter.run(), Err(InterpreterError::MinimalData)));
+    }
+
+    #[test]
+    fn clean_stack_requires_single_item() {
+        let config = flags_with(|f| f.clean_stack = true);
+        assert!(run_with_config("OP_1", &config).is_ok());
+        assert!(matches!(run_with_config("OP_1 OP_1", &config), Err(InterpreterError::CleanStack)));
+        assert!(run_with_config("OP_1 OP_1", &ScriptConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn max_ops_per_script_is_enforced() {
+        let config = ScriptConfig {
+            max_ops_per_script: 2,
+            ..ScriptConfig::default()
+        };
+
+        // Push opcodes do not count towards the limit
+        assert!(run_with_config("OP_1 OP_2 OP_3 OP_DUP OP_DROP", &config).is_ok());
+        assert!(matches!(run_with_config("OP_1 OP_DUP OP_DUP OP_DUP", &config), Err(InterpreterError::OpCount)));
+    }
+
+    #[test]
+    fn max_script_size_is_enforced() {
+        let config = ScriptConfig {
+            max_script_size: 2,
+            ..ScriptConfig::default()
+        };
+
+        assert!(run_with_config("OP_1 OP_1", &config).is_ok());
+        assert!(matches!(run_with_config("OP_1 OP_1 OP_1", &config), Err(InterpreterError::ScriptSize)));
+    }
+
+    #[test]
+    fn max_stack_size_counts_alt_stack() {
+        let config = ScriptConfig {
+            max_stack_size: 2,
+            ..ScriptConfig::default()
+        };
+
+        assert!(run_with_config("OP_1 OP_TOALTSTACK OP_1", &config).is_ok());
+        assert!(matches!(run_with_config("OP_1 OP_TOALTSTACK OP_1 OP_1", &config), Err(InterpreterError::StackSize)));
+    }
+
+    #[test]
+    fn pre_genesis_element_size_is_enforced() {
+        let asm = hex::encode([0x21; 521]);
+
+        assert!(run_with_config(&asm, &ScriptConfig::post_genesis()).is_ok());
+        assert!(matches!(run_with_config(&asm, &ScriptConfig::pre_genesis()), Err(InterpreterError::PushSize)));
+    }
+
+    #[test]
+    fn sig_push_only_rejects_opcodes_in_unlocking_script() {
+        let tx = p2pk_transaction("{sig} {pubkey} OP_NOP", SigHash::InputsOutputs);
+
+        // Push only unlocking scripts are consensus since Genesis
+        assert!(matches!(Interpreter::from_transaction(&tx, 0), Err(InterpreterError::SigPushOnly)));
+        assert!(Interpreter::from_transaction_with_config(&tx, 0, &ScriptConfig::pre_genesis()).is_ok());
+        assert!(Interpreter::from_transaction_with_config(&tx, 0, &flags_with(|f| f.sig_push_only = false)).is_ok());
+    }
+
+    #[test]
+    fn standard_flags_accept_p2pk_spend() {
+        let tx = p2pk_transaction("{sig} {pubkey}", SigHash::InputsOutputs);
+
+        let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, &ScriptConfig::standard()).unwrap();
+        interpreter.run().unwrap();
+
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8]]);
+    }
+
+    #[test]
+    fn empty_signature_fails_checksig_without_error() {
+        let tx = p2pk_transaction("0 {pubkey}", SigHash::InputsOutputs);
+
+        let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, &ScriptConfig::standard()).unwrap();
+        interpreter.run().unwrap();
+
+        assert_eq!(interpreter.state().stack(), &[Vec::<u8>::new()]);
+    }
+
+    #[test]
+    fn null_fail_rejects_invalid_non_empty_signature() {
+        // Signed for a different locking script, so the signature will not verify
+        let private_key = PrivateKey::from_wif("L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh").unwrap();
+        let mut tx = p2pk_transaction("{pubkey}", SigHash::InputsOutputs);
+        let bad_signature = tx.sign(&private_key, SigHash::InputsOutputs, 0, &Script::from_asm_string("OP_1").unwrap(), 0).unwrap();
+
+        let mut txin = tx.get_input(0).unwrap();
+        let unlocking_script = format!("{} {}", bad_signature.to_hex().unwrap(), txin.get_unlocking_script().to_asm_string());
+        txin.set_unlocking_script(&Script::from_asm_string(&unlocking_script).unwrap());
+        tx.set_input(0, &txin);
+
+        let mut interpreter = Interpreter::from_transaction(&tx, 0).unwrap();
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack(), &[Vec::<u8>::new()]);
+
+        let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, &flags_with(|f| f.null_fail = true)).unwrap();
+        assert!(matches!(interpreter.run(), Err(InterpreterError::NullFail)));
+    }
+
+    #[test]
+    fn strict_enc_rejects_invalid_pubkey() {
+        let tx = p2pk_transaction("{sig} 05aabbcc", SigHash::InputsOutputs);
+
+        let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, &flags_with(|f| f.strict_enc = true)).unwrap();
+        assert!(matches!(interpreter.run(), Err(InterpreterError::PubKeyType)));
+    }
+
+    #[test]
+    fn strict_enc_rejects_non_der_signature() {
+        let tx = p2pk_transaction("3006020101020101 {pubkey}", SigHash::InputsOutputs);
+
+        let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, &flags_with(|f| f.strict_enc = true)).unwrap();
+        assert!(matches!(interpreter.run(), Err(InterpreterError::SigDer)));
+    }
+
+    #[test]
+    fn high_s_signatures_are_consensus_valid_but_fail_low_s() {
+        let low_s_tx = p2pk_transaction("{sig} {pubkey}", SigHash::InputsOutputs);
+        let low_s_sig = low_s_tx.get_input(0).unwrap().get_unlocking_script().to_asm_string();
+        let low_s_sig = hex::decode(low_s_sig.split(' ').next().unwrap()).unwrap();
+
+        // Rebuild the DER signature with S' = n - S
+        let r_len = low_s_sig[3] as usize;
+        let r = &low_s_sig[4..4 + r_len];
+        let s = num_bigint::BigUint::from_bytes_be(&low_s_sig[6 + r_len..low_s_sig.len() - 1]);
+        let n = num_bigint::BigUint::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141", 16).unwrap();
+        let mut high_s = (n - s).to_bytes_be();
+        high_s.insert(0, 0x00);
+
+        let mut high_s_sig = vec![0x30, (4 + r.len() + high_s.len()) as u8, 0x02, r.len() as u8];
+        high_s_sig.extend_from_slice(r);
+        high_s_sig.extend_from_slice(&[0x02, high_s.len() as u8]);
+        high_s_sig.extend_from_slice(&high_s);
+        high_s_sig.push(*low_s_sig.last().unwrap());
+
+        let tx = p2pk_transaction(&format!("{} {{pubkey}}", hex::encode(high_s_sig)), SigHash::InputsOutputs);
+
+        let mut interpreter = Interpreter::from_transaction(&tx, 0).unwrap();
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8]]);
+
+        let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, &flags_with(|f| f.low_s = true)).unwrap();
+        assert!(matches!(interpreter.run(), Err(InterpreterError::SigHighS)));
+    }
+}
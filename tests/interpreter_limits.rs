@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod interpreter_limits_tests {
+    use bsv::{Interpreter, InterpreterError, Script, ScriptConfig, ELEMENT_OVERHEAD};
+
+    fn run_with_config(asm: &str, config: &ScriptConfig) -> Result<Interpreter, InterpreterError> {
+        let mut interpreter = Interpreter::from_script_with_config(&Script::from_asm_string(asm).unwrap(), config);
+        interpreter.run()?;
+        Ok(interpreter)
+    }
+
+    #[test]
+    fn script_num_length_is_enforced() {
+        // 5 byte operand
+        let asm = "0000000001 OP_1 OP_ADD";
+
+        assert!(run_with_config(asm, &ScriptConfig::post_genesis()).is_ok());
+        assert!(matches!(run_with_config(asm, &ScriptConfig::pre_genesis()), Err(InterpreterError::NumberOutOfRange)));
+
+        let config = ScriptConfig {
+            max_script_num_length: 2,
+            ..ScriptConfig::default()
+        };
+        assert!(run_with_config("ff7f OP_1 OP_ADD", &config).is_ok());
+        assert!(matches!(run_with_config("ffff00 OP_1 OP_ADD", &config), Err(InterpreterError::NumberOutOfRange)));
+    }
+
+    #[test]
+    fn bin2num_allows_padded_input() {
+        let config = ScriptConfig {
+            max_script_num_length: 1,
+            ..ScriptConfig::default()
+        };
+
+        let interpreter = run_with_config("0100000000 OP_BIN2NUM", &config).unwrap();
+        assert_eq!(interpreter.state().stack(), &[vec![1_u8]]);
+        assert!(matches!(run_with_config("0001 OP_BIN2NUM", &config), Err(InterpreterError::NumberOutOfRange)));
+    }
+
+    #[test]
+    fn stack_memory_usage_is_tracked() {
+        let interpreter = run_with_config("aabbcc OP_DUP OP_TOALTSTACK", &ScriptConfig::default()).unwrap();
+        assert_eq!(interpreter.state().stack_memory_usage, 2 * (3 + ELEMENT_OVERHEAD));
+    }
+
+    #[test]
+    fn stack_memory_usage_is_enforced() {
+        let config = ScriptConfig {
+            max_stack_memory_usage: 2 * (3 + ELEMENT_OVERHEAD),
+            ..ScriptConfig::default()
+        };
+
+        assert!(run_with_config("aabbcc OP_DUP", &config).is_ok());
+        assert!(matches!(run_with_config("aabbcc OP_DUP OP_DUP", &config), Err(InterpreterError::StackMemoryUsage)));
+        assert!(matches!(run_with_config("aabbcc OP_DUP OP_CAT OP_1", &config), Err(InterpreterError::StackMemoryUsage)));
+    }
+
+    #[test]
+    fn num2bin_size_is_checked_before_allocating() {
+        let config = ScriptConfig {
+            max_stack_memory_usage: 1_000,
+            ..ScriptConfig::default()
+        };
+
+        assert!(run_with_config("OP_1 e803 OP_NUM2BIN", &ScriptConfig::default()).is_ok());
+        // 2^31 - 1 bytes
+        assert!(matches!(run_with_config("OP_1 ffffff7f OP_NUM2BIN", &config), Err(InterpreterError::PushSize)));
+        assert!(matches!(run_with_config("OP_1 0902 OP_NUM2BIN", &ScriptConfig::pre_genesis()), Err(InterpreterError::PushSize)));
+    }
+
+    #[test]
+    fn cat_size_is_checked() {
+        let element = hex::encode([0x01; 300]);
+        let asm = format!("{} OP_DUP OP_CAT", element);
+
+        assert!(run_with_config(&asm, &ScriptConfig::post_genesis()).is_ok());
+        assert!(matches!(run_with_config(&asm, &ScriptConfig::pre_genesis()), Err(InterpreterError::PushSize)));
+    }
+
+    #[test]
+    fn cost_counts_steps_and_stack_growth() {
+        let interpreter = run_with_config("OP_1 OP_DROP", &ScriptConfig::default()).unwrap();
+        // 1 for each step, plus the growth of pushing a 1 byte element
+        assert_eq!(interpreter.state().cost, 2 + 1 + ELEMENT_OVERHEAD as u64);
+
+        let config = ScriptConfig {
+            max_cost: 100,
+            ..ScriptConfig::default()
+        };
+        let element = hex::encode([0x01; 100]);
+        assert!(matches!(run_with_config(&element, &config), Err(InterpreterError::ExecutionCost)));
+        assert!(run_with_config("OP_1 OP_DROP OP_1 OP_DROP", &config).is_ok());
+    }
+}
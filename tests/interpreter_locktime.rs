@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod interpreter_locktime_tests {
+    use bsv::{Interpreter, InterpreterError, Script, ScriptConfig, Transaction, TxIn, VerifyFlags};
+
+    /// Minimally encodes a number as little endian sign magnitude hex
+    fn script_num(value: i64) -> String {
+        let mut abs = value.unsigned_abs();
+        let mut bytes = vec![];
+        while abs > 0 {
+            bytes.push((abs & 0xff) as u8);
+            abs >>= 8;
+        }
+
+        match bytes.last() {
+            Some(last) if last & 0x80 != 0 => bytes.push(if value < 0 { 0x80 } else { 0x00 }),
+            Some(_) if value < 0 => *bytes.last_mut().unwrap() |= 0x80,
+            _ => (),
+        }
+
+        hex::encode(bytes)
+    }
+
+    fn run_locking_script(locking_asm: &str, version: u32, n_locktime: u32, sequence: u32, config: &ScriptConfig) -> Result<(), InterpreterError> {
+        let mut tx = Transaction::new(version, n_locktime);
+
+        let mut txin = TxIn::default();
+        txin.set_satoshis(0);
+        txin.set_sequence(sequence);
+        txin.set_locking_script(&Script::from_asm_string(locking_asm).unwrap());
+        tx.add_input(&txin);
+
+        let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, config)?;
+        interpreter.run()
+    }
+
+    fn cltv(locktime: i64, n_locktime: u32, sequence: u32) -> Result<(), InterpreterError> {
+        let asm = format!("{} OP_CHECKLOCKTIMEVERIFY OP_DROP OP_1", script_num(locktime));
+        run_locking_script(&asm, 2, n_locktime, sequence, &ScriptConfig::pre_genesis())
+    }
+
+    fn csv(sequence: i64, version: u32, tx_sequence: u32) -> Result<(), InterpreterError> {
+        let asm = format!("{} OP_CHECKSEQUENCEVERIFY OP_DROP OP_1", script_num(sequence));
+        run_locking_script(&asm, version, 0, tx_sequence, &ScriptConfig::pre_genesis())
+    }
+
+    #[test]
+    fn checklocktimeverify_block_height() {
+        assert!(cltv(100, 100, 0).is_ok());
+        assert!(cltv(100, 150, 0).is_ok());
+        assert!(matches!(cltv(101, 100, 0), Err(InterpreterError::UnsatisfiedLocktime)));
+    }
+
+    #[test]
+    fn checklocktimeverify_timestamp() {
+        assert!(cltv(500_000_000, 500_000_100, 0).is_ok());
+        assert!(matches!(cltv(500_000_200, 500_000_100, 0), Err(InterpreterError::UnsatisfiedLocktime)));
+    }
+
+    #[test]
+    fn checklocktimeverify_rejects_mixed_lock_types() {
+        assert!(matches!(cltv(100, 500_000_100, 0), Err(InterpreterError::UnsatisfiedLocktime)));
+        assert!(matches!(cltv(500_000_000, 100, 0), Err(InterpreterError::UnsatisfiedLocktime)));
+    }
+
+    #[test]
+    fn checklocktimeverify_rejects_final_input() {
+        assert!(matches!(cltv(100, 100, u32::MAX), Err(InterpreterError::UnsatisfiedLocktime)));
+    }
+
+    #[test]
+    fn checklocktimeverify_rejects_negative_and_empty_stack() {
+        assert!(matches!(cltv(-1, 100, 0), Err(InterpreterError::NegativeLocktime)));
+        assert!(matches!(
+            run_locking_script("OP_CHECKLOCKTIMEVERIFY", 2, 100, 0, &ScriptConfig::pre_genesis()),
+            Err(InterpreterError::EmptyStack)
+        ));
+    }
+
+    #[test]
+    fn checksequenceverify_block_count() {
+        assert!(csv(10, 2, 10).is_ok());
+        assert!(csv(10, 2, 20).is_ok());
+        assert!(matches!(csv(11, 2, 10), Err(InterpreterError::UnsatisfiedLocktime)));
+    }
+
+    #[test]
+    fn checksequenceverify_time_interval() {
+        let type_flag = 1 << 22;
+        assert!(csv(type_flag | 10, 2, type_flag as u32 | 10).is_ok());
+        assert!(matches!(csv(type_flag | 10, 2, 10), Err(InterpreterError::UnsatisfiedLocktime)));
+        assert!(matches!(csv(10, 2, type_flag as u32 | 10), Err(InterpreterError::UnsatisfiedLocktime)));
+    }
+
+    #[test]
+    fn checksequenceverify_requires_version_2_and_enabled_sequence() {
+        assert!(matches!(csv(10, 1, 10), Err(InterpreterError::UnsatisfiedLocktime)));
+        assert!(matches!(csv(10, 2, (1 << 31) | 10), Err(InterpreterError::UnsatisfiedLocktime)));
+    }
+
+    #[test]
+    fn checksequenceverify_disable_flag_is_nop() {
+        assert!(csv(1 << 31, 1, 0).is_ok());
+    }
+
+    #[test]
+    fn locktime_opcodes_are_nops_without_flags() {
+        let flags = VerifyFlags {
+            check_locktime_verify: false,
+            check_sequence_verify: false,
+            ..VerifyFlags::pre_genesis()
+        };
+        let config = ScriptConfig::new(flags);
+
+        assert!(run_locking_script("OP_CHECKLOCKTIMEVERIFY OP_CHECKSEQUENCEVERIFY OP_1", 1, 0, u32::MAX, &config).is_ok());
+        assert!(run_locking_script(&format!("{} OP_CHECKLOCKTIMEVERIFY", script_num(1000)), 1, 0, u32::MAX, &ScriptConfig::post_genesis()).is_ok());
+    }
+
+    #[test]
+    fn locktime_opcodes_require_transaction() {
+        let mut interpreter = Interpreter::from_script_with_config(&Script::from_asm_string("OP_1 OP_CHECKLOCKTIMEVERIFY").unwrap(), &ScriptConfig::pre_genesis());
+        assert!(matches!(interpreter.run(), Err(InterpreterError::RequiresTransaction(_))));
+    }
+}
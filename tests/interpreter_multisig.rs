@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod interpreter_multisig_tests {
+    use bsv::{Interpreter, InterpreterError, PrivateKey, Script, ScriptConfig, SigHash, Transaction, TxIn, VerifyFlags};
+
+    const WIFS: [&str; 3] = [
+        "L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh",
+        "Kz859spUJBWUBTYqesPMbW1kmFZ7BisBSJckSVYthvvFZ8cRnaPd",
+        "KxQZuMUEecRFubLb52hmfzK4q1Mq4Wi2FfaEs7ZXHkF2cuJqjK16",
+    ];
+
+    struct Vector {
+        tx: Transaction,
+        locking_script: Script,
+        keys: Vec<PrivateKey>,
+    }
+
+    impl Vector {
+        /// `{pk1}`..`{pk3}` in the locking script asm are replaced with the public keys
+        fn new(locking_asm: &str) -> Vector {
+            let keys: Vec<PrivateKey> = WIFS.iter().map(|x| PrivateKey::from_wif(x).unwrap()).collect();
+
+            let mut locking_asm = locking_asm.to_string();
+            for (i, key) in keys.iter().enumerate() {
+                locking_asm = locking_asm.replace(&format!("{{pk{}}}", i + 1), &key.to_public_key().unwrap().to_hex().unwrap());
+            }
+            let locking_script = Script::from_asm_string(&locking_asm).unwrap();
+
+            let mut tx = Transaction::new(2, 0);
+            let mut txin = TxIn::default();
+            txin.set_satoshis(0);
+            txin.set_locking_script(&locking_script);
+            tx.add_input(&txin);
+
+            Vector { tx, locking_script, keys }
+        }
+
+        /// `{sig1}`..`{sig3}` in the unlocking script asm are replaced with signatures from the matching keys
+        fn run(&self, unlocking_asm: &str, config: &ScriptConfig) -> Result<Vec<Vec<u8>>, InterpreterError> {
+            let mut tx = self.tx.clone();
+            let mut unlocking_asm = unlocking_asm.to_string();
+            for (i, key) in self.keys.iter().enumerate() {
+                let sig = tx.sign(key, SigHash::InputsOutputs, 0, &self.locking_script, 0).unwrap();
+                unlocking_asm = unlocking_asm.replace(&format!("{{sig{}}}", i + 1), &sig.to_hex().unwrap());
+            }
+
+            let mut txin = tx.get_input(0).unwrap();
+            txin.set_unlocking_script(&Script::from_asm_string(&unlocking_asm).unwrap());
+            tx.set_input(0, &txin);
+
+            let mut interpreter = Interpreter::from_transaction_with_config(&tx, 0, config)?;
+            interpreter.run()?;
+            Ok(interpreter.state().stack().to_vec())
+        }
+    }
+
+    fn flags_with(update: impl FnOnce(&mut VerifyFlags)) -> ScriptConfig {
+        let mut flags = VerifyFlags::post_genesis();
+        update(&mut flags);
+        ScriptConfig::new(flags)
+    }
+
+    const TRUE: &[u8] = &[1];
+    const FALSE: &[u8] = &[];
+
+    #[test]
+    fn zero_of_zero_is_valid() {
+        let vector = Vector::new("OP_0 OP_CHECKMULTISIG");
+        assert_eq!(vector.run("OP_0 OP_0", &ScriptConfig::default()).unwrap(), vec![TRUE]);
+    }
+
+    #[test]
+    fn zero_of_n_is_valid() {
+        let vector = Vector::new("OP_0 {pk1} {pk2} OP_2 OP_CHECKMULTISIG");
+        assert_eq!(vector.run("OP_0", &ScriptConfig::default()).unwrap(), vec![TRUE]);
+    }
+
+    #[test]
+    fn signatures_in_key_order_are_valid() {
+        let vector = Vector::new("OP_2 {pk1} {pk2} {pk3} OP_3 OP_CHECKMULTISIG");
+        let config = ScriptConfig::default();
+
+        assert_eq!(vector.run("OP_0 {sig1} {sig2}", &config).unwrap(), vec![TRUE]);
+        assert_eq!(vector.run("OP_0 {sig2} {sig3}", &config).unwrap(), vec![TRUE]);
+        // Keys may be skipped
+        assert_eq!(vector.run("OP_0 {sig1} {sig3}", &config).unwrap(), vec![TRUE]);
+    }
+
+    #[test]
+    fn signatures_out_of_key_order_are_invalid() {
+        let vector = Vector::new("OP_2 {pk1} {pk2} {pk3} OP_3 OP_CHECKMULTISIG");
+        let config = ScriptConfig::default();
+
+        assert_eq!(vector.run("OP_0 {sig2} {sig1}", &config).unwrap(), vec![FALSE]);
+        assert_eq!(vector.run("OP_0 {sig3} {sig1}", &config).unwrap(), vec![FALSE]);
+        // The same signature can not be counted twice
+        assert_eq!(vector.run("OP_0 {sig1} {sig1}", &config).unwrap(), vec![FALSE]);
+    }
+
+    #[test]
+    fn malformed_signatures_and_keys_fail_without_error() {
+        let config = ScriptConfig::default();
+
+        let vector = Vector::new("OP_1 {pk1} OP_1 OP_CHECKMULTISIG");
+        assert_eq!(vector.run("OP_0 3006020101020101", &config).unwrap(), vec![FALSE]);
+        assert_eq!(vector.run("OP_0 aabbccdd41", &config).unwrap(), vec![FALSE]);
+        assert_eq!(vector.run("OP_0 OP_0", &config).unwrap(), vec![FALSE]);
+
+        let vector = Vector::new("OP_1 0411 OP_1 OP_CHECKMULTISIG");
+        assert_eq!(vector.run("OP_0 {sig1}", &config).unwrap(), vec![FALSE]);
+    }
+
+    #[test]
+    fn null_dummy_is_enforced_by_flag() {
+        let vector = Vector::new("OP_1 {pk1} OP_1 OP_CHECKMULTISIG");
+
+        assert_eq!(vector.run("OP_1 {sig1}", &ScriptConfig::default()).unwrap(), vec![TRUE]);
+        assert!(matches!(vector.run("OP_1 {sig1}", &flags_with(|f| f.null_dummy = true)), Err(InterpreterError::SigNullDummy)));
+        assert_eq!(vector.run("OP_0 {sig1}", &flags_with(|f| f.null_dummy = true)).unwrap(), vec![TRUE]);
+    }
+
+    #[test]
+    fn null_fail_only_applies_to_non_empty_signatures() {
+        let vector = Vector::new("OP_2 {pk1} {pk2} OP_2 OP_CHECKMULTISIG");
+        let config = flags_with(|f| f.null_fail = true);
+
+        assert_eq!(vector.run("OP_0 OP_0 OP_0", &config).unwrap(), vec![FALSE]);
+        assert!(matches!(vector.run("OP_0 {sig2} {sig1}", &config), Err(InterpreterError::NullFail)));
+    }
+
+    #[test]
+    fn counts_are_range_checked() {
+        let config = ScriptConfig::default();
+
+        let vector = Vector::new("OP_2 {pk1} OP_1 OP_CHECKMULTISIG");
+        assert!(matches!(vector.run("OP_0 {sig1} {sig1}", &config), Err(InterpreterError::SigCount)));
+
+        let vector = Vector::new("OP_1NEGATE OP_CHECKMULTISIG");
+        assert!(matches!(vector.run("OP_0", &config), Err(InterpreterError::PubKeyCount)));
+
+        let vector = Vector::new(&format!("OP_0 {} OP_16 OP_5 OP_ADD OP_CHECKMULTISIG", vec!["{pk1}"; 21].join(" ")));
+        assert_eq!(vector.run("OP_0", &config).unwrap(), vec![TRUE]);
+        assert!(matches!(vector.run("OP_0", &ScriptConfig::pre_genesis()), Err(InterpreterError::PubKeyCount)));
+    }
+
+    #[test]
+    fn public_keys_count_towards_op_limit() {
+        let vector = Vector::new("OP_0 {pk1} {pk2} {pk3} OP_3 OP_CHECKMULTISIG");
+        let config = ScriptConfig {
+            max_ops_per_script: 3,
+            ..ScriptConfig::default()
+        };
+
+        assert!(matches!(vector.run("OP_0", &config), Err(InterpreterError::OpCount)));
+    }
+
+    #[test]
+    fn missing_dummy_element_fails() {
+        let vector = Vector::new("OP_1 {pk1} OP_1 OP_CHECKMULTISIG");
+        assert!(matches!(vector.run("{sig1}", &ScriptConfig::default()), Err(InterpreterError::EmptyStack)));
+    }
+
+    #[test]
+    fn checkmultisigverify_consumes_result() {
+        let vector = Vector::new("OP_1 {pk1} {pk2} OP_2 OP_CHECKMULTISIGVERIFY OP_1");
+        let config = ScriptConfig::default();
+
+        assert_eq!(vector.run("OP_0 {sig2}", &config).unwrap(), vec![TRUE]);
+        assert!(matches!(vector.run("OP_0 {sig3}", &config), Err(InterpreterError::CheckMultisigVerify)));
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod interpreter_observer_tests {
+    use std::sync::{Arc, Mutex};
+
+    use bsv::{Interpreter, InterpreterError, InterpreterObserver, OpCodes, PrivateKey, Script, ScriptBit, SigHash, State, TraceEvent, TraceRecorder, Transaction, TxIn};
+
+    #[derive(Default)]
+    struct Counter {
+        before: usize,
+        after: usize,
+        errors: usize,
+    }
+
+    impl InterpreterObserver for Counter {
+        fn before_opcode(&mut self, _script_index: usize, _bit: &ScriptBit, _state: &State) {
+            self.before += 1;
+        }
+
+        fn after_opcode(&mut self, _script_index: usize, _bit: &ScriptBit, result: Result<&State, &InterpreterError>) {
+            self.after += 1;
+            if result.is_err() {
+                self.errors += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn observer_sees_every_step() {
+        let counter = Arc::new(Mutex::new(Counter::default()));
+
+        let mut interpreter = Interpreter::from_script(&Script::from_asm_string("OP_1 OP_2 OP_ADD OP_DROP OP_DROP").unwrap());
+        interpreter.add_observer(counter.clone());
+        assert!(interpreter.run().is_err());
+
+        let counter = counter.lock().unwrap();
+        assert_eq!(counter.before, 5);
+        assert_eq!(counter.after, 5);
+        assert_eq!(counter.errors, 1);
+    }
+
+    #[test]
+    fn removed_observer_is_not_notified() {
+        let counter = Arc::new(Mutex::new(Counter::default()));
+
+        let mut interpreter = Interpreter::from_script(&Script::from_asm_string("OP_1 OP_2").unwrap());
+        interpreter.add_observer(counter.clone());
+        interpreter.step().unwrap().unwrap();
+        interpreter.remove_observer(&counter);
+        interpreter.run().unwrap();
+
+        assert_eq!(counter.lock().unwrap().before, 1);
+    }
+
+    #[test]
+    fn trace_recorder_records_branches() {
+        let recorder = Arc::new(Mutex::new(TraceRecorder::new()));
+
+        let mut interpreter = Interpreter::from_script(&Script::from_asm_string("OP_1 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF").unwrap());
+        interpreter.add_observer(recorder.clone());
+        interpreter.run().unwrap();
+
+        let events = recorder.lock().unwrap().events();
+        assert!(events.iter().any(|e| matches!(e, TraceEvent::Branch { opcode: OpCodes::OP_IF, taken: true })));
+
+        match events.last().unwrap() {
+            TraceEvent::Step { state, .. } => assert_eq!(state.stack(), &[vec![2_u8]]),
+            e => panic!("Expected final step, got {:?}", e),
+        }
+
+        // The trace is serialisable for consumers such as debuggers
+        let json = serde_json::to_string(&events).unwrap();
+        let round_trip: Vec<TraceEvent> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.len(), events.len());
+    }
+
+    #[test]
+    fn trace_recorder_records_signature_checks() {
+        let private_key = PrivateKey::from_wif("L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh").unwrap();
+        let locking_script = Script::from_asm_string("OP_CHECKSIG").unwrap();
+
+        let mut tx = Transaction::new(2, 0);
+        let mut txin = TxIn::default();
+        txin.set_satoshis(0);
+        txin.set_locking_script(&locking_script);
+        tx.add_input(&txin);
+
+        let signature = tx.sign(&private_key, SigHash::InputsOutputs, 0, &locking_script, 0).unwrap();
+        let unlocking_asm = format!("{} {}", signature.to_hex().unwrap(), private_key.to_public_key().unwrap().to_hex().unwrap());
+        txin.set_unlocking_script(&Script::from_asm_string(&unlocking_asm).unwrap());
+        tx.set_input(0, &txin);
+
+        let recorder = Arc::new(Mutex::new(TraceRecorder::new()));
+        let mut interpreter = Interpreter::from_transaction(&tx, 0).unwrap();
+        interpreter.add_observer(recorder.clone());
+        interpreter.run().unwrap();
+
+        let events = recorder.lock().unwrap().events();
+        let expected_preimage = tx.sighash_preimage(SigHash::InputsOutputs, 0, &locking_script, 0).unwrap();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, TraceEvent::SighashPreimage { sighash: SigHash::InputsOutputs, preimage } if preimage == &expected_preimage)));
+        assert!(events.iter().any(|e| matches!(e, TraceEvent::SignatureChecked { is_valid: true, .. })));
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod interpreter_signature_tests {
     use bsv::Interpreter;
-    use bsv::{Hash, PrivateKey, Script, SigHash, Transaction, TxIn};
+    use bsv::{Hash, PrivateKey, Script, SigHash, Transaction, TxIn, TxOut, ECDSA, SIGHASH_SINGLE_BUG_DIGEST};
 
     #[test]
     fn simple_p2pkh_signature_test() {
@@ -188,4 +188,39 @@ mod interpreter_signature_tests {
 
         assert_eq!(interpreter.state().stack().last().unwrap(), &vec![1_u8]);
     }
+
+    #[test]
+    fn legacy_sighash_single_without_matching_output_signs_the_number_one() {
+        let private_key = PrivateKey::from_wif("L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh").unwrap();
+        let pubkey = private_key.to_public_key().unwrap();
+        let locking_script = Script::from_asm_string(&format!("OP_DUP OP_HASH160 {} OP_EQUALVERIFY OP_CHECKSIG", Hash::hash_160(&pubkey.to_bytes().unwrap()).to_hex())).unwrap();
+
+        let mut signature = ECDSA::sign_digest_with_deterministic_k(&private_key, &SIGHASH_SINGLE_BUG_DIGEST).unwrap().to_der_bytes();
+        signature.push(SigHash::SINGLE as u8);
+        let unlocking_script = Script::from_asm_string(&format!("{} {}", hex::encode(&signature), pubkey.to_hex().unwrap())).unwrap();
+
+        let mut tx = Transaction::new(1, 0);
+        for vout in 0..2 {
+            let mut txin = TxIn::new(&[7; 32], vout, &unlocking_script, None);
+            txin.set_satoshis(1000);
+            txin.set_locking_script(&locking_script);
+            tx.add_input(&txin);
+        }
+        tx.add_output(&TxOut::new(500, &locking_script));
+
+        // Input 1 has no output at its index, the signature does not commit to the transaction
+        let mut interpreter = Interpreter::from_transaction(&tx, 1).unwrap();
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack().last().unwrap(), &vec![1_u8]);
+
+        tx.set_output(0, &TxOut::new(1, &locking_script));
+        let mut interpreter = Interpreter::from_transaction(&tx, 1).unwrap();
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack().last().unwrap(), &vec![1_u8]);
+
+        // Input 0 has a matching output and signs the usual preimage
+        let mut interpreter = Interpreter::from_transaction(&tx, 0).unwrap();
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack().last().unwrap(), &Vec::<u8>::new());
+    }
 }
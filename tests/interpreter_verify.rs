@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod interpreter_verify_tests {
+    use bsv::{Interpreter, InterpreterError, P2PKHAddress, PrivateKey, Script, ScriptConfig, ScriptError, ScriptStage, SigHash, Transaction, TxIn, TxOut};
+
+    const WIF: &str = "L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh";
+
+    fn p2pkh_output(private_key: &PrivateKey, satoshis: u64) -> TxOut {
+        let address = P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap();
+        TxOut::new(satoshis, &address.get_locking_script().unwrap())
+    }
+
+    /// Signs input `index` of the transaction as a P2PKH spend of `prev_output`.
+    fn sign_p2pkh(tx: &mut Transaction, index: usize, private_key: &PrivateKey, prev_output: &TxOut) {
+        let signature = tx
+            .sign(private_key, SigHash::InputsOutputs, index, &prev_output.get_script_pub_key(), prev_output.get_satoshis())
+            .unwrap();
+        let address = P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap();
+
+        let mut txin = tx.get_input(index).unwrap();
+        txin.set_unlocking_script(&address.get_unlocking_script(&private_key.to_public_key().unwrap(), &signature).unwrap());
+        tx.set_input(index, &txin);
+    }
+
+    fn spend(unlocking_asm: &str) -> Transaction {
+        let mut tx = Transaction::new(2, 0);
+        let mut txin = TxIn::default();
+        txin.set_unlocking_script(&Script::from_asm_string(unlocking_asm).unwrap());
+        tx.add_input(&txin);
+        tx
+    }
+
+    #[test]
+    fn verify_p2pkh_input() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+        let prev_output = p2pkh_output(&private_key, 1000);
+
+        let mut tx = spend("");
+        sign_p2pkh(&mut tx, 0, &private_key, &prev_output);
+
+        let verdict = tx.verify_input(0, &prev_output).unwrap();
+        assert!(verdict.is_valid(), "{:?}", verdict.failure);
+        assert_eq!(verdict.input_index, 0);
+
+        let verdict = tx.verify_input_with_config(0, &prev_output, &ScriptConfig::standard()).unwrap();
+        assert!(verdict.is_valid(), "{:?}", verdict.failure);
+    }
+
+    #[test]
+    fn verify_p2pkh_input_with_wrong_key() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+        let prev_output = p2pkh_output(&PrivateKey::from_random(), 1000);
+
+        let mut tx = spend("");
+        sign_p2pkh(&mut tx, 0, &private_key, &prev_output);
+
+        let failure = tx.verify_input(0, &prev_output).unwrap().failure.unwrap();
+        assert_eq!(failure.stage, ScriptStage::Locking);
+        // OP_DUP OP_HASH160 <pkh> OP_EQUALVERIFY
+        assert_eq!(failure.script_index, 3);
+        assert!(matches!(failure.error, InterpreterError::EqualVerify));
+        assert_eq!(failure.script_error(), ScriptError::Equalverify);
+    }
+
+    #[test]
+    fn verify_input_with_false_result() {
+        let tx = spend("OP_0");
+        let prev_output = TxOut::new(0, &Script::from_asm_string("OP_NOP").unwrap());
+
+        let failure = tx.verify_input(0, &prev_output).unwrap().failure.unwrap();
+        assert_eq!(failure.stage, ScriptStage::Locking);
+        assert_eq!(failure.script_index, 1);
+        assert!(matches!(failure.error, InterpreterError::EvalFalse));
+
+        // Negative zero is false too
+        let tx = spend("80");
+        let failure = tx.verify_input(0, &prev_output).unwrap().failure.unwrap();
+        assert!(matches!(failure.error, InterpreterError::EvalFalse));
+
+        let tx = spend("");
+        let failure = tx.verify_input(0, &prev_output).unwrap().failure.unwrap();
+        assert!(matches!(failure.error, InterpreterError::EvalFalse));
+    }
+
+    #[test]
+    fn unlocking_script_cannot_skip_the_locking_script() {
+        let mut tx = spend("OP_RETURN");
+        let prev_output = TxOut::new(0, &Script::from_asm_string("OP_0").unwrap());
+        let mut txin = tx.get_input(0).unwrap();
+        txin.set_locking_script(&prev_output.get_script_pub_key());
+        tx.set_input(0, &txin);
+
+        assert!(matches!(Interpreter::from_transaction(&tx, 0), Err(InterpreterError::SigPushOnly)));
+        let failure = tx.verify_input(0, &prev_output).unwrap().failure.unwrap();
+        assert_eq!(failure.stage, ScriptStage::Unlocking);
+        assert!(matches!(failure.error, InterpreterError::SigPushOnly));
+    }
+
+    #[test]
+    fn verify_input_with_clean_stack() {
+        let tx = spend("OP_1 OP_1");
+        let prev_output = TxOut::new(0, &Script::from_asm_string("OP_NOP").unwrap());
+
+        assert!(tx.verify_input(0, &prev_output).unwrap().is_valid());
+
+        let config = ScriptConfig::standard();
+        let failure = tx.verify_input_with_config(0, &prev_output, &config).unwrap().failure.unwrap();
+        assert!(matches!(failure.error, InterpreterError::CleanStack));
+    }
+
+    #[test]
+    fn alt_stack_is_not_carried_to_locking_script() {
+        let tx = spend("OP_1 OP_TOALTSTACK");
+        let prev_output = TxOut::new(0, &Script::from_asm_string("OP_FROMALTSTACK").unwrap());
+
+        // Unlocking scripts with opcodes only run before Genesis
+        let failure = tx.verify_input_with_config(0, &prev_output, &ScriptConfig::pre_genesis()).unwrap().failure.unwrap();
+        assert_eq!(failure.stage, ScriptStage::Locking);
+        assert_eq!(failure.script_index, 0);
+        assert!(matches!(failure.error, InterpreterError::EmptyAltStack));
+    }
+
+    #[test]
+    fn unlocking_script_failure_is_reported() {
+        let tx = spend("OP_1 OP_DROP OP_DROP");
+        let prev_output = TxOut::new(0, &Script::from_asm_string("OP_1").unwrap());
+
+        let failure = tx.verify_input_with_config(0, &prev_output, &ScriptConfig::pre_genesis()).unwrap().failure.unwrap();
+        assert_eq!(failure.stage, ScriptStage::Unlocking);
+        assert_eq!(failure.script_index, 2);
+        assert!(matches!(failure.error, InterpreterError::EmptyStack));
+
+        let tx = spend("OP_1 OP_DUP");
+        let failure = tx.verify_input(0, &prev_output).unwrap().failure.unwrap();
+        assert_eq!(failure.stage, ScriptStage::Unlocking);
+        assert_eq!(failure.script_index, 1);
+        assert!(matches!(failure.error, InterpreterError::SigPushOnly));
+    }
+
+    #[test]
+    fn verify_all_inputs_reports_each_input() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+        let prev_outputs = vec![p2pkh_output(&private_key, 1000), p2pkh_output(&private_key, 2000)];
+
+        let mut tx = Transaction::new(2, 0);
+        tx.add_input(&TxIn::default());
+        tx.add_input(&TxIn::default());
+        sign_p2pkh(&mut tx, 0, &private_key, &prev_outputs[0]);
+        // Signed for the wrong amount
+        sign_p2pkh(&mut tx, 1, &private_key, &prev_outputs[0]);
+
+        let verdicts = tx.verify_all_inputs(&prev_outputs).unwrap();
+        assert_eq!(verdicts.len(), 2);
+        assert!(verdicts[0].is_valid());
+        assert!(!verdicts[1].is_valid());
+        assert_eq!(verdicts[1].input_index, 1);
+
+        assert!(tx.verify_all_inputs(&prev_outputs[..1]).is_err());
+        assert!(tx.verify_input(2, &prev_outputs[0]).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod merkle_path_tests {
+    use bsv::{BSVErrors, Hash, MerklePath, MerklePathLeaf};
+
+    // Block 100000
+    const BLOCK_TXIDS: [&str; 4] = [
+        "8c14f0db3df150123e6f3dbbf30f8b955a8249b62ac1d1ff16284aefa3d06d87",
+        "fff2525b8931402dd09222c50775608f75787bd2b87e56995a7bdd30f79702c4",
+        "6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4",
+        "e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d",
+    ];
+    const BLOCK_MERKLE_ROOT: &str = "f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766";
+
+    // The example of BRC-74, block 813706
+    const BRC74_HEX: &str = "fe8a6a0c000c04fde80b0011774f01d26412f0d16ea3f0447be0b5ebec67b0782e321a7a01cbdf7f734e30fde90b02004e53753e3fe4667073063a17987292cfdea278824e9888e52180581d7188d8fdea0b025e441996fc53f0191d649e68a200e752fb5f39e0d5617083408fa179ddc5c998fdeb0b0102fdf405000671394f72237d08a4277f4435e5b6edf7adc272f25effef27cdfe805ce71a81fdf50500262bccabec6c4af3ed00cc7a7414edea9c5efa92fb8623dd6160a001450a528201fdfb020101fd7c010093b3efca9b77ddec914f8effac691ecb54e2c81d0ab81cbc4c4b93befe418e8501bf01015e005881826eb6973c54003a02118fe270f03d46d02681c8bc71cd44c613e86302f8012e00e07a2bb8bb75e5accff266022e1e5e6e7b4d6d943a04faadcf2ab4a22f796ff30116008120cafa17309c0bb0e0ffce835286b3a2dcae48e4497ae2d2b7ced4f051507d010a00502e59ac92f46543c23006bff855d96f5e648043f0fb87a7a5949e6a9bebae430104001ccd9f8f64f4d0489b30cc815351cf425e0e78ad79a589350e4341ac165dbe45010301010000af8764ce7e1cc132ab5ed2229a005c87201c9a5ee15c0f91dd53eff31ab30cd4";
+    const BRC74_TXIDS: [&str; 2] = [
+        "d888711d588021e588984e8278a2decf927298173a06737066e43f3e75534e00",
+        "98c9c5dd79a18f40837061d5e0395ffb52e700a2689e641d19f053fc9619445e",
+    ];
+    const BRC74_MERKLE_ROOT: &str = "57aab6e6fb1b697174ffb64e062c4728f2ffd33ddcfa02a43b64d8cd29b483b4";
+
+    fn txid(hex_str: &str) -> Vec<u8> {
+        hex::decode(hex_str).unwrap()
+    }
+
+    fn hash_pair(left: &[u8], right: &[u8]) -> Vec<u8> {
+        let internal = |x: &[u8]| x.iter().rev().copied().collect::<Vec<u8>>();
+        let mut hash = Hash::sha_256d(&[internal(left), internal(right)].concat()).to_bytes();
+        hash.reverse();
+        hash
+    }
+
+    /// Synthetic txids, with every level of their tree.
+    fn tree(n_txs: usize) -> Vec<Vec<Vec<u8>>> {
+        let mut levels = vec![(0..n_txs as u32).map(|i| Hash::sha_256d(&i.to_le_bytes()).to_bytes()).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels.last().unwrap();
+            levels.push(level.chunks(2).map(|x| hash_pair(&x[0], x.get(1).unwrap_or(&x[0]))).collect());
+        }
+        levels
+    }
+
+    /// A path listing every transaction, with the duplicates at the end of odd length levels.
+    fn full_path(levels: &[Vec<Vec<u8>>]) -> MerklePath {
+        // A block of just the coinbase has no siblings
+        if levels.len() == 1 {
+            return MerklePath::new(1_000, vec![vec![MerklePathLeaf::new(0, &levels[0][0], true)]]).unwrap();
+        }
+
+        let path = levels[..levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| {
+                let mut leaves = match height {
+                    0 => level.iter().enumerate().map(|(offset, hash)| MerklePathLeaf::new(offset as u64, hash, true)).collect(),
+                    _ => vec![],
+                };
+                if level.len() % 2 == 1 {
+                    leaves.push(MerklePathLeaf::duplicate(level.len() as u64));
+                }
+                leaves
+            })
+            .collect();
+        MerklePath::new(1_000, path).unwrap()
+    }
+
+    #[test]
+    fn computes_the_root_of_a_block() {
+        let path = MerklePath::new(
+            100_000,
+            vec![
+                vec![MerklePathLeaf::new(3, &txid(BLOCK_TXIDS[3]), false), MerklePathLeaf::new(2, &txid(BLOCK_TXIDS[2]), true)],
+                vec![MerklePathLeaf::new(0, &hash_pair(&txid(BLOCK_TXIDS[0]), &txid(BLOCK_TXIDS[1])), false)],
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(path.compute_root_hex(BLOCK_TXIDS[2]).unwrap(), BLOCK_MERKLE_ROOT);
+        // The sibling is also proven by the path, though it is not flagged as a txid
+        assert_eq!(path.compute_root_hex(BLOCK_TXIDS[3]).unwrap(), BLOCK_MERKLE_ROOT);
+        assert_eq!(path.txids(), vec![txid(BLOCK_TXIDS[2])]);
+        assert!(matches!(path.compute_root(&txid(BLOCK_TXIDS[0])), Err(BSVErrors::MerklePath(_))));
+
+        let full = MerklePath::new(
+            100_000,
+            vec![BLOCK_TXIDS.iter().enumerate().map(|(i, x)| MerklePathLeaf::new(i as u64, &txid(x), true)).collect(), vec![]],
+        )
+        .unwrap();
+        for txid in BLOCK_TXIDS {
+            assert_eq!(full.compute_root_hex(txid).unwrap(), BLOCK_MERKLE_ROOT);
+        }
+    }
+
+    #[test]
+    fn round_trips_bytes() {
+        let path = MerklePath::new(
+            100_000,
+            vec![
+                vec![MerklePathLeaf::new(2, &txid(BLOCK_TXIDS[2]), true), MerklePathLeaf::new(3, &txid(BLOCK_TXIDS[3]), false)],
+                vec![MerklePathLeaf::new(0, &hash_pair(&txid(BLOCK_TXIDS[0]), &txid(BLOCK_TXIDS[1])), false)],
+            ],
+        )
+        .unwrap();
+
+        let reversed = |x: &[u8]| x.iter().rev().map(|x| format!("{:02x}", x)).collect::<String>();
+        let expected = [
+            "fea0860100",
+            "02",
+            "02",
+            "0202",
+            &reversed(&txid(BLOCK_TXIDS[2])),
+            "0300",
+            &reversed(&txid(BLOCK_TXIDS[3])),
+            "01",
+            "0000",
+            &reversed(&hash_pair(&txid(BLOCK_TXIDS[0]), &txid(BLOCK_TXIDS[1]))),
+        ]
+        .concat();
+        assert_eq!(path.to_hex().unwrap(), expected);
+        assert_eq!(MerklePath::from_hex(&expected).unwrap(), path);
+        assert_eq!(MerklePath::from_hex(&expected).unwrap().compute_root_hex(BLOCK_TXIDS[2]).unwrap(), BLOCK_MERKLE_ROOT);
+
+        let levels = tree(37);
+        let full = full_path(&levels);
+        assert_eq!(MerklePath::from_bytes(&full.to_bytes().unwrap()).unwrap(), full);
+
+        assert!(MerklePath::from_hex(&expected[..expected.len() - 2]).is_err());
+        assert!(MerklePath::from_hex("fea086010000").is_err());
+    }
+
+    #[test]
+    fn parses_the_brc74_example() {
+        let path = MerklePath::from_hex(BRC74_HEX).unwrap();
+        assert_eq!(path.to_hex().unwrap(), BRC74_HEX);
+        assert_eq!(path.txids(), BRC74_TXIDS.iter().map(|x| txid(x)).collect::<Vec<_>>());
+        for txid in BRC74_TXIDS {
+            assert_eq!(path.compute_root_hex(txid).unwrap(), BRC74_MERKLE_ROOT);
+        }
+    }
+
+    #[test]
+    fn rejects_paths_taller_than_64_levels() {
+        let path = (0..=64_u64).map(|height| vec![MerklePathLeaf::new(1, &[height as u8; 32], height == 0)]).collect::<Vec<_>>();
+        assert!(MerklePath::new(1_000, path[..64].to_vec()).is_ok());
+        assert!(matches!(MerklePath::new(1_000, path), Err(BSVErrors::MerklePath(_))));
+
+        // Height 65, a transaction at offset 0 and every sibling on its path duplicated
+        let bytes = ["fde803", "41", "02", "0002", &"11".repeat(32), "0101", &"010101".repeat(64)].concat();
+        assert!(matches!(MerklePath::from_hex(&bytes), Err(BSVErrors::MerklePath(_))));
+        let bytes = ["fde803", "40", "02", "0002", &"11".repeat(32), "0101", &"010101".repeat(63)].concat();
+        assert!(MerklePath::from_hex(&bytes).unwrap().compute_root_hex(&"11".repeat(32)).is_ok());
+    }
+
+    #[test]
+    fn handles_odd_length_levels() {
+        for n_txs in [1, 2, 5, 11, 16] {
+            let levels = tree(n_txs);
+            let root = levels.last().unwrap()[0].clone();
+            let full = full_path(&levels);
+            for txid in &levels[0] {
+                assert_eq!(full.compute_root(txid).unwrap(), root, "{} transactions", n_txs);
+            }
+        }
+    }
+
+    #[test]
+    fn extracts_minimal_paths() {
+        let levels = tree(5);
+        let root = levels.last().unwrap()[0].clone();
+        let full = full_path(&levels);
+
+        let single = full.extract(&[levels[0][0].clone()]).unwrap();
+        let shape = single
+            .path
+            .iter()
+            .map(|level| level.iter().map(|x| (x.offset, x.hash.is_some(), x.txid)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(shape, vec![vec![(0, true, true), (1, true, false)], vec![(1, true, false)], vec![(1, true, false)]]);
+        assert_eq!(
+            single.path[2][0].hash.as_ref(),
+            Some(&hash_pair(&hash_pair(&levels[0][4], &levels[0][4]), &hash_pair(&levels[0][4], &levels[0][4])))
+        );
+        assert_eq!(single.compute_root(&levels[0][0]).unwrap(), root);
+
+        // The last transaction needs the duplicates
+        let last = full.extract(&[levels[0][4].clone()]).unwrap();
+        assert_eq!(last.path[0][1], MerklePathLeaf::duplicate(5));
+        assert_eq!(last.path[1][0], MerklePathLeaf::duplicate(3));
+        assert_eq!(last.compute_root(&levels[0][4]).unwrap(), root);
+
+        // Siblings of each other need nothing else at the first level
+        let pair = full.extract(&[levels[0][3].clone(), levels[0][2].clone()]).unwrap();
+        assert_eq!(pair.path[0].len(), 2);
+        assert_eq!(pair.txids(), vec![levels[0][2].clone(), levels[0][3].clone()]);
+        assert!(pair.to_bytes().unwrap().len() < full.to_bytes().unwrap().len());
+
+        assert!(full.extract(&[vec![0; 32]]).is_err());
+    }
+
+    #[test]
+    fn combines_paths_of_the_same_block() {
+        let levels = tree(11);
+        let full = full_path(&levels);
+        let txids = [levels[0][1].clone(), levels[0][6].clone(), levels[0][10].clone()];
+
+        let combined = full.extract(&txids[..1]).unwrap().combine(&full.extract(&txids[1..]).unwrap()).unwrap();
+        assert_eq!(combined, full.extract(&txids).unwrap());
+        for txid in &txids {
+            assert_eq!(combined.compute_root(txid).unwrap(), levels.last().unwrap()[0]);
+        }
+
+        let mut other_block = full.extract(&txids[..1]).unwrap();
+        other_block.block_height += 1;
+        assert!(combined.combine(&other_block).is_err());
+
+        let other_tree = full_path(&tree(12)).extract(&[tree(12)[0][1].clone()]).unwrap();
+        assert!(combined.combine(&other_tree).is_err());
+    }
+}
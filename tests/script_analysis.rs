@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod script_analysis_tests {
+    use bsv::{P2PKHAddress, PrivateKey, Script, ScriptType};
+
+    fn public_key_hex(seed: u8) -> String {
+        let private_key = PrivateKey::from_bytes(&[seed; 32]).unwrap();
+        private_key.to_public_key().unwrap().to_hex().unwrap()
+    }
+
+    fn p2pkh_asm() -> String {
+        let private_key = PrivateKey::from_bytes(&[1; 32]).unwrap();
+        P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap().get_locking_script().unwrap().to_asm_string()
+    }
+
+    fn classify(asm: &str) -> ScriptType {
+        Script::from_asm_string(asm).unwrap().classify()
+    }
+
+    #[test]
+    fn classifies_key_and_hash_locks() {
+        assert_eq!(classify(&p2pkh_asm()), ScriptType::P2PKH);
+        assert_eq!(classify(&format!("{} OP_CHECKSIG", public_key_hex(1))), ScriptType::P2PK);
+        assert_eq!(classify("OP_HASH160 0a0b0c0d0e0f101112131415161718191a1b1c1d OP_EQUAL"), ScriptType::P2SH);
+
+        // Not a valid public key
+        assert_eq!(classify("0a0b0c OP_CHECKSIG"), ScriptType::NonStandard);
+        assert_eq!(classify(&format!("{} OP_NOP", p2pkh_asm())), ScriptType::NonStandard);
+    }
+
+    #[test]
+    fn classifies_bare_multisig() {
+        let keys = (1..=3).map(public_key_hex).collect::<Vec<_>>().join(" ");
+        assert_eq!(classify(&format!("OP_2 {} OP_3 OP_CHECKMULTISIG", keys)), ScriptType::Multisig { required: 2, keys: 3 });
+
+        // The key count has to agree with the keys pushed, and at most all keys can be required
+        assert_eq!(classify(&format!("OP_2 {} OP_2 OP_CHECKMULTISIG", keys)), ScriptType::NonStandard);
+        assert_eq!(classify(&format!("OP_1 {} OP_1 OP_CHECKMULTISIG", public_key_hex(1))), ScriptType::Multisig { required: 1, keys: 1 });
+        assert_eq!(classify(&format!("OP_2 {} OP_1 OP_CHECKMULTISIG", public_key_hex(1))), ScriptType::NonStandard);
+    }
+
+    #[test]
+    fn classifies_data_carriers_and_r_puzzles() {
+        assert_eq!(classify("OP_0 OP_RETURN 68656c6c6f 776f726c64"), ScriptType::DataCarrier);
+        assert_eq!(classify("OP_0 OP_RETURN"), ScriptType::DataCarrier);
+        assert_eq!(classify("OP_RETURN 68656c6c6f"), ScriptType::NonStandard);
+
+        let prefix = "OP_OVER OP_3 OP_SPLIT OP_NIP OP_1 OP_SPLIT OP_SWAP OP_SPLIT OP_DROP";
+        assert_eq!(classify(&format!("{} {} OP_EQUALVERIFY OP_CHECKSIG", prefix, "11".repeat(32))), ScriptType::RPuzzle);
+        assert_eq!(classify(&format!("{} OP_HASH160 {} OP_EQUALVERIFY OP_CHECKSIG", prefix, "11".repeat(20))), ScriptType::RPuzzle);
+        assert_eq!(classify(&format!("{} OP_HASH160 {} OP_EQUAL OP_CHECKSIG", prefix, "11".repeat(20))), ScriptType::NonStandard);
+    }
+
+    #[test]
+    fn classifies_ordinal_inscriptions() {
+        // OP_FALSE OP_IF "ord" OP_1 "text/plain" OP_0 "hello" OP_ENDIF
+        let envelope = "OP_0 OP_IF 6f7264 OP_1 746578742f706c61696e OP_0 68656c6c6f OP_ENDIF";
+        assert_eq!(classify(envelope), ScriptType::OrdinalInscription);
+
+        let script = Script::from_asm_string(&format!("{} {}", p2pkh_asm(), envelope)).unwrap();
+        assert_eq!(script.classify(), ScriptType::OrdinalInscription);
+        assert!(Script::from_bytes(&script.to_bytes()).unwrap().has_ordinal_inscription());
+
+        assert_eq!(classify("OP_1 OP_IF 6f7264 OP_ENDIF"), ScriptType::NonStandard);
+        assert_eq!(classify("OP_0 OP_IF 6f7265 OP_ENDIF"), ScriptType::NonStandard);
+    }
+
+    #[test]
+    fn counts_sigops() {
+        let script = Script::from_asm_string("OP_CHECKSIG OP_IF OP_CHECKSIGVERIFY OP_ELSE OP_3 OP_CHECKMULTISIG OP_ENDIF OP_CHECKMULTISIGVERIFY").unwrap();
+        assert_eq!(script.count_sigops(false), 1 + 1 + 20 + 20);
+        assert_eq!(script.count_sigops(true), 1 + 1 + 3 + 20);
+
+        assert_eq!(Script::from_asm_string(&p2pkh_asm()).unwrap().count_sigops(true), 1);
+        assert_eq!(Script::from_asm_string("OP_0 OP_RETURN ac").unwrap().count_sigops(true), 0);
+    }
+
+    #[test]
+    fn detects_unspendable_scripts() {
+        let data_carrier = Script::from_asm_string("OP_0 OP_RETURN 68656c6c6f").unwrap();
+        assert!(data_carrier.is_provably_unspendable(true));
+        assert!(data_carrier.is_provably_unspendable(false));
+
+        let op_return = Script::from_asm_string("OP_RETURN 68656c6c6f").unwrap();
+        assert!(!op_return.is_provably_unspendable(true));
+        assert!(op_return.is_provably_unspendable(false));
+
+        let large = Script::from_asm_string(&format!("{} OP_DROP OP_1", "00".repeat(10_000))).unwrap();
+        assert!(!large.is_provably_unspendable(true));
+        assert!(large.is_provably_unspendable(false));
+
+        assert!(!Script::from_asm_string(&p2pkh_asm()).unwrap().is_provably_unspendable(false));
+    }
+
+    #[test]
+    fn reports_push_only() {
+        assert!(Script::from_asm_string("OP_0 OP_1NEGATE OP_16 68656c6c6f").unwrap().is_push_only());
+        assert!(Script::default().is_push_only());
+        assert!(!Script::from_asm_string("68656c6c6f OP_DROP").unwrap().is_push_only());
+        assert!(!Script::from_asm_string("OP_1 OP_IF OP_1 OP_ENDIF").unwrap().is_push_only());
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod script_asm_dialect_tests {
+    use bsv::{AsmDialect, OpCodes, PrivateKey, Script, ScriptBuilder, SigHash, Transaction, TxIn, TxOut};
+
+    fn signature_bytes(sighash: SigHash) -> Vec<u8> {
+        let private_key = PrivateKey::from_bytes(&[1; 32]).unwrap();
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&TxIn::new(&[1; 32], 0, &Script::default(), None));
+        tx.add_output(&TxOut::new(500, &Script::default()));
+        tx.sign(&private_key, sighash, 0, &Script::default(), 1000).unwrap().to_bytes().unwrap()
+    }
+
+    fn round_trip(hex: &str, dialect: AsmDialect, asm: &str) {
+        let script = Script::from_hex(hex).unwrap();
+        assert_eq!(script.to_asm_string_with_dialect(dialect), asm);
+        assert_eq!(Script::from_asm_string_with_dialect(asm, dialect).unwrap(), script);
+    }
+
+    #[test]
+    fn bitcoind_writes_small_pushes_as_numbers() {
+        // OP_0 OP_1NEGATE OP_16 <11> <8000> <ffffffff> <ffff7f> <0102030405> OP_ADD
+        round_trip(
+            concat!("004f600111028000", "04ffffffff", "03ffff7f", "050102030405", "93"),
+            AsmDialect::Bitcoind,
+            "0 -1 16 17 128 -2147483647 8388607 0102030405 OP_ADD",
+        );
+    }
+
+    #[test]
+    fn bitcoind_reads_long_decimal_tokens_as_hex() {
+        round_trip("051122334455", AsmDialect::Bitcoind, "1122334455");
+        round_trip("0a11223344556677889900", AsmDialect::Bitcoind, "11223344556677889900");
+
+        // A positive number of 10 digits cannot be told apart from a 5 byte push
+        let script = Script::from_hex("0400ca9a3b").unwrap();
+        assert_eq!(script.to_asm_string_with_dialect(AsmDialect::Bitcoind), "1000000000");
+        assert_eq!(Script::from_asm_string_with_dialect("1000000000", AsmDialect::Bitcoind).unwrap().to_hex(), "051000000000");
+    }
+
+    #[test]
+    fn upgradable_nops_keep_their_node_names() {
+        round_trip("b0b3b4b5b6b7b8b9", AsmDialect::Bitcoind, "OP_NOP1 OP_NOP4 OP_NOP5 OP_NOP6 OP_NOP7 OP_NOP8 OP_NOP9 OP_NOP10");
+        round_trip("b3b7", AsmDialect::BsvJs, "OP_NOP4 OP_NOP8");
+    }
+
+    #[test]
+    fn bitcoind_decodes_signature_sighash_types() {
+        let signature = signature_bytes(SigHash::InputsOutputs);
+        let der = hex::encode(&signature[..signature.len() - 1]);
+        let script = ScriptBuilder::new().push_data(&signature).push_data(&[2; 33]).build();
+        let asm = format!("{}[ALL|FORKID] {}", der, "02".repeat(33));
+        assert_eq!(script.to_asm_string_with_dialect(AsmDialect::Bitcoind), asm);
+        assert_eq!(Script::from_asm_string_with_dialect(&asm, AsmDialect::Bitcoind).unwrap(), script);
+
+        let signature = signature_bytes(SigHash::Legacy_InputOutput);
+        let script = ScriptBuilder::new().push_data(&signature).build();
+        assert_eq!(
+            script.to_asm_string_with_dialect(AsmDialect::Bitcoind),
+            format!("{}[SINGLE|ANYONECANPAY]", hex::encode(&signature[..signature.len() - 1]))
+        );
+
+        // Not decoded in unspendable scripts
+        let script = ScriptBuilder::new().push_bool(false).push_opcode(OpCodes::OP_RETURN).push_data(&signature).build();
+        assert_eq!(script.to_asm_string_with_dialect(AsmDialect::Bitcoind), format!("0 OP_RETURN {}", hex::encode(&signature)));
+    }
+
+    #[test]
+    fn bitcoind_marks_unknown_and_truncated_instructions() {
+        let script = Script::from_hex("51bb4c").unwrap();
+        assert_eq!(script.to_asm_string_with_dialect(AsmDialect::Bitcoind), "1 OP_UNKNOWN [error]");
+        assert!(Script::from_asm_string_with_dialect("1 OP_UNKNOWN", AsmDialect::Bitcoind).is_err());
+        assert!(Script::from_asm_string_with_dialect("3044[ANY]", AsmDialect::Bitcoind).is_err());
+    }
+
+    #[test]
+    fn bsv_js_names_opcodes_except_zero_and_negative_one() {
+        round_trip("004f516001016a0568656c6c6f", AsmDialect::BsvJs, "0 -1 OP_1 OP_16 01 OP_RETURN 68656c6c6f");
+
+        // Unknown opcodes are their hex byte, which bsv.js reads back as a push
+        assert_eq!(Script::from_hex("bb").unwrap().to_asm_string_with_dialect(AsmDialect::BsvJs), "bb");
+        assert_eq!(Script::from_asm_string_with_dialect("OP_FALSE OP_TRUE", AsmDialect::BsvJs).unwrap().to_hex(), "0051");
+        // Hex digits are data rather than small numbers
+        assert_eq!(Script::from_asm_string_with_dialect("10", AsmDialect::BsvJs).unwrap().to_hex(), "0110");
+    }
+
+    #[test]
+    fn scrypt_names_every_opcode() {
+        round_trip("004f5263630068670068", AsmDialect::Scrypt, "OP_0 OP_1NEGATE OP_2 OP_IF OP_IF OP_0 OP_ENDIF OP_ELSE OP_0 OP_ENDIF");
+        round_trip("0568656c6c6f87", AsmDialect::Scrypt, "68656c6c6f OP_EQUAL");
+        assert_eq!(Script::from_asm_string_with_dialect("OP_FALSE OP_RETURN", AsmDialect::Scrypt).unwrap().to_hex(), "006a");
+    }
+
+    #[test]
+    fn default_dialect_is_the_plain_asm() {
+        let script = Script::from_hex("00516a0102").unwrap();
+        assert_eq!(script.to_asm_string_with_dialect(AsmDialect::Default), script.to_asm_string());
+        assert_eq!(Script::from_asm_string_with_dialect(&script.to_asm_string(), AsmDialect::Default).unwrap(), script);
+    }
+}
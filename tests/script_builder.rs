@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod script_builder_tests {
+    use bsv::{Interpreter, OpCodes, P2PKHAddress, PrivateKey, Script, ScriptBit, ScriptBuilder, SigHash, Transaction, TxIn, TxOut};
+
+    #[test]
+    fn push_data_uses_the_smallest_push() {
+        let script = ScriptBuilder::new()
+            .push_data(&[])
+            .push_data(&[5])
+            .push_data(&[0x81])
+            .push_data(&[0])
+            .push_data(&[17])
+            .push_data(&[1; 75])
+            .push_data(&[1; 76])
+            .push_data(&[1; 256])
+            .build();
+
+        assert_eq!(
+            script.to_script_bits(),
+            vec![
+                ScriptBit::OpCode(OpCodes::OP_0),
+                ScriptBit::OpCode(OpCodes::OP_5),
+                ScriptBit::OpCode(OpCodes::OP_1NEGATE),
+                ScriptBit::Push(vec![0]),
+                ScriptBit::Push(vec![17]),
+                ScriptBit::Push(vec![1; 75]),
+                ScriptBit::PushData(OpCodes::OP_PUSHDATA1, vec![1; 76]),
+                ScriptBit::PushData(OpCodes::OP_PUSHDATA2, vec![1; 256]),
+            ]
+        );
+    }
+
+    #[test]
+    fn push_int_uses_script_numbers() {
+        let script = ScriptBuilder::new()
+            .push_int(0)
+            .push_int(-1)
+            .push_int(16)
+            .push_int(17)
+            .push_int(-2)
+            .push_int(128)
+            .push_int(-256)
+            .build();
+        assert_eq!(script.to_asm_string(), "0 OP_1NEGATE OP_16 11 82 8000 0081");
+
+        let script = ScriptBuilder::new().push_bool(true).push_bool(false).build();
+        assert_eq!(script.to_asm_string(), "OP_1 0");
+    }
+
+    #[test]
+    fn if_else_nests_branches() {
+        let script = ScriptBuilder::new()
+            .push_int(1)
+            .if_else(|pass| pass.push_int(0).if_then(|inner| inner.push_opcode(OpCodes::OP_DROP)).push_int(0), |fail| fail.push_int(3))
+            .push_if(OpCodes::OP_NOTIF, ScriptBuilder::new().push_int(4), None)
+            .build();
+
+        assert_eq!(script.to_asm_string(), "OP_1 OP_IF 0 OP_IF OP_DROP OP_ENDIF 0 OP_ELSE OP_3 OP_ENDIF OP_NOTIF OP_4 OP_ENDIF");
+        assert_eq!(Script::from_bytes(&script.to_bytes()).unwrap(), script);
+
+        let mut interpreter = Interpreter::from_script(&script);
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack(), &vec![vec![4]]);
+    }
+
+    #[test]
+    fn builds_a_p2pkh_unlocking_script() {
+        let private_key = PrivateKey::from_bytes(&[1; 32]).unwrap();
+        let public_key = private_key.to_public_key().unwrap();
+        let address = P2PKHAddress::from_pubkey(&public_key).unwrap();
+        let locking_script = address.get_locking_script().unwrap();
+
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&TxIn::new(&[1; 32], 0, &Script::default(), None));
+        tx.add_output(&TxOut::new(500, &locking_script));
+        let signature = tx.sign(&private_key, SigHash::InputsOutputs, 0, &locking_script, 1000).unwrap();
+
+        let unlocking_script = ScriptBuilder::new().push_sig(&signature).unwrap().push_pubkey(&public_key).unwrap().build();
+        assert_eq!(unlocking_script, address.get_unlocking_script(&public_key, &signature).unwrap());
+    }
+}
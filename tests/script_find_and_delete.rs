@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod script_find_and_delete_tests {
+    use std::collections::HashMap;
+
+    use bsv::{OpCodes, PrivateKey, Script, ScriptBuilder, SigHash, Transaction, TransactionVerifier, TxIn, TxOut};
+
+    fn find_and_delete(script_hex: &str, needle_hex: &str) -> (String, usize) {
+        let mut script = Script::from_hex(script_hex).unwrap();
+        let found = script.find_and_delete(&Script::from_hex(needle_hex).unwrap());
+        (script.to_hex(), found)
+    }
+
+    #[test]
+    fn deletes_on_instruction_boundaries() {
+        assert_eq!(find_and_delete("515253", "52"), ("5153".into(), 1));
+        assert_eq!(find_and_delete("535153535453", "53"), ("5154".into(), 4));
+        assert_eq!(find_and_delete("0302ff03", "0302ff03"), ("".into(), 1));
+        assert_eq!(find_and_delete("0302ff030302ff03", "0302ff03"), ("".into(), 2));
+        assert_eq!(find_and_delete("0003feed", "03feed"), ("00".into(), 1));
+        assert_eq!(find_and_delete("0003feed", "00"), ("03feed".into(), 1));
+
+        // Matches inside a push are left alone
+        assert_eq!(find_and_delete("0302ff030302ff03", "02"), ("0302ff030302ff03".into(), 0));
+        assert_eq!(find_and_delete("0302ff030302ff03", "ff"), ("0302ff030302ff03".into(), 0));
+        assert_eq!(find_and_delete("0302ff030302ff03", "03"), ("02ff0302ff03".into(), 2));
+
+        assert_eq!(find_and_delete("515253", ""), ("515253".into(), 0));
+    }
+
+    #[test]
+    fn removes_codeseparators_in_branches() {
+        let mut script = Script::from_asm_string("OP_1 OP_IF OP_CODESEPARATOR OP_ENDIF OP_CODESEPARATOR").unwrap();
+        script.remove_codeseparators();
+        assert_eq!(script.to_asm_string(), "OP_1 OP_IF OP_ENDIF");
+
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&TxIn::new(&[1; 32], 0, &Script::default(), None));
+        tx.add_output(&TxOut::new(500, &Script::default()));
+        let with_separator = Script::from_asm_string("OP_0 OP_IF OP_CODESEPARATOR OP_ENDIF OP_1").unwrap();
+        let without_separator = Script::from_asm_string("OP_0 OP_IF OP_ENDIF OP_1").unwrap();
+        assert_eq!(
+            tx.sighash_preimage(SigHash::ALL, 0, &with_separator, 1000).unwrap(),
+            tx.sighash_preimage(SigHash::ALL, 0, &without_separator, 1000).unwrap()
+        );
+    }
+
+    /// Spends `<sig> OP_DROP <pubkey> OP_CHECKSIG` with the same signature in the unlocking script.
+    /// The signature is made over the locking script without its own push, which is what the legacy sighash signs.
+    fn verify_signature_in_locking_script(sighash: SigHash) -> bool {
+        let private_key = PrivateKey::from_bytes(&[3; 32]).unwrap();
+        let public_key = private_key.to_public_key().unwrap();
+        let script_code = ScriptBuilder::new()
+            .push_opcode(OpCodes::OP_DROP)
+            .push_pubkey(&public_key)
+            .unwrap()
+            .push_opcode(OpCodes::OP_CHECKSIG)
+            .build();
+
+        let txin = TxIn::new(&[1; 32], 0, &Script::default(), None);
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&txin);
+        tx.add_output(&TxOut::new(500, &Script::default()));
+        let signature = tx.sign(&private_key, sighash, 0, &script_code, 1000).unwrap();
+
+        let locking_script = ScriptBuilder::new().push_sig(&signature).unwrap().push_script(&script_code).build();
+        let mut signed_input = txin.clone();
+        signed_input.set_unlocking_script(&ScriptBuilder::new().push_sig(&signature).unwrap().build());
+        tx.set_input(0, &signed_input);
+
+        let prev_outputs = HashMap::from([(txin.get_outpoint_bytes(Some(true)), TxOut::new(1000, &locking_script))]);
+        TransactionVerifier::new(&tx, &prev_outputs).unwrap().verify_input(0).unwrap().is_valid()
+    }
+
+    #[test]
+    fn legacy_signatures_are_deleted_from_the_signed_script() {
+        assert!(verify_signature_in_locking_script(SigHash::ALL));
+        // FORKID signatures sign the script as it is
+        assert!(!verify_signature_in_locking_script(SigHash::InputsOutputs));
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod script_iter_tests {
+    use bsv::{OpCodes, Script, ScriptItem, ScriptIter};
+
+    fn op(offset: usize, opcode: u8, data: &[u8]) -> ScriptItem<'_> {
+        ScriptItem::Op { offset, opcode, data }
+    }
+
+    #[test]
+    fn yields_opcodes_and_pushes_with_offsets() {
+        let bytes = hex::decode("76a9140102030405060708090a0b0c0d0e0f101112131488ac").unwrap();
+        let items = ScriptIter::new(&bytes).collect::<Vec<_>>();
+
+        assert_eq!(items.len(), 5);
+        assert_eq!(items[0], op(0, 0x76, &[]));
+        assert_eq!(items[2], op(2, 0x14, &bytes[3..23]));
+        assert_eq!(items[4].offset(), 24);
+        assert_eq!(items[4].op_code(), Some(OpCodes::OP_CHECKSIG));
+        assert_eq!(items[2].push_data(), Some(&bytes[3..23]));
+        assert_eq!(items[3].push_data(), None);
+    }
+
+    #[test]
+    fn reads_pushdata_lengths() {
+        let bytes = [0x00, 0x4c, 0x02, 0xaa, 0xbb, 0x4d, 0x01, 0x00, 0xcc, 0x4e, 0x00, 0x00, 0x00, 0x00, 0xc0];
+
+        let items = ScriptIter::new(&bytes).collect::<Vec<_>>();
+        assert_eq!(items, vec![op(0, 0x00, &[]), op(1, 0x4c, &[0xaa, 0xbb]), op(5, 0x4d, &[0xcc]), op(9, 0x4e, &[]), op(14, 0xc0, &[])]);
+        assert_eq!(items[0].push_data(), Some(&[][..]));
+        // 0xc0 is not a known opcode
+        assert_eq!(items[4].op_code(), None);
+    }
+
+    #[test]
+    fn truncated_tail_is_a_distinct_item() {
+        let bytes = [0x51, 0x6a, 0x05, 0x01, 0x02];
+        let items = ScriptIter::new(&bytes).collect::<Vec<_>>();
+        assert_eq!(items, vec![op(0, 0x51, &[]), op(1, 0x6a, &[]), ScriptItem::Malformed { offset: 2, bytes: &bytes[2..] }]);
+
+        // The pushdata length itself can be cut off
+        let bytes = [0x4d, 0x01];
+        assert_eq!(ScriptIter::new(&bytes).collect::<Vec<_>>(), vec![ScriptItem::Malformed { offset: 0, bytes: &bytes[..] }]);
+
+        let bytes = [0x4e, 0xff, 0xff, 0xff, 0xff, 0x00];
+        assert_eq!(ScriptIter::new(&bytes).count(), 1);
+        assert!(matches!(ScriptIter::new(&bytes).next(), Some(ScriptItem::Malformed { offset: 0, .. })));
+    }
+
+    #[test]
+    fn tolerates_unbalanced_and_truncated_scripts() {
+        // Unbalanced OP_IF followed by a truncated push
+        let bytes = [0x63, 0x51, 0x4c];
+        assert_eq!(Script::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+
+        let items = ScriptIter::new(&bytes).collect::<Vec<_>>();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].op_code(), Some(OpCodes::OP_IF));
+        assert!(matches!(items[2], ScriptItem::Malformed { offset: 2, .. }));
+
+        assert_eq!(ScriptIter::new(&[]).next(), None);
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod script_num_tests {
+    use bsv::{Interpreter, InterpreterError, Script, ScriptConfig, ScriptNum, VerifyFlags};
+    use num_bigint::BigInt;
+
+    #[test]
+    fn encodes_minimally() {
+        assert_eq!(ScriptNum::from(0).to_bytes(), Vec::<u8>::new());
+        assert_eq!(ScriptNum::from(1).to_bytes(), vec![0x01]);
+        assert_eq!(ScriptNum::from(-1).to_bytes(), vec![0x81]);
+        assert_eq!(ScriptNum::from(127).to_bytes(), vec![0x7f]);
+        assert_eq!(ScriptNum::from(128).to_bytes(), vec![0x80, 0x00]);
+        assert_eq!(ScriptNum::from(-128).to_bytes(), vec![0x80, 0x80]);
+        assert_eq!(ScriptNum::from(0x8000).to_bytes(), vec![0x00, 0x80, 0x00]);
+    }
+
+    #[test]
+    fn decodes_sign_magnitude() {
+        let decode = |data: &[u8]| ScriptNum::from_bytes(data, 8, false).unwrap().into_bigint();
+
+        assert_eq!(decode(&[]), BigInt::from(0));
+        assert_eq!(decode(&[0x80]), BigInt::from(0));
+        assert_eq!(decode(&[0x81]), BigInt::from(-1));
+        assert_eq!(decode(&[0x01, 0x00]), BigInt::from(1));
+        assert_eq!(decode(&[0xff, 0xff, 0xff, 0xff, 0x00]), BigInt::from(u32::MAX));
+    }
+
+    #[test]
+    fn minimal_encoding() {
+        assert!(ScriptNum::is_minimally_encoded(&[]));
+        assert!(ScriptNum::is_minimally_encoded(&[0x01]));
+        assert!(ScriptNum::is_minimally_encoded(&[0x80, 0x00]));
+        assert!(ScriptNum::is_minimally_encoded(&[0x80, 0x80]));
+
+        assert!(!ScriptNum::is_minimally_encoded(&[0x00]));
+        assert!(!ScriptNum::is_minimally_encoded(&[0x80]));
+        assert!(!ScriptNum::is_minimally_encoded(&[0x01, 0x00]));
+        assert!(!ScriptNum::is_minimally_encoded(&[0x01, 0x80]));
+
+        assert_eq!(ScriptNum::minimally_encode(&[0x01, 0x00, 0x00]), vec![0x01]);
+        assert_eq!(ScriptNum::minimally_encode(&[0x01, 0x00, 0x80]), vec![0x81]);
+        assert_eq!(ScriptNum::minimally_encode(&[0x00, 0x80]), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn length_and_encoding_are_checked() {
+        assert!(matches!(ScriptNum::from_bytes(&[1, 2, 3, 4, 5], 4, false), Err(InterpreterError::NumberOutOfRange)));
+        assert!(matches!(ScriptNum::from_bytes(&[0x01, 0x00], 4, true), Err(InterpreterError::NonMinimalNumber)));
+        assert!(ScriptNum::from_bytes(&[0x01, 0x00], 4, false).is_ok());
+    }
+
+    #[test]
+    fn arithmetic_operands_follow_minimal_data() {
+        let script = Script::from_asm_string("0100 OP_1ADD").unwrap();
+
+        let mut interpreter = Interpreter::from_script(&script);
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack(), &vec![vec![0x02]]);
+
+        let config = ScriptConfig::new(VerifyFlags {
+            minimal_data: true,
+            ..VerifyFlags::post_genesis()
+        });
+        let mut interpreter = Interpreter::from_script_with_config(&script, &config);
+        assert!(matches!(interpreter.run(), Err(InterpreterError::NonMinimalNumber)));
+    }
+
+    #[test]
+    fn operands_longer_than_four_bytes_fail_before_genesis() {
+        let script = Script::from_asm_string("0000008000 OP_1ADD").unwrap();
+
+        let mut interpreter = Interpreter::from_script_with_config(&script, &ScriptConfig::pre_genesis());
+        assert!(matches!(interpreter.run(), Err(InterpreterError::NumberOutOfRange)));
+
+        let mut interpreter = Interpreter::from_script(&script);
+        interpreter.run().unwrap();
+        assert_eq!(interpreter.state().stack(), &vec![vec![0x01, 0x00, 0x00, 0x80, 0x00]]);
+    }
+}
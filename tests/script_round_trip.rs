@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod script_round_trip_tests {
+    use bsv::{Interpreter, InterpreterError, OpCodes, Script, ScriptBit, ScriptError};
+    use rand_core::{OsRng, RngCore};
+
+    fn round_trip(hex: &str) -> Script {
+        let script = Script::from_hex(hex).unwrap();
+        assert_eq!(script.to_hex(), hex);
+        script
+    }
+
+    #[test]
+    fn keeps_non_minimal_pushes() {
+        // OP_PUSHDATA1 of 10 bytes, OP_PUSHDATA2 of nothing and OP_PUSHDATA4 of 2 bytes
+        let script = round_trip("4c0a000102030405060708094d00004e02000000abcd");
+        assert_eq!(
+            script.to_script_bits(),
+            vec![
+                ScriptBit::PushData(OpCodes::OP_PUSHDATA1, (0..10).collect()),
+                ScriptBit::PushData(OpCodes::OP_PUSHDATA2, vec![]),
+                ScriptBit::PushData(OpCodes::OP_PUSHDATA4, vec![0xab, 0xcd]),
+            ]
+        );
+
+        // A number pushed as data rather than as OP_1
+        round_trip("010187");
+    }
+
+    #[test]
+    fn keeps_unknown_opcodes() {
+        let script = round_trip("51bbfa");
+        assert_eq!(
+            script.to_script_bits(),
+            vec![ScriptBit::OpCode(OpCodes::OP_1), ScriptBit::UnknownOpCode(0xbb), ScriptBit::UnknownOpCode(0xfa)]
+        );
+        assert_eq!(script.to_asm_string(), "OP_1 OP_UNKNOWN187 OP_UNKNOWN250");
+        assert_eq!(Script::from_asm_string(&script.to_asm_string()).unwrap(), script);
+    }
+
+    #[test]
+    fn keeps_truncated_pushes() {
+        let script = round_trip("76a914000102");
+        assert_eq!(
+            script.to_script_bits(),
+            vec![
+                ScriptBit::OpCode(OpCodes::OP_DUP),
+                ScriptBit::OpCode(OpCodes::OP_HASH160),
+                ScriptBit::Malformed(vec![0x14, 0x00, 0x01, 0x02])
+            ]
+        );
+        assert_eq!(script.to_asm_string(), "OP_DUP OP_HASH160 malformed:14000102");
+        assert_eq!(Script::from_asm_string(&script.to_asm_string()).unwrap(), script);
+
+        round_trip("4e");
+        round_trip("4dff");
+    }
+
+    #[test]
+    fn keeps_unbalanced_conditionals_flat() {
+        let script = round_trip("6351");
+        assert_eq!(script.to_script_bits(), vec![ScriptBit::OpCode(OpCodes::OP_IF), ScriptBit::OpCode(OpCodes::OP_1)]);
+
+        round_trip("6851");
+        round_trip("67635168");
+        // Balanced conditionals are still nested
+        assert!(matches!(round_trip("0063516851").to_script_bits()[1], ScriptBit::If { .. }));
+    }
+
+    #[test]
+    fn op_verif_blocks_scope_op_return_like_op_if() {
+        // OP_0 OP_VERIF OP_1 OP_ENDIF OP_RETURN <0102>
+        let script = round_trip("006551686a0102");
+        assert_eq!(script.to_script_bits().last(), Some(&ScriptBit::NonScriptData(vec![1, 2])));
+        assert_eq!(Script::from_asm_string(&script.to_asm_string()).unwrap(), script);
+
+        // OP_0 OP_VERNOTIF OP_RETURN OP_ENDIF OP_1
+        let script = round_trip("00666a6851");
+        assert!(matches!(script.to_script_bits()[1], ScriptBit::If { .. }));
+        assert_eq!(Script::from_asm_string(&script.to_asm_string()).unwrap(), script);
+    }
+
+    #[test]
+    fn unknown_opcodes_only_fail_when_executed() {
+        let run = |hex: &str| Interpreter::from_script(&Script::from_hex(hex).unwrap()).run();
+
+        assert!(run("0063bb6851").is_ok());
+        let error = run("51bb").unwrap_err();
+        assert!(matches!(error, InterpreterError::UnknownOpcode(0xbb)));
+        assert_eq!(error.script_error(), ScriptError::BadOpcode);
+
+        // A truncated push can not be read even when the branch is skipped
+        let error = run("00634c").unwrap_err();
+        assert!(matches!(error, InterpreterError::MalformedScript));
+        assert_eq!(error.script_error(), ScriptError::BadOpcode);
+    }
+
+    #[test]
+    fn random_bytes_round_trip() {
+        let mut rng = OsRng;
+        for _ in 0..2000 {
+            let mut bytes = vec![0; (rng.next_u32() % 300) as usize];
+            rng.fill_bytes(&mut bytes);
+
+            let script = Script::from_bytes(&bytes).unwrap();
+            assert_eq!(script.to_bytes(), bytes, "{}", hex::encode(&bytes));
+        }
+    }
+
+    #[test]
+    fn random_opcodes_round_trip() {
+        // Mostly opcodes with few pushes, so conditionals and OP_RETURN come up far more often than in uniform bytes
+        let mut rng = OsRng;
+        for _ in 0..2000 {
+            let bytes = (0..rng.next_u32() % 64)
+                .map(|_| match rng.next_u32() % 8 {
+                    0 => OpCodes::OP_IF as u8,
+                    1 => OpCodes::OP_ELSE as u8,
+                    2 => OpCodes::OP_ENDIF as u8,
+                    3 => OpCodes::OP_RETURN as u8,
+                    _ => 0x4f + (rng.next_u32() % 0xb1) as u8,
+                })
+                .collect::<Vec<u8>>();
+
+            let script = Script::from_bytes(&bytes).unwrap();
+            assert_eq!(script.to_bytes(), bytes, "{}", hex::encode(&bytes));
+        }
+    }
+}
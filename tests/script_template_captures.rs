@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod script_template_captures_tests {
+    use bsv::{MatchDataTypes, P2PKHAddress, PrivateKey, PublicKey, Script, ScriptTemplate, ScriptTemplateErrors};
+
+    const UNLOCKING_P2PKH: &str = "304402206173a490a5e62036e64f77f8c98db6c57f162a68147cb276bc61da589a114e27022053c19c60dbe7a97ce609631071ee5293c6e6bf4b859094c25a3385490f772c5541 0319a38fb498ff221b6e1b528b911c62f6ff2ac5023405c637859e4d7ff28f265d";
+
+    fn public_key(seed: u8) -> PublicKey {
+        PrivateKey::from_bytes(&[seed; 32]).unwrap().to_public_key().unwrap()
+    }
+
+    #[test]
+    fn captures_by_name() {
+        let script = Script::from_asm_string(&format!("{} OP_DUP OP_HASH160 08ed73ac2a3564dd1a431c61f7c2ce6b64e1fe80 OP_EQUALVERIFY OP_CHECKSIG", UNLOCKING_P2PKH)).unwrap();
+        let template = ScriptTemplate::from_asm_string("<sig:Signature> <signer:PublicKey> OP_DUP OP_HASH160 <receiver:PublicKeyHash> OP_EQUALVERIFY OP_CHECKSIG").unwrap();
+
+        let captures = script.captures(&template).unwrap();
+        let mut names = captures.names();
+        names.sort();
+        assert_eq!(names, vec!["receiver", "sig", "signer"]);
+
+        assert_eq!(captures.get("receiver").unwrap(), hex::decode("08ed73ac2a3564dd1a431c61f7c2ce6b64e1fe80").unwrap());
+        assert_eq!(captures.address("receiver").unwrap(), P2PKHAddress::from_pubkey_hash(&captures.get("receiver").unwrap()).unwrap());
+        assert_eq!(
+            captures.public_key("signer").unwrap().to_hex().unwrap(),
+            "0319a38fb498ff221b6e1b528b911c62f6ff2ac5023405c637859e4d7ff28f265d"
+        );
+        assert_eq!(captures.address("signer").unwrap(), P2PKHAddress::from_pubkey(&captures.public_key("signer").unwrap()).unwrap());
+        assert_eq!(captures.signature("sig").unwrap().to_der_hex(), UNLOCKING_P2PKH[..140]);
+        assert!(captures.get("missing").is_err());
+
+        // Named and unnamed data is still returned in order by matches
+        let matches = script.matches(&template).unwrap();
+        assert_eq!(
+            matches.iter().map(|(data_type, _)| data_type.clone()).collect::<Vec<_>>(),
+            vec![MatchDataTypes::Signature, MatchDataTypes::PublicKey, MatchDataTypes::PublicKeyHash]
+        );
+    }
+
+    #[test]
+    fn repeated_placeholders_capture_every_match() {
+        let keys = (1..=3).map(|seed| public_key(seed).to_hex().unwrap()).collect::<Vec<_>>();
+        let template = ScriptTemplate::from_asm_string("<required:Number> <keys:PublicKey>{1,20} <total:Number> OP_CHECKMULTISIG").unwrap();
+
+        let script = Script::from_asm_string(&format!("OP_2 {} OP_3 OP_CHECKMULTISIG", keys.join(" "))).unwrap();
+        let captures = script.captures(&template).unwrap();
+        assert_eq!(captures.int("required").unwrap(), 2);
+        assert_eq!(captures.int("total").unwrap(), 3);
+        assert_eq!(captures.public_keys("keys").unwrap().iter().map(|x| x.to_hex().unwrap()).collect::<Vec<_>>(), keys);
+
+        // Numbers are also read from pushes
+        let script = Script::from_asm_string(&format!("OP_1 {} 7f OP_CHECKMULTISIG", keys[0])).unwrap();
+        assert_eq!(script.captures(&template).unwrap().int("total").unwrap(), 127);
+
+        let script = Script::from_asm_string("OP_1 OP_1 OP_CHECKMULTISIG").unwrap();
+        assert!(matches!(script.captures(&template), Err(ScriptTemplateErrors::LengthsDiffer)));
+    }
+
+    #[test]
+    fn quantifiers_backtrack() {
+        let script = Script::from_asm_string("01 02 03 OP_DROP").unwrap();
+
+        let captures = script.captures(&ScriptTemplate::from_asm_string("<items:Data>* <last:Data> OP_DROP").unwrap()).unwrap();
+        assert_eq!(captures.get_all("items").unwrap(), vec![vec![1], vec![2]]);
+        assert_eq!(captures.get("last").unwrap(), vec![3]);
+
+        let captures = script.captures(&ScriptTemplate::from_asm_string("<first:Data>? <items:Data>+ OP_DROP <rest:Data>*").unwrap()).unwrap();
+        assert_eq!(captures.get_all("first").unwrap(), vec![vec![1]]);
+        assert_eq!(captures.get_all("items").unwrap().len(), 2);
+        assert!(captures.get_all("rest").unwrap().is_empty());
+        assert!(captures.get("rest").is_err());
+
+        assert!(script.is_match(&ScriptTemplate::from_asm_string("<items:OP_DATA=1>{3} OP_DROP").unwrap()));
+        assert!(!script.is_match(&ScriptTemplate::from_asm_string("<items:OP_DATA=1>{2} OP_DROP").unwrap()));
+        assert!(!script.is_match(&ScriptTemplate::from_asm_string("<items:OP_DATA=2>+ OP_DROP").unwrap()));
+    }
+
+    #[test]
+    fn quantifiers_do_not_backtrack_exponentially() {
+        // Every split of the pushes between the captures is tried before the final opcode fails
+        let script = Script::from_asm_string(&format!("{} OP_DROP", vec!["01"; 40].join(" "))).unwrap();
+        let template = ScriptTemplate::from_asm_string(&format!("{} OP_NIP", vec!["<items:Data>*"; 20].join(" "))).unwrap();
+
+        assert!(!script.is_match(&template));
+    }
+
+    #[test]
+    fn matches_inside_conditionals() {
+        let script = Script::from_asm_string("OP_IF 68656c6c6f OP_ELSE OP_0 OP_ENDIF").unwrap();
+        let template = ScriptTemplate::from_asm_string("OP_IF <message:Data> OP_ELSE <fallback:Number> OP_ENDIF").unwrap();
+
+        let captures = script.captures(&template).unwrap();
+        assert_eq!(captures.get("message").unwrap(), b"hello".to_vec());
+        assert_eq!(captures.int("fallback").unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_malformed_placeholders() {
+        for asm in ["<pkh>", "<:Data>", "<pkh:PublicKeyHash", "<items:Data>{3,1}", "<items:Data>{x}", "<items:Data>!", "<items:zz>"] {
+            assert!(ScriptTemplate::from_asm_string(asm).is_err(), "{}", asm);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+#[cfg(test)]
+mod transaction_builder_tests {
+    use std::collections::HashMap;
+
+    use bsv::{BSVErrors, Script, SigHash, Transaction, TransactionBuilder, TransactionVerifier, TxOut, Utxo};
+
+    use crate::common::{key_and_address, utxo};
+
+    fn verify_all(tx: &Transaction, utxos: &[Utxo]) -> bool {
+        let prev_outputs = (0..tx.get_ninputs())
+            .map(|i| {
+                let txin = tx.get_input(i).unwrap();
+                let utxo = utxos.iter().find(|x| x.vout == txin.get_vout()).unwrap();
+                (txin.get_outpoint_bytes(Some(true)), TxOut::new(utxo.satoshis, &utxo.locking_script))
+            })
+            .collect::<HashMap<_, _>>();
+        let verifier = TransactionVerifier::new(tx, &prev_outputs).unwrap();
+        (0..tx.get_ninputs()).all(|i| verifier.verify_input(i).unwrap().is_valid())
+    }
+
+    #[test]
+    fn signs_inputs_and_adds_change() {
+        let (key_a, address_a) = key_and_address(1);
+        let (key_b, address_b) = key_and_address(2);
+        let (_, receiver) = key_and_address(3);
+        let utxos = vec![utxo(&address_a, 0, 3_000), utxo(&address_b, 1, 4_000)];
+
+        let builder = TransactionBuilder::new()
+            .add_input(&utxos[0], &key_a)
+            .add_input(&utxos[1], &key_b)
+            .pay_to_address(&receiver, 5_000)
+            .unwrap()
+            .set_change_address(&address_a)
+            .unwrap()
+            .set_fee_rate(1_000);
+        let tx = builder.build().unwrap();
+
+        assert!(verify_all(&tx, &utxos));
+        assert_eq!(tx.get_noutputs(), 2);
+        let change = tx.get_output(1).unwrap();
+        assert_eq!(change.get_script_pub_key(), address_a.get_locking_script().unwrap());
+
+        // The fee covers the signed size and the estimate is never below it
+        let size = tx.get_size().unwrap();
+        let fee = 7_000 - 5_000 - change.get_satoshis();
+        assert!(builder.estimate_size().unwrap() >= size);
+        assert!(fee >= size as u64);
+        assert_eq!(fee, builder.estimate_fee().unwrap());
+        assert_eq!(builder.change().unwrap(), Some(change.get_satoshis()));
+    }
+
+    #[test]
+    fn leaves_dust_change_to_the_miner() {
+        let (private_key, address) = key_and_address(1);
+        let utxo = utxo(&address, 0, 1_000);
+        let builder = TransactionBuilder::new().add_input(&utxo, &private_key).set_change_address(&address).unwrap().set_fee_rate(0);
+
+        let tx = builder.clone().pay_to_address(&address, 900).unwrap().set_dust_limit(101).build().unwrap();
+        assert_eq!(tx.get_noutputs(), 1);
+        assert!(verify_all(&tx, std::slice::from_ref(&utxo)));
+
+        let tx = builder.pay_to_address(&address, 900).unwrap().set_dust_limit(100).build().unwrap();
+        assert_eq!(tx.get_noutputs(), 2);
+        assert_eq!(tx.get_output(1).unwrap().get_satoshis(), 100);
+    }
+
+    #[test]
+    fn uses_the_sighash_and_transaction_fields() {
+        let (private_key, address) = key_and_address(1);
+        let utxo = utxo(&address, 0, 10_000);
+        let tx = TransactionBuilder::new()
+            .set_version(2)
+            .set_nlocktime(700_000)
+            .set_sighash(SigHash::Legacy_InputOutputs)
+            .add_input(&utxo, &private_key)
+            .add_output(&TxOut::new(0, &Script::from_asm_string("OP_0 OP_RETURN 68656c6c6f").unwrap()))
+            .build()
+            .unwrap();
+
+        assert_eq!(tx.get_version(), 2);
+        assert_eq!(tx.get_n_locktime(), 700_000);
+        // Without a change address everything left is the fee
+        assert_eq!(tx.get_noutputs(), 1);
+        assert!(verify_all(&tx, &[utxo]));
+    }
+
+    #[test]
+    fn errors_on_insufficient_funds() {
+        let (private_key, address) = key_and_address(1);
+        let builder = TransactionBuilder::new().add_input(&utxo(&address, 0, 1_000), &private_key).pay_to_address(&address, 1_000).unwrap();
+
+        assert!(matches!(builder.build(), Err(BSVErrors::InsufficientFunds(1_000, required)) if required > 1_000));
+        assert!(builder.set_fee_rate(0).build().is_ok());
+    }
+}
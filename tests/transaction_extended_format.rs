@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod transaction_extended_format_tests {
+    use bsv::{BSVErrors, P2PKHAddress, PrivateKey, Script, Transaction, TransactionBuilder, TxIn, TxOut, Utxo, EXTENDED_FORMAT_MARKER};
+
+    #[test]
+    fn writes_the_marker_and_previous_outputs() {
+        let locking_script = Script::from_hex("76a914000102030405060708090a0b0c0d0e0f1011121388ac").unwrap();
+        let mut txin = TxIn::new(
+            &hex::decode("e4a2d0a3f5a8a0d7e8f9b0c1d2e3f40516273849506172839405162738495061").unwrap(),
+            1,
+            &Script::from_hex("51").unwrap(),
+            None,
+        );
+        txin.set_satoshis(1_000);
+        txin.set_locking_script(&locking_script);
+
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&txin);
+        tx.add_output(&TxOut::new(900, &Script::from_hex("6a").unwrap()));
+
+        let input = "6150493827160594837261504938271605f4e3d2c1b0f9e8d7a0a8f5a3d0a2e4010000000151ffffffff";
+        let previous_output = "e8030000000000001976a914000102030405060708090a0b0c0d0e0f1011121388ac";
+        let outputs = "018403000000000000016a00000000";
+        let standard = ["01000000", "01", input, outputs].concat();
+        let expected = ["01000000", "0000000000ef", "01", input, previous_output, outputs].concat();
+        assert_eq!(tx.to_hex().unwrap(), standard);
+        assert_eq!(tx.to_extended_hex().unwrap(), expected);
+
+        let parsed = Transaction::from_extended_hex(&expected).unwrap();
+        assert_eq!(parsed.to_hex().unwrap(), standard);
+        assert_eq!(parsed.get_input(0).unwrap().get_satoshis(), Some(1_000));
+        assert_eq!(parsed.get_input(0).unwrap().get_locking_script(), Some(locking_script));
+        assert_eq!(parsed.get_input(0).unwrap().get_prev_tx_id(None), txin.get_prev_tx_id(None));
+    }
+
+    #[test]
+    fn round_trips_signed_transactions() {
+        let private_key = PrivateKey::from_bytes(&[8; 32]).unwrap();
+        let address = P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap();
+        let locking_script = address.get_locking_script().unwrap();
+        let tx = TransactionBuilder::new()
+            .add_input(&Utxo::new(&[1; 32], 0, &locking_script, 5_000), &private_key)
+            .add_input(&Utxo::new(&[2; 32], 3, &locking_script, 7_000), &private_key)
+            .pay_to_address(&address, 10_000)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let extended = tx.to_extended_bytes().unwrap();
+        assert_eq!(extended[4..10], EXTENDED_FORMAT_MARKER);
+        assert_eq!(extended.len(), tx.get_size().unwrap() + 6 + 2 * (8 + 1 + 25));
+
+        let parsed = Transaction::from_extended_bytes(&extended).unwrap();
+        assert_eq!(parsed.get_id_hex().unwrap(), tx.get_id_hex().unwrap());
+        assert_eq!(parsed.satoshis_in(), Some(12_000));
+        assert_eq!(parsed.get_input(1).unwrap().get_locking_script(), Some(locking_script));
+        assert_eq!(parsed.to_extended_bytes().unwrap(), extended);
+    }
+
+    #[test]
+    fn rejects_missing_fields_and_markers() {
+        let mut tx = Transaction::new(1, 0);
+        tx.add_input(&TxIn::new(&[1; 32], 0, &Script::default(), None));
+        tx.add_output(&TxOut::new(0, &Script::default()));
+        assert!(matches!(tx.to_extended_bytes(), Err(BSVErrors::SerialiseExtendedTxIn(_))));
+
+        let mut txin = tx.get_input(0).unwrap();
+        txin.set_satoshis(100);
+        tx.set_input(0, &txin);
+        assert!(matches!(tx.to_extended_bytes(), Err(BSVErrors::SerialiseExtendedTxIn(_))));
+
+        txin.set_locking_script(&Script::default());
+        tx.set_input(0, &txin);
+        let extended = tx.to_extended_bytes().unwrap();
+        assert!(Transaction::from_extended_bytes(&extended).is_ok());
+
+        // Standard transactions have no marker
+        assert!(Transaction::from_extended_bytes(&tx.to_bytes().unwrap()).is_err());
+        assert!(Transaction::from_extended_bytes(&extended[..extended.len() - 20]).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod transaction_verifier_tests {
+    use std::collections::HashMap;
+
+    use bsv::{InterpreterError, P2PKHAddress, PrivateKey, ScriptConfig, SigHash, Transaction, TransactionVerifier, TxIn, TxOut};
+
+    const WIF: &str = "L2WAdy8C19GHNtZDSkbsVBJrBaF9XHpPLTgmnc2N5aGyguhJf7zh";
+
+    fn p2pkh_output(private_key: &PrivateKey, satoshis: u64) -> TxOut {
+        let address = P2PKHAddress::from_pubkey(&private_key.to_public_key().unwrap()).unwrap();
+        TxOut::new(satoshis, &address.get_locking_script().unwrap())
+    }
+
+    /// Spends `count` P2PKH outputs of the key, input `i` spends output `i` of a single funding transaction.
+    /// Returns the spending transaction and the spent outputs keyed by outpoint.
+    fn spend_p2pkh(private_key: &PrivateKey, count: u32) -> (Transaction, HashMap<Vec<u8>, TxOut>) {
+        let prev_tx_id = [7_u8; 32];
+        let mut tx = Transaction::new(2, 0);
+        let mut prev_outputs = HashMap::new();
+        for vout in 0..count {
+            let txin = TxIn::new(&prev_tx_id, vout, &Default::default(), None);
+            prev_outputs.insert(txin.get_outpoint_bytes(Some(true)), p2pkh_output(private_key, 1000 + vout as u64));
+            tx.add_input(&txin);
+        }
+        tx.add_output(&p2pkh_output(private_key, 500));
+
+        let public_key = private_key.to_public_key().unwrap();
+        let address = P2PKHAddress::from_pubkey(&public_key).unwrap();
+        for index in 0..count as usize {
+            let txin = tx.get_input(index).unwrap();
+            let prev_output = &prev_outputs[&txin.get_outpoint_bytes(Some(true))];
+            let signature = tx.sign(private_key, SigHash::InputsOutputs, index, &prev_output.get_script_pub_key(), prev_output.get_satoshis()).unwrap();
+
+            let mut txin = tx.get_input(index).unwrap();
+            txin.set_unlocking_script(&address.get_unlocking_script(&public_key, &signature).unwrap());
+            tx.set_input(index, &txin);
+        }
+
+        (tx, prev_outputs)
+    }
+
+    #[test]
+    fn verifies_every_input() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+        let (tx, prev_outputs) = spend_p2pkh(&private_key, 5);
+
+        let verifier = TransactionVerifier::new(&tx, &prev_outputs).unwrap();
+        let verdicts = verifier.verify();
+        assert_eq!(verdicts.len(), 5);
+        for (index, verdict) in verdicts.iter().enumerate() {
+            assert!(verdict.is_valid(), "{:?}", verdict.failure);
+            assert_eq!(verdict.input_index, index);
+        }
+    }
+
+    #[test]
+    fn threads_return_verdicts_in_input_order() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+        let (mut tx, prev_outputs) = spend_p2pkh(&private_key, 7);
+
+        // Swapping two unlocking scripts invalidates both inputs
+        let (mut first, mut last) = (tx.get_input(1).unwrap(), tx.get_input(5).unwrap());
+        let unlocking_script = first.get_unlocking_script();
+        first.set_unlocking_script(&last.get_unlocking_script());
+        last.set_unlocking_script(&unlocking_script);
+        tx.set_input(1, &first);
+        tx.set_input(5, &last);
+
+        let mut verifier = TransactionVerifier::new(&tx, &prev_outputs).unwrap();
+        verifier.set_threads(3);
+        let verdicts = verifier.verify();
+
+        assert_eq!(verdicts.iter().map(|x| x.input_index).collect::<Vec<_>>(), (0..7).collect::<Vec<_>>());
+        assert_eq!(verdicts.iter().map(|x| x.is_valid()).collect::<Vec<_>>(), vec![true, false, true, true, true, false, true]);
+        // Both inputs are signed by the same key, only the signature check fails
+        assert!(matches!(verdicts[1].failure.as_ref().unwrap().error, InterpreterError::EvalFalse));
+    }
+
+    #[test]
+    fn uses_the_given_config() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+        let (mut tx, prev_outputs) = spend_p2pkh(&private_key, 2);
+
+        let mut txin = tx.get_input(0).unwrap();
+        let mut unlocking_script = txin.get_unlocking_script().to_asm_string();
+        unlocking_script.push_str(" OP_NOP");
+        txin.set_unlocking_script(&bsv::Script::from_asm_string(&unlocking_script).unwrap());
+        tx.set_input(0, &txin);
+
+        let mut verifier = TransactionVerifier::new(&tx, &prev_outputs).unwrap();
+        assert!(verifier.verify_input(0).unwrap().is_valid());
+
+        verifier.set_config(&ScriptConfig::standard());
+        let failure = verifier.verify_input(0).unwrap().failure.unwrap();
+        assert!(matches!(failure.error, InterpreterError::SigPushOnly));
+        assert!(verifier.verify_input(1).unwrap().is_valid());
+        assert!(verifier.verify_input(2).is_err());
+    }
+
+    #[test]
+    fn missing_prev_output_is_an_error() {
+        let private_key = PrivateKey::from_wif(WIF).unwrap();
+        let (tx, mut prev_outputs) = spend_p2pkh(&private_key, 2);
+
+        prev_outputs.remove(&tx.get_input(1).unwrap().get_outpoint_bytes(Some(true)));
+        assert!(TransactionVerifier::new(&tx, &prev_outputs).is_err());
+    }
+}